@@ -1,9 +1,11 @@
 // main app
 
-use crate::modes::{Mode, ReadMode, Selected};
+use crate::modes::{
+    EntrySort, FeedSort, HealthSort, Mode, PaneLayout, PaneRatios, ReadMode, Selected,
+};
 use crate::util;
 use crate::util::sanitize_for_display;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use copypasta::{ClipboardContext, ClipboardProvider};
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::sync::{Arc, Mutex};
@@ -40,12 +42,14 @@ impl App {
         (error_flash_is_empty, bool),
         (feed_ids, Result<Vec<crate::rss::FeedId>>),
         (force_redraw, Result<()>),
-        (http_client, ureq::Agent),
+        (http_client, crate::http::HttpClient),
         (mode, Mode),
         (selected, Selected),
         (open_link_in_browser, Result<()>),
         (should_quit, bool),
         (refresh_feed, Result<()>),
+        (cancel_refresh, Result<()>),
+        (current_feed_id, Option<crate::rss::FeedId>),
         (subscribe_to_feed, Result<()>),
         (feed_subscription_input_is_empty, bool),
         (is_renaming, bool)
@@ -67,11 +71,27 @@ impl App {
         (show_combined_unread, Result<()>),
         (delete_feed, Result<()>),
         (toggle_help, Result<()>),
+        (toggle_diagnostics, Result<()>),
+        (show_feed_health, Result<()>),
+        (update_feed_health, Result<()>),
+        (cycle_sort, Result<()>),
+        (move_feed_up, Result<()>),
+        (move_feed_down, Result<()>),
+        (update_feeds, Result<()>),
+        (update_feed_summaries, Result<()>),
+        (toggle_health_mark, ()),
+        (show_trash, Result<()>),
+        (restore_from_trash, Result<()>),
+        (show_profiles, Result<()>),
+        (switch_profile, Result<()>),
+        (undo, Result<()>),
+        (mark_feed_read, Result<()>),
         (toggle_read, Result<()>),
         (toggle_read_mode, Result<()>),
         (update_current_feed_and_entries, Result<()>),
         (select_and_show_current_entry, Result<()>),
-        (cycle_theme, ())
+        (cycle_theme, ()),
+        (cycle_layout, ())
     ];
 
     pub fn new(
@@ -88,14 +108,9 @@ impl App {
         let mut inner = self.inner.lock().unwrap();
 
         terminal.draw(|f| {
-            let chunks = crate::ui::predraw(f, &inner);
+            let panes = crate::ui::predraw(f, &inner);
 
-            assert!(
-                chunks.len() >= 3,
-                "Layout must provide left, right, and command bar chunks"
-            );
-
-            let new_width = chunks[1].width;
+            let new_width = panes.article().width;
 
             if inner.entry_column_width != new_width {
                 inner.entry_column_width = new_width;
@@ -106,7 +121,13 @@ impl App {
                 }
             }
 
-            crate::ui::draw(f, chunks, &mut inner);
+            if panes.preview.is_some() && !matches!(inner.selected, Selected::Entry(_)) {
+                inner.update_preview().unwrap_or_else(|e| {
+                    inner.error_flash = vec![e];
+                })
+            }
+
+            crate::ui::draw(f, panes, &mut inner);
         })?;
 
         Ok(())
@@ -117,6 +138,42 @@ impl App {
         inner.should_quit = should_quit
     }
 
+    /// grows or shrinks the feeds column by `delta` percent
+    pub fn resize_feeds_pane(&self, delta: i16) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.pane_ratios.resize_feeds(delta);
+    }
+
+    /// grows or shrinks the entries list next to the preview by `delta` percent
+    pub fn resize_entries_pane(&self, delta: i16) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.layout == PaneLayout::ThreePane {
+            inner.pane_ratios.resize_entries(delta);
+        } else {
+            inner.flash = Some("Press 'L' for the three pane layout to resize entries".to_string());
+        }
+    }
+
+    pub fn on_click(&self, column: u16, row: u16) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.on_click(column, row)
+    }
+
+    pub fn on_scroll(&self, down: bool) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.on_scroll(down)
+    }
+
+    pub(crate) fn load_profile(&self, options: crate::ReadOptions) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.load_profile(options)
+    }
+
+    pub(crate) fn set_refresh_progress(&self, progress: Option<crate::io::RefreshProgress>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.refresh_progress = progress;
+    }
+
     pub fn set_flash(&self, flash: String) {
         let mut inner = self.inner.lock().unwrap();
         inner.flash = Some(flash)
@@ -125,6 +182,8 @@ impl App {
     pub fn push_error_flash(&self, e: anyhow::Error) {
         let mut inner = self.inner.lock().unwrap();
         inner.error_flash.push(e);
+        // errors come from the io thread, where nothing else would show them
+        let _ = inner.force_redraw();
     }
 
     pub fn set_mode(&self, mode: Mode) {
@@ -137,12 +196,6 @@ impl App {
         inner.feed_subscription_input.push(input);
     }
 
-    pub fn set_feeds(&self, feeds: Vec<crate::rss::Feed>) {
-        let mut inner = self.inner.lock().unwrap();
-        let feeds = feeds.into();
-        inner.feeds = feeds;
-    }
-
     pub(crate) fn refresh_feeds(&self) -> Result<()> {
         let feed_ids = self.feed_ids()?;
         let inner = self.inner.lock().unwrap();
@@ -152,6 +205,13 @@ impl App {
         Ok(())
     }
 
+    /// remember what is on screen for the next start
+    pub(crate) fn save_session(&self) -> Result<()> {
+        let inner = self.inner.lock().unwrap();
+        inner.remember_entry_scroll_position()?;
+        crate::session::save(&inner.conn, &inner.session_state())
+    }
+
     pub(crate) fn break_io_thread(&self) -> Result<()> {
         let inner = self.inner.lock().unwrap();
         inner.io_tx.send(crate::io::Action::Break)?;
@@ -161,8 +221,8 @@ impl App {
     pub(crate) fn has_entries(&self) -> bool {
         let inner = self.inner.lock().unwrap();
         match &inner.selected {
-            Selected::CombinedUnread => !inner.combined_entries.items.is_empty(),
-            _ => !inner.entries.items.is_empty(),
+            Selected::CombinedUnread => !inner.combined_entries.is_empty(),
+            _ => !inner.entries.is_empty(),
         }
     }
 
@@ -211,6 +271,52 @@ impl App {
         }
     }
 
+    /// write every feed the health dashboard doesn't consider healthy to an OPML file for review
+    pub(crate) fn export_unhealthy_feeds(&self) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+
+        let feeds = inner
+            .feed_health
+            .items
+            .iter()
+            .filter(|health| health.status() != crate::rss::FeedHealthStatus::Healthy)
+            .map(|health| health.feed.clone())
+            .collect::<Vec<_>>();
+
+        if feeds.is_empty() {
+            inner.flash = Some("All feeds are healthy, nothing to export".to_string());
+            return Ok(());
+        }
+
+        let export_dir = inner
+            .database_path
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("database path has no parent directory"))?;
+
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+        let export_path = export_dir.join(format!("rss_tui_unhealthy_{}.opml", timestamp));
+
+        let tags = crate::rss::get_feed_tags(&inner.conn)?;
+        let selectors = crate::rss::get_scrape_selectors(&inner.conn)?;
+
+        match crate::opml::write_feeds(
+            feeds,
+            &tags,
+            &selectors,
+            "rss-tui Unhealthy Feeds",
+            &export_path,
+        ) {
+            Ok(()) => {
+                inner.flash = Some(format!("Exported unhealthy feeds to {:?}", export_path));
+            }
+            Err(e) => {
+                inner.error_flash.push(e);
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn email_article(&self) -> Result<()> {
         let mut inner = self.inner.lock().unwrap();
         inner.email_article()
@@ -236,11 +342,6 @@ impl App {
         inner.set_read_mode(mode)
     }
 
-    pub(crate) fn refresh_single_feed_activity(&self, feed_id: crate::rss::FeedId) -> Result<()> {
-        let mut inner = self.inner.lock().unwrap();
-        inner.refresh_single_feed_activity(feed_id)
-    }
-
     pub(crate) fn set_feed_error(&self, feed_id: crate::rss::FeedId, error: anyhow::Error) {
         let mut inner = self.inner.lock().unwrap();
         inner.set_feed_error(feed_id, error);
@@ -259,23 +360,73 @@ impl App {
 }
 
 const SPARKLINE_DAYS: u32 = 14;
+const REFRESH_LOG_DISPLAY_LIMIT: usize = 20;
+/// how many actions `u` can take back
+const UNDO_LIMIT: usize = 100;
+/// lines one notch of the mouse wheel scrolls an entry by
+const MOUSE_SCROLL_LINES: usize = 3;
+
+/// A destructive action that can be taken back with `u` for the rest of the session
+#[derive(Debug)]
+pub enum Undo {
+    /// feeds moved to the trash, with their titles
+    Delete(Vec<(crate::rss::FeedId, String)>),
+    /// an entry marked read or unread, with its previous `read_at`
+    ToggleRead(crate::rss::EntryId, Option<chrono::DateTime<chrono::Utc>>),
+    /// every unread entry of a feed marked read
+    MarkFeedRead {
+        feed_title: String,
+        read_state: Vec<(crate::rss::EntryId, Option<chrono::DateTime<chrono::Utc>>)>,
+    },
+    Rename {
+        feed_id: crate::rss::FeedId,
+        previous_title: Option<String>,
+    },
+}
 
 #[derive(Debug)]
 pub struct AppImpl {
     // database stuff
     pub conn: rusqlite::Connection,
     pub database_path: std::path::PathBuf,
+    /// shared with other readers, so `rss-tui db restore` knows the database is open
+    _reader_lock: std::fs::File,
     // network stuff
-    pub http_client: ureq::Agent,
+    pub http_client: crate::http::HttpClient,
     // feed stuff
     pub current_feed: Option<crate::rss::Feed>,
     pub feeds: util::StatefulList<crate::rss::Feed>,
-    pub feed_activity_cache: std::collections::HashMap<crate::rss::FeedId, Vec<u64>>,
+    pub feed_sort: FeedSort,
+    /// unread counts and activity of every feed, reloaded by `update_feed_summaries`
+    /// after refreshes, read state changes and deletes rather than on every draw
+    pub feed_summaries: std::collections::HashMap<crate::rss::FeedId, crate::rss::FeedSummary>,
     pub feed_errors: std::collections::HashMap<crate::rss::FeedId, anyhow::Error>,
+    /// set while feeds are refreshing in the background
+    pub refresh_progress: Option<crate::io::RefreshProgress>,
+    /// recent refresh attempts of the current feed, loaded while diagnostics are shown
+    pub refresh_log: Vec<crate::rss::RefreshAttempt>,
+    // feed health dashboard
+    pub feed_health: util::StatefulList<crate::rss::FeedHealth>,
+    pub health_sort: HealthSort,
+    /// feeds marked for bulk unsubscribe
+    pub health_marked: std::collections::HashSet<crate::rss::FeedId>,
+    pub pending_bulk_deletion: bool,
+    // trash
+    pub trash: util::StatefulList<crate::rss::TrashedFeed>,
+    // profiles
+    /// what the app was started with, for the profile it is showing
+    options: crate::ReadOptions,
+    /// every profile in config.toml, loaded when the switcher is shown
+    pub profiles: util::StatefulList<String>,
+    pub undo_stack: Vec<Undo>,
     // entry stuff
     pub current_entry_meta: Option<crate::rss::EntryMetadata>,
-    pub entries: util::StatefulList<crate::rss::EntryMetadata>,
-    pub combined_entries: util::StatefulList<(String, crate::rss::EntryMetadata)>,
+    /// only the entries around the selection are loaded, see `select_entry`
+    pub entries: util::PagedList<crate::rss::EntryMetadata>,
+    /// the order of the current feed's entries
+    pub entry_sort: EntrySort,
+    pub combined_entries: util::PagedList<(String, crate::rss::EntryMetadata)>,
+    pub combined_sort: EntrySort,
     /// true when current Entry was opened from CombinedUnread (back goes to combined)
     pub came_from_combined_unread: bool,
     pub entry_selection_position: usize,
@@ -284,12 +435,22 @@ pub struct AppImpl {
     pub entry_lines_len: usize,
     pub entry_lines_rendered_len: u16,
     pub entry_column_width: u16,
+    /// the highlighted entry's text in the three pane layout,
+    /// rendered for the entry and width in `preview_rendered_for`
+    pub preview_text: String,
+    pub preview_rendered_for: Option<(crate::rss::EntryId, u16)>,
+    /// where things were drawn, for mapping mouse clicks back to them
+    pub click_targets: crate::ui::ClickTargets,
+    // layout
+    pub layout: PaneLayout,
+    pub pane_ratios: PaneRatios,
     // modes
     pub should_quit: bool,
     pub selected: Selected,
     pub mode: Mode,
     pub read_mode: ReadMode,
     pub show_help: bool,
+    pub show_diagnostics: bool,
     pub current_theme: crate::ui::Theme,
     // misc
     pub error_flash: Vec<anyhow::Error>,
@@ -308,17 +469,22 @@ impl AppImpl {
         event_tx: std::sync::mpsc::Sender<crate::Event<crossterm::event::KeyEvent>>,
         io_tx: std::sync::mpsc::Sender<crate::io::Action>,
     ) -> Result<AppImpl> {
+        let reader_lock = crate::db::try_lock_shared(&options.database_path, "reader")?
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "{:?} is being restored, try again once that is done",
+                    options.database_path
+                )
+            })?;
         let mut conn = rusqlite::Connection::open(&options.database_path)?;
 
-        let http_client = ureq::AgentBuilder::new()
-            .timeout_read(options.network_timeout)
-            // use crate version as user agent
-            .user_agent(&format!("rss-tui/{}", env!("CARGO_PKG_VERSION")))
-            .build();
+        let http_client =
+            crate::http::HttpClient::new(options.network_timeout, &options.config.network)?;
+        let network_warnings = http_client.warnings().to_vec();
 
-        crate::rss::initialize_db(&mut conn)?;
+        // the database of a profile switched to from the reader may still need migrating
+        let migrated = crate::migrations::migrate(&mut conn)?;
         let feeds: util::StatefulList<crate::rss::Feed> = vec![].into();
-        let entries: util::StatefulList<crate::rss::EntryMetadata> = vec![].into();
         // default to having nothing selected,
         // as it's possible we are starting for the first time,
         // with an empty feeds db
@@ -327,30 +493,52 @@ impl AppImpl {
 
         let is_wsl = wsl::is_wsl();
 
-        // initialize theme from env var or default to hacker
-        let current_theme = match std::env::var("RSS_TUI_THEME").as_ref().map(|s| s.as_str()) {
-            Ok("boring") => crate::ui::Theme::Boring,
-            Ok("ubuntu") => crate::ui::Theme::Ubuntu,
-            _ => crate::ui::Theme::Hacker,
+        // initialize theme from env var or default to hacker.
+        // the theme of the saved session wins over the default, but not over the env var
+        let theme_from_env = match std::env::var("RSS_TUI_THEME").as_ref().map(|s| s.as_str()) {
+            Ok("boring") => Some(crate::ui::Theme::Boring),
+            Ok("ubuntu") => Some(crate::ui::Theme::Ubuntu),
+            Ok("hacker") => Some(crate::ui::Theme::Hacker),
+            _ => None,
         };
+        let current_theme = theme_from_env.unwrap_or(crate::ui::Theme::Hacker);
 
         let mut app = AppImpl {
             conn,
             database_path: options.database_path.clone(),
+            _reader_lock: reader_lock,
             http_client,
             should_quit: false,
             error_flash: vec![],
             feeds,
-            feed_activity_cache: std::collections::HashMap::new(),
+            feed_sort: FeedSort::Title,
+            feed_summaries: std::collections::HashMap::new(),
             feed_errors: std::collections::HashMap::new(),
-            entries,
-            combined_entries: vec![].into(),
+            refresh_progress: None,
+            refresh_log: vec![],
+            feed_health: vec![].into(),
+            health_sort: HealthSort::Status,
+            health_marked: std::collections::HashSet::new(),
+            pending_bulk_deletion: false,
+            trash: vec![].into(),
+            options: options.clone(),
+            profiles: vec![].into(),
+            undo_stack: vec![],
+            entries: util::PagedList::default(),
+            entry_sort: EntrySort::NewestFirst,
+            combined_entries: util::PagedList::default(),
+            combined_sort: EntrySort::NewestFirst,
             came_from_combined_unread: false,
             selected,
             entry_scroll_position: 0,
             entry_lines_len: 0,
             entry_lines_rendered_len: 0,
             entry_column_width: 0,
+            preview_text: String::new(),
+            preview_rendered_for: None,
+            click_targets: crate::ui::ClickTargets::default(),
+            layout: PaneLayout::TwoPane,
+            pane_ratios: PaneRatios::default(),
             current_entry_meta: None,
             current_entry_text: String::new(),
             current_feed: initial_current_feed,
@@ -358,6 +546,7 @@ impl AppImpl {
             mode: Mode::Normal,
             read_mode: ReadMode::ShowUnread,
             show_help: true,
+            show_diagnostics: false,
             entry_selection_position: 0,
             flash: None,
             pending_deletion: None,
@@ -368,8 +557,23 @@ impl AppImpl {
             current_theme,
         };
 
+        // feeds deleted long enough ago are gone for good
+        let purged = crate::rss::purge_expired_trash(
+            &mut app.conn,
+            app.options.config.trash.retention_days,
+        )?;
+        app.forget_credentials(&purged)?;
+
         app.update_feeds()?;
-        app.update_current_feed_and_entries()?;
+        app.update_feed_summaries()?;
+        if let Err(e) = app.update_current_feed_and_entries() {
+            app.error_flash.push(e);
+        }
+
+        // feeds that were broken last time are still broken until they refresh successfully
+        for (feed_id, error) in crate::rss::get_failing_feeds(&app.conn)? {
+            app.feed_errors.insert(feed_id, anyhow::anyhow!(error));
+        }
 
         // we default to having Selected::None,
         // so if there are actually feeds, select them
@@ -377,119 +581,862 @@ impl AppImpl {
             app.select_feeds()
         }
 
-        Ok(app)
+        if !options.fresh {
+            // a session that can't be restored is not worth refusing to start over
+            if let Err(e) = app.restore_session(theme_from_env.is_none()) {
+                app.error_flash
+                    .push(e.context("Unable to restore the last session, pass --fresh to skip it"));
+            }
+        }
+
+        if let Some(backup_path) = migrated {
+            app.flash = Some(format!(
+                "Migrated the database, the old one is backed up at {backup_path:?}"
+            ));
+        }
+
+        app.error_flash
+            .extend(network_warnings.into_iter().map(anyhow::Error::msg));
+
+        Ok(app)
+    }
+
+    /// the name of the profile being shown
+    pub fn profile(&self) -> &str {
+        &self.options.profile
+    }
+
+    /// how many days deleted feeds are kept in the trash
+    pub fn trash_retention_days(&self) -> u32 {
+        self.options.config.trash.retention_days
+    }
+
+    /// switch to the list of profiles
+    pub fn show_profiles(&mut self) -> Result<()> {
+        self.cancel_pending_deletion();
+        // read again, so profiles added since starting show up
+        let profiles = crate::config::Config::load()?.profile_names();
+        let current = profiles
+            .iter()
+            .position(|profile| *profile == self.options.profile);
+        self.profiles = profiles.into();
+        self.profiles.state.select(current.or(Some(0)));
+        self.selected = Selected::Profiles;
+        Ok(())
+    }
+
+    /// Hands the selected profile to the io thread, which stops the refresh workers
+    /// for the old database before the app starts over on the new one, see `load_profile`.
+    /// Anything that goes wrong is flashed, and the current profile stays
+    pub fn switch_profile(&mut self) -> Result<()> {
+        let Some(profile) = self
+            .profiles
+            .state
+            .selected()
+            .and_then(|idx| self.profiles.items.get(idx))
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        // back to the feeds, where errors are shown, until the new profile takes over
+        self.selected = Selected::Feeds;
+        if profile == self.options.profile {
+            return Ok(());
+        }
+
+        let options = match self.options.with_profile(&profile) {
+            Ok(options) => options,
+            Err(e) => {
+                self.error_flash
+                    .push(e.context(format!("Unable to switch to profile {profile}")));
+                return Ok(());
+            }
+        };
+
+        // so the profile being left opens where it was, next time
+        if let Err(e) = self
+            .remember_entry_scroll_position()
+            .and_then(|()| crate::session::save(&self.conn, &self.session_state()))
+        {
+            self.error_flash.push(e);
+        }
+
+        self.flash = Some(format!("Switching to profile {profile}..."));
+        self.io_tx
+            .send(crate::io::Action::SwitchProfile(Box::new(options)))?;
+
+        Ok(())
+    }
+
+    /// Starts over on the profile's database, where its last session left off.
+    /// Called by the io thread once nothing is refreshing the old database anymore
+    fn load_profile(&mut self, options: crate::ReadOptions) -> Result<()> {
+        let profile = options.profile.clone();
+        let mut app = AppImpl::new(options, self.event_tx.clone(), self.io_tx.clone())
+            .with_context(|| format!("Unable to switch to profile {profile}"))?;
+        app.flash = Some(match app.flash.take() {
+            Some(migrated) => format!("Switched to profile {profile}. {migrated}"),
+            None => format!("Switched to profile {profile}"),
+        });
+        *self = app;
+        Ok(())
+    }
+
+    fn session_state(&self) -> crate::session::SessionState {
+        use crate::session::SessionView;
+
+        let (view, entry_id) = match &self.selected {
+            Selected::Entry(entry) => (SessionView::Entry, Some(entry.id)),
+            Selected::Entries => (
+                SessionView::Entries,
+                self.current_entry_meta.as_ref().map(|entry| entry.id),
+            ),
+            Selected::CombinedUnread => (
+                SessionView::CombinedUnread,
+                self.current_entry_meta.as_ref().map(|entry| entry.id),
+            ),
+            Selected::Feeds
+            | Selected::Health
+            | Selected::Trash
+            | Selected::Profiles
+            | Selected::None => (SessionView::Feeds, None),
+        };
+
+        crate::session::SessionState {
+            feed_id: self.current_feed.as_ref().map(|feed| feed.id),
+            entry_id,
+            view,
+            came_from_combined_unread: self.came_from_combined_unread,
+            read_mode: self.read_mode.clone(),
+            feed_sort: self.feed_sort,
+            combined_sort: self.combined_sort,
+            theme: self.current_theme,
+            show_help: self.show_help,
+            layout: self.layout,
+            pane_ratios: self.pane_ratios,
+        }
+    }
+
+    /// puts the app back where the last session left it,
+    /// as far as the feeds and entries it was on still exist
+    fn restore_session(&mut self, restore_theme: bool) -> Result<()> {
+        use crate::session::SessionView;
+
+        let Some(state) = crate::session::load(&self.conn)? else {
+            return Ok(());
+        };
+
+        self.show_help = state.show_help;
+        self.layout = state.layout;
+        self.pane_ratios = state.pane_ratios;
+        self.read_mode = state.read_mode;
+        self.combined_sort = state.combined_sort;
+        if self.feed_sort != state.feed_sort {
+            self.feed_sort = state.feed_sort;
+            self.update_feeds()?;
+        }
+        if restore_theme {
+            self.current_theme = state.theme;
+        }
+
+        if self.feeds.items.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(feed_id) = state.feed_id {
+            self.select_feed(feed_id);
+        }
+        self.update_current_feed_and_entries()?;
+
+        let Some(entry_id) = state.entry_id else {
+            if state.view == SessionView::CombinedUnread {
+                self.show_combined_unread()?;
+            }
+            return Ok(());
+        };
+
+        let from_combined = state.view == SessionView::CombinedUnread
+            || (state.view == SessionView::Entry && state.came_from_combined_unread);
+
+        if from_combined {
+            self.show_combined_unread()?;
+            let Some(idx) = crate::rss::EntryQuery::combined_unread(self.combined_sort)
+                .position(&self.conn, entry_id)?
+            else {
+                return Ok(());
+            };
+            self.select_combined_entry(Some(idx))?;
+        } else {
+            let Some(idx) = self.entry_position(entry_id)? else {
+                return Ok(());
+            };
+            self.entry_selection_position = idx;
+            self.select_entry(Some(idx))?;
+            self.selected = Selected::Entries;
+        }
+        self.update_current_entry_meta()?;
+
+        if state.view == SessionView::Entry {
+            self.select_and_show_current_entry()?;
+        }
+
+        Ok(())
+    }
+
+    /// keep the scroll position of the open entry, to come back to it later
+    fn remember_entry_scroll_position(&self) -> Result<()> {
+        if let Selected::Entry(entry) = &self.selected {
+            crate::rss::set_entry_scroll_position(
+                &self.conn,
+                entry.id,
+                self.entry_scroll_position,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_feed(&mut self) -> Result<()> {
+        if matches!(self.selected, Selected::Health) {
+            return self.delete_marked_feeds();
+        }
+
+        if matches!(self.selected, Selected::Trash) {
+            return self.purge_from_trash();
+        }
+
+        // handle deletion in editing mode (backward compatibility)
+        if matches!(self.selected, Selected::Feeds) && matches!(self.mode(), Mode::Editing) {
+            let feed_id = self.selected_feed_id();
+            self.perform_feed_deletion(feed_id)?;
+            return Ok(());
+        }
+
+        // handle deletion in normal mode with confirmation
+        if matches!(self.selected, Selected::Feeds) && matches!(self.mode(), Mode::Normal) {
+            let feed_id = self.selected_feed_id();
+
+            // if already pending deletion for this feed, confirm and delete
+            if self.pending_deletion == Some(feed_id) {
+                self.perform_feed_deletion(feed_id)?;
+                self.pending_deletion = None;
+                return Ok(());
+            }
+
+            // otherwise, set pending deletion and show confirmation message
+            self.pending_deletion = Some(feed_id);
+            let feed_title = self
+                .feeds
+                .items
+                .iter()
+                .find(|f| f.id == feed_id)
+                .and_then(|f| f.title.as_ref())
+                .map(|t| t.as_str())
+                .unwrap_or("this feed");
+            self.flash = Some(format!(
+                "Delete '{}'? Hit 'd' confirm, 'n' to cancel",
+                feed_title
+            ));
+            return Ok(());
+        }
+
+        Ok(())
+    }
+
+    fn perform_feed_deletion(&mut self, feed_id: crate::rss::FeedId) -> Result<()> {
+        let feed_title = self.remove_feed(feed_id)?;
+
+        self.flash = Some(format!("Moved '{}' to the trash, 'u' to undo", feed_title));
+        self.push_undo(Undo::Delete(vec![(feed_id, feed_title)]));
+
+        // update
+        self.update_current_feed_and_entries()?;
+        self.pending_deletion = None;
+
+        Ok(())
+    }
+
+    /// unsubscribes from the marked feeds, or the selected one if none are marked,
+    /// after asking for confirmation
+    fn delete_marked_feeds(&mut self) -> Result<()> {
+        let feed_ids = if self.health_marked.is_empty() {
+            self.selected_health_feed_id().into_iter().collect()
+        } else {
+            self.health_marked.iter().copied().collect::<Vec<_>>()
+        };
+
+        if feed_ids.is_empty() {
+            return Ok(());
+        }
+
+        if !self.pending_bulk_deletion {
+            self.pending_bulk_deletion = true;
+            self.flash = Some(format!(
+                "Unsubscribe from {} feeds? Hit 'd' confirm, 'n' to cancel",
+                feed_ids.len()
+            ));
+            return Ok(());
+        }
+
+        let mut deleted = vec![];
+        for feed_id in feed_ids {
+            deleted.push((feed_id, self.remove_feed(feed_id)?));
+        }
+
+        self.pending_bulk_deletion = false;
+        self.health_marked.clear();
+        self.flash = Some(format!(
+            "Moved {} feeds to the trash, 'u' to undo",
+            deleted.len()
+        ));
+        self.push_undo(Undo::Delete(deleted));
+
+        self.update_current_feed_and_entries()?;
+        self.update_feed_health()?;
+
+        Ok(())
+    }
+
+    /// moves the feed to the trash and out of app state, returning its title
+    fn remove_feed(&mut self, feed_id: crate::rss::FeedId) -> Result<String> {
+        crate::rss::trash_feed(&mut self.conn, feed_id)?;
+        self.update_feed_summaries()?;
+
+        // remove the feed in app state
+        let feeds_len = self.feeds.items.len();
+        let mut feed_title = "Feed".to_string();
+
+        for i in 0..feeds_len {
+            if self.feeds.items[i].id == feed_id {
+                feed_title = self.feeds.items[i]
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| "Feed".to_string());
+                self.feeds.items.remove(i);
+
+                if i == feeds_len - 1 {
+                    self.feeds.previous();
+                }
+
+                break;
+            }
+        }
+
+        // remove the entries from the feed in app state
+        if self
+            .current_feed
+            .as_ref()
+            .is_some_and(|feed| feed.id == feed_id)
+        {
+            self.entries = util::PagedList::default();
+        }
+        self.feed_errors.remove(&feed_id);
+
+        Ok(feed_title)
+    }
+
+    /// don't leave the credentials of purged feeds lying around
+    fn forget_credentials(&self, feed_urls: &[String]) -> Result<()> {
+        if feed_urls.is_empty() {
+            return Ok(());
+        }
+
+        let mut credentials =
+            crate::credentials::CredentialStore::for_database(&self.database_path)?;
+        let mut removed = false;
+        for feed_url in feed_urls {
+            removed |= credentials.remove(feed_url);
+        }
+        if removed {
+            credentials.save()?;
+        }
+
+        Ok(())
+    }
+
+    fn push_undo(&mut self, undo: Undo) {
+        self.undo_stack.push(undo);
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// takes back the most recent delete, mark-read or rename
+    pub fn undo(&mut self) -> Result<()> {
+        self.cancel_pending_deletion();
+
+        let Some(undo) = self.undo_stack.pop() else {
+            self.flash = Some("Nothing to undo".to_string());
+            return Ok(());
+        };
+
+        match undo {
+            Undo::Delete(feeds) => {
+                for (feed_id, _) in &feeds {
+                    crate::rss::restore_feed(&mut self.conn, *feed_id)?;
+                }
+                self.update_feeds()?;
+                if let Some((feed_id, _)) = feeds.first() {
+                    self.select_feed(*feed_id);
+                }
+                if matches!(self.selected, Selected::None) {
+                    self.selected = Selected::Feeds;
+                }
+                self.flash = Some(match feeds.as_slice() {
+                    [(_, title)] => format!("Restored '{title}'"),
+                    _ => format!("Restored {} feeds", feeds.len()),
+                });
+            }
+            Undo::ToggleRead(entry_id, read_at) => {
+                crate::rss::restore_read_state(&mut self.conn, &[(entry_id, read_at)])?;
+                self.flash = Some(
+                    if read_at.is_some() {
+                        "Marked entry read again"
+                    } else {
+                        "Marked entry unread again"
+                    }
+                    .to_string(),
+                );
+            }
+            Undo::MarkFeedRead {
+                feed_title,
+                read_state,
+            } => {
+                crate::rss::restore_read_state(&mut self.conn, &read_state)?;
+                self.flash = Some(format!(
+                    "Marked {} entries in '{}' unread again",
+                    read_state.len(),
+                    feed_title
+                ));
+            }
+            Undo::Rename {
+                feed_id,
+                previous_title,
+            } => {
+                crate::rss::update_feed_title(&mut self.conn, feed_id, previous_title.clone())?;
+                self.update_feeds()?;
+                self.select_feed(feed_id);
+                self.flash = Some(format!(
+                    "Renamed back to '{}'",
+                    previous_title.as_deref().unwrap_or("No title")
+                ));
+            }
+        }
+
+        self.update_current_feed_and_entries()?;
+        self.update_current_entry_meta()?;
+        if matches!(self.selected, Selected::CombinedUnread) {
+            self.update_combined_entries(0)?;
+            self.update_current_entry_meta()?;
+        }
+        self.update_feed_summaries()?;
+        self.update_feed_health()?;
+        self.update_trash()?;
+
+        Ok(())
+    }
+
+    /// marks every unread entry of the current feed as read
+    pub fn mark_feed_read(&mut self) -> Result<()> {
+        let Some(feed) = self.current_feed.clone() else {
+            return Ok(());
+        };
+
+        let read_state = crate::rss::mark_feed_read(&mut self.conn, feed.id)?;
+        self.update_feed_summaries()?;
+
+        if read_state.is_empty() {
+            self.flash = Some("No unread entries".to_string());
+            return Ok(());
+        }
+
+        let feed_title = feed.title.unwrap_or_else(|| "Feed".to_string());
+        self.flash = Some(format!(
+            "Marked {} entries in '{}' read, 'u' to undo",
+            read_state.len(),
+            feed_title
+        ));
+        self.push_undo(Undo::MarkFeedRead {
+            feed_title,
+            read_state,
+        });
+
+        self.update_current_entries()?;
+        self.update_entry_selection_position();
+        if self.entries.is_empty() && matches!(self.selected, Selected::Entries) {
+            self.selected = Selected::Feeds;
+        }
+        self.update_current_entry_meta()?;
+
+        Ok(())
+    }
+
+    /// switch to the trash
+    pub fn show_trash(&mut self) -> Result<()> {
+        self.cancel_pending_deletion();
+        self.selected = Selected::Trash;
+        self.update_trash()?;
+        if self.trash.items.is_empty() {
+            self.trash.unselect();
+        } else {
+            self.trash.reset();
+        }
+        Ok(())
+    }
+
+    /// reloads the trash, if it is shown
+    fn update_trash(&mut self) -> Result<()> {
+        if !matches!(self.selected, Selected::Trash) {
+            return Ok(());
+        }
+
+        let selected_idx = self.trash.state.selected();
+        self.trash = crate::rss::get_trashed_feeds(&self.conn)?.into();
+        match selected_idx {
+            _ if self.trash.items.is_empty() => self.trash.unselect(),
+            Some(idx) => self
+                .trash
+                .state
+                .select(Some(idx.min(self.trash.items.len() - 1))),
+            None => self.trash.reset(),
+        }
+
+        Ok(())
+    }
+
+    fn selected_trashed_feed(&self) -> Option<&crate::rss::TrashedFeed> {
+        self.trash
+            .state
+            .selected()
+            .and_then(|idx| self.trash.items.get(idx))
+    }
+
+    /// puts the selected feed back in the feeds list, entries and read state included
+    pub fn restore_from_trash(&mut self) -> Result<()> {
+        let Some(trashed) = self.selected_trashed_feed() else {
+            return Ok(());
+        };
+        let feed_id = trashed.feed.id;
+        let feed_title = trashed
+            .feed
+            .title
+            .clone()
+            .unwrap_or_else(|| "Feed".to_string());
+
+        self.cancel_pending_deletion();
+        crate::rss::restore_feed(&mut self.conn, feed_id)?;
+        self.update_feed_summaries()?;
+
+        self.update_feeds()?;
+        self.select_feed(feed_id);
+        self.update_current_feed_and_entries()?;
+        self.update_trash()?;
+
+        // deleting it again should not be undone by an old undo of its deletion
+        self.undo_stack.retain(|undo| match undo {
+            Undo::Delete(feeds) => !feeds.iter().any(|(id, _)| *id == feed_id),
+            _ => true,
+        });
+
+        self.flash = Some(format!("Restored '{}'", feed_title));
+
+        Ok(())
+    }
+
+    /// permanently deletes the selected feed in the trash, after asking for confirmation
+    fn purge_from_trash(&mut self) -> Result<()> {
+        let Some(trashed) = self.selected_trashed_feed() else {
+            return Ok(());
+        };
+        let feed_id = trashed.feed.id;
+        let feed_url = trashed.feed.feed_link.clone();
+        let feed_title = trashed
+            .feed
+            .title
+            .clone()
+            .unwrap_or_else(|| "this feed".to_string());
+
+        if self.pending_deletion != Some(feed_id) {
+            self.pending_deletion = Some(feed_id);
+            self.flash = Some(format!(
+                "Permanently delete '{}'? This can't be undone. Hit 'd' confirm, 'n' to cancel",
+                feed_title
+            ));
+            return Ok(());
+        }
+
+        crate::rss::delete_feed(&mut self.conn, feed_id)?;
+        self.forget_credentials(&feed_url.into_iter().collect::<Vec<_>>())?;
+
+        self.undo_stack.retain(|undo| match undo {
+            Undo::Delete(feeds) => !feeds.iter().any(|(id, _)| *id == feed_id),
+            _ => true,
+        });
+
+        self.pending_deletion = None;
+        self.flash = Some(format!("Permanently deleted '{}'", feed_title));
+        self.update_trash()?;
+
+        Ok(())
+    }
+
+    fn select_feed(&mut self, feed_id: crate::rss::FeedId) {
+        if let Some(idx) = self.feeds.items.iter().position(|feed| feed.id == feed_id) {
+            self.feeds.state.select(Some(idx));
+        }
+    }
+
+    pub fn cancel_pending_deletion(&mut self) {
+        self.pending_deletion = None;
+        self.pending_bulk_deletion = false;
+        self.flash = None;
+    }
+
+    /// switch to the feed health dashboard
+    pub fn show_feed_health(&mut self) -> Result<()> {
+        self.cancel_pending_deletion();
+        self.selected = Selected::Health;
+        self.health_marked.clear();
+        self.update_feed_health()?;
+        if self.feed_health.items.is_empty() {
+            self.feed_health.unselect();
+        } else {
+            self.feed_health.reset();
+        }
+        self.sync_current_feed_to_health_selection()
+    }
+
+    /// reloads the dashboard, if it is shown, keeping the selected feed selected
+    pub fn update_feed_health(&mut self) -> Result<()> {
+        if !matches!(self.selected, Selected::Health) {
+            return Ok(());
+        }
+
+        let selected_feed_id = self.selected_health_feed_id();
+
+        self.feed_health = crate::rss::get_feeds_health(&self.conn)?.into();
+        self.sort_feed_health();
+
+        let feed_ids = self
+            .feed_health
+            .items
+            .iter()
+            .map(|health| health.feed.id)
+            .collect::<std::collections::HashSet<_>>();
+        self.health_marked
+            .retain(|feed_id| feed_ids.contains(feed_id));
+
+        let idx = self
+            .feed_health
+            .items
+            .iter()
+            .position(|health| Some(health.feed.id) == selected_feed_id)
+            .unwrap_or(0);
+        if self.feed_health.items.is_empty() {
+            self.feed_health.unselect();
+        } else {
+            self.feed_health.state.select(Some(idx));
+        }
+
+        Ok(())
+    }
+
+    fn sort_feed_health(&mut self) {
+        let title = |health: &crate::rss::FeedHealth| {
+            health
+                .feed
+                .title
+                .as_deref()
+                .unwrap_or_default()
+                .to_lowercase()
+        };
+        // feeds without any entries sort as the stalest
+        let days =
+            |health: &crate::rss::FeedHealth| health.days_since_last_entry().unwrap_or(i64::MAX);
+
+        match self.health_sort {
+            HealthSort::Status => self.feed_health.items.sort_by(|a, b| {
+                a.status()
+                    .cmp(&b.status())
+                    .then_with(|| b.consecutive_errors.cmp(&a.consecutive_errors))
+                    .then_with(|| days(b).cmp(&days(a)))
+            }),
+            HealthSort::ConsecutiveErrors => self
+                .feed_health
+                .items
+                .sort_by_key(|health| std::cmp::Reverse(health.consecutive_errors)),
+            HealthSort::DaysSinceLastEntry => self
+                .feed_health
+                .items
+                .sort_by_key(|health| std::cmp::Reverse(days(health))),
+            HealthSort::Frequency => self
+                .feed_health
+                .items
+                .sort_by(|a, b| a.entries_per_month.total_cmp(&b.entries_per_month)),
+            HealthSort::Title => self.feed_health.items.sort_by_key(title),
+        }
+    }
+
+    /// changes the order of whatever list is shown:
+    /// the feeds, the current feed's entries, the combined unread view or the health dashboard
+    pub fn cycle_sort(&mut self) -> Result<()> {
+        match &self.selected {
+            Selected::Feeds => {
+                let feed_id = self.current_feed.as_ref().map(|feed| feed.id);
+                self.feed_sort = self.feed_sort.next();
+                self.update_feeds()?;
+                if let Some(feed_id) = feed_id {
+                    self.select_feed(feed_id);
+                }
+                self.update_current_feed_and_entries()?;
+                self.flash = Some(format!("Feeds sorted by {}", self.feed_sort.name()));
+            }
+            Selected::Entries | Selected::Entry(_) => {
+                let Some(feed) = &self.current_feed else {
+                    return Ok(());
+                };
+                let feed_id = feed.id;
+                let entry_id = self.current_entry_meta.as_ref().map(|entry| entry.id);
+
+                self.entry_sort = self.entry_sort.next(false);
+                crate::rss::set_feed_entry_sort(&self.conn, feed_id, self.entry_sort)?;
+                self.update_current_entries()?;
+
+                if let Some(entry_id) = entry_id
+                    && let Some(idx) = self.entry_position(entry_id)?
+                {
+                    self.entry_selection_position = idx;
+                    self.select_entry(Some(idx))?;
+                }
+                self.update_current_entry_meta()?;
+                self.flash = Some(format!("Entries sorted {}", self.entry_sort.name()));
+            }
+            Selected::CombinedUnread => {
+                self.combined_sort = self.combined_sort.next(true);
+                self.show_combined_unread()?;
+                self.flash = Some(format!("Entries sorted by {}", self.combined_sort.name()));
+            }
+            Selected::Health => self.cycle_health_sort(),
+            Selected::Trash | Selected::Profiles | Selected::None => (),
+        }
+
+        Ok(())
+    }
+
+    pub fn move_feed_up(&mut self) -> Result<()> {
+        self.move_feed(-1)
     }
 
-    pub fn delete_feed(&mut self) -> Result<()> {
-        // handle deletion in editing mode (backward compatibility)
-        if matches!(self.selected, Selected::Feeds) && matches!(self.mode(), Mode::Editing) {
-            let feed_id = self.selected_feed_id();
-            self.perform_feed_deletion(feed_id)?;
+    pub fn move_feed_down(&mut self) -> Result<()> {
+        self.move_feed(1)
+    }
+
+    /// moves the selected feed in the manual order
+    fn move_feed(&mut self, offset: isize) -> Result<()> {
+        if !matches!(self.selected, Selected::Feeds) {
             return Ok(());
         }
 
-        // handle deletion in normal mode with confirmation
-        if matches!(self.selected, Selected::Feeds) && matches!(self.mode(), Mode::Normal) {
-            let feed_id = self.selected_feed_id();
-
-            // if already pending deletion for this feed, confirm and delete
-            if self.pending_deletion == Some(feed_id) {
-                self.perform_feed_deletion(feed_id)?;
-                self.pending_deletion = None;
-                return Ok(());
-            }
-
-            // otherwise, set pending deletion and show confirmation message
-            self.pending_deletion = Some(feed_id);
-            let feed_title = self
-                .feeds
-                .items
-                .iter()
-                .find(|f| f.id == feed_id)
-                .and_then(|f| f.title.as_ref())
-                .map(|t| t.as_str())
-                .unwrap_or("this feed");
-            self.flash = Some(format!(
-                "Delete '{}'? Hit 'd' confirm, 'n' to cancel",
-                feed_title
-            ));
+        if self.feed_sort != FeedSort::Manual {
+            self.flash = Some("Press 's' until feeds are sorted manually to move them".to_string());
             return Ok(());
         }
 
+        let feed_id = self.selected_feed_id();
+        crate::rss::move_feed(&mut self.conn, feed_id, offset)?;
+        self.update_feeds()?;
+        self.select_feed(feed_id);
+        self.update_current_feed_and_entries()?;
+
         Ok(())
     }
 
-    fn perform_feed_deletion(&mut self, feed_id: crate::rss::FeedId) -> Result<()> {
-        crate::rss::delete_feed(&mut self.conn, feed_id)?;
+    pub fn cycle_health_sort(&mut self) {
+        let selected_feed_id = self.selected_health_feed_id();
 
-        // remove the feed in app state
-        let feeds_len = self.feeds.items.len();
-        let mut feed_title = "Feed".to_string();
+        self.health_sort = self.health_sort.next();
+        self.sort_feed_health();
 
-        for i in 0..feeds_len {
-            if self.feeds.items[i].id == feed_id {
-                feed_title = self.feeds.items[i]
-                    .title
-                    .clone()
-                    .unwrap_or_else(|| "Feed".to_string());
-                self.feeds.items.remove(i);
+        if let Some(idx) = self
+            .feed_health
+            .items
+            .iter()
+            .position(|health| Some(health.feed.id) == selected_feed_id)
+        {
+            self.feed_health.state.select(Some(idx));
+        }
 
-                if i == feeds_len - 1 {
-                    self.feeds.previous();
-                }
+        self.flash = Some(format!("Sorted by {}", self.health_sort.name()));
+    }
 
-                break;
+    /// mark or unmark the selected feed for bulk unsubscribe, then move to the next one
+    pub fn toggle_health_mark(&mut self) {
+        if let Some(feed_id) = self.selected_health_feed_id() {
+            if !self.health_marked.remove(&feed_id) {
+                self.health_marked.insert(feed_id);
             }
+            self.pending_bulk_deletion = false;
+            if !self.feed_health.items.is_empty() {
+                self.feed_health.next();
+            }
+            // only fails on database errors, which the next draw will surface anyway
+            let _ = self.sync_current_feed_to_health_selection();
         }
-
-        self.flash = Some(format!("Deleted '{}'", feed_title));
-
-        // remove the entries from the feed in app state
-        self.entries.items.retain(|entry| entry.feed_id != feed_id);
-
-        // update
-        self.update_current_feed_and_entries()?;
-        self.pending_deletion = None;
-
-        Ok(())
     }
 
-    pub fn cancel_pending_deletion(&mut self) {
-        self.pending_deletion = None;
-        self.flash = None;
+    fn selected_health_feed_id(&self) -> Option<crate::rss::FeedId> {
+        self.feed_health
+            .state
+            .selected()
+            .and_then(|idx| self.feed_health.items.get(idx))
+            .map(|health| health.feed.id)
     }
 
-    pub fn update_feeds(&mut self) -> Result<()> {
-        let feeds = crate::rss::get_feeds(&self.conn)?.into();
-        self.feeds = feeds;
-        self.refresh_feed_activity()?;
+    /// keep the info panel (and the feeds list) on the feed selected in the dashboard
+    fn sync_current_feed_to_health_selection(&mut self) -> Result<()> {
+        if let Some(feed_id) = self.selected_health_feed_id()
+            && let Some(idx) = self.feeds.items.iter().position(|feed| feed.id == feed_id)
+        {
+            self.feeds.state.select(Some(idx));
+            self.update_current_feed_and_entries()?;
+        }
         Ok(())
     }
 
-    pub fn refresh_feed_activity(&mut self) -> Result<()> {
-        self.feed_activity_cache.clear();
-        for feed in &self.feeds.items {
-            if let Ok(activity) = crate::rss::get_feed_activity(&self.conn, feed.id, SPARKLINE_DAYS)
-            {
-                self.feed_activity_cache.insert(feed.id, activity);
-            }
-        }
+    pub fn update_feeds(&mut self) -> Result<()> {
+        let feeds = crate::rss::get_feeds_sorted(&self.conn, self.feed_sort)?.into();
+        self.feeds = feeds;
         Ok(())
     }
 
-    pub fn refresh_single_feed_activity(&mut self, feed_id: crate::rss::FeedId) -> Result<()> {
-        if let Ok(activity) = crate::rss::get_feed_activity(&self.conn, feed_id, SPARKLINE_DAYS) {
-            self.feed_activity_cache.insert(feed_id, activity);
-        }
+    pub fn update_feed_summaries(&mut self) -> Result<()> {
+        self.feed_summaries = crate::rss::get_feed_summaries(&self.conn, SPARKLINE_DAYS)?;
         Ok(())
     }
 
     pub fn update_current_feed_and_entries(&mut self) -> Result<()> {
         self.update_current_feed()?;
         self.update_current_entries()?;
+        self.update_refresh_log()?;
+        Ok(())
+    }
+
+    fn update_refresh_log(&mut self) -> Result<()> {
+        self.refresh_log = match &self.current_feed {
+            Some(feed) if self.show_diagnostics => {
+                crate::rss::get_refresh_log(&self.conn, feed.id, REFRESH_LOG_DISPLAY_LIMIT)?
+            }
+            _ => vec![],
+        };
         Ok(())
     }
 
+    /// show the refresh history and last error of the selected feed instead of its entries
+    pub fn toggle_diagnostics(&mut self) -> Result<()> {
+        self.show_diagnostics = !self.show_diagnostics;
+        self.update_refresh_log()
+    }
+
     fn update_current_feed(&mut self) -> Result<()> {
         self.current_feed = if self.feeds.items.is_empty() {
             self.selected = Selected::None;
@@ -503,6 +1450,7 @@ impl AppImpl {
                 }
             };
             let feed_id = self.feeds.items[selected_idx].id;
+            self.entry_sort = crate::rss::get_feed_entry_sort(&self.conn, feed_id)?;
             Some(crate::rss::get_feed(&self.conn, feed_id)?)
         };
 
@@ -510,63 +1458,94 @@ impl AppImpl {
     }
 
     fn update_current_entries(&mut self) -> Result<()> {
-        let entries = if let Some(feed) = &self.current_feed {
-            crate::rss::get_entries_metas(&self.conn, &self.read_mode, feed.id)?
-                .into_iter()
-                .collect::<Vec<_>>()
-                .into()
-        } else {
-            vec![].into()
+        self.entries = match self.entries_query() {
+            Some(query) => util::PagedList::new(query.count(&self.conn)?),
+            None => util::PagedList::default(),
         };
 
-        self.entries = entries;
+        let last = self.entries.len().saturating_sub(1);
+        self.select_entry(Some(self.entry_selection_position.min(last)))
+    }
 
-        if self.entry_selection_position < self.entries.items.len() {
-            self.entries
-                .state
-                .select(Some(self.entry_selection_position))
-        } else {
-            match self.entries.items.len().checked_sub(1) {
-                Some(n) => self.entries.state.select(Some(n)),
-                None => self.entries.reset(),
+    /// what the entries list shows, unless there is no feed to show the entries of
+    fn entries_query(&self) -> Option<crate::rss::EntryQuery> {
+        self.current_feed.as_ref().map(|feed| {
+            crate::rss::EntryQuery::feed(feed.id, self.read_mode.clone(), self.entry_sort)
+        })
+    }
+
+    /// where the entry is in the entries list, if it is in it
+    fn entry_position(&self, entry_id: crate::rss::EntryId) -> Result<Option<usize>> {
+        match self.entries_query() {
+            Some(query) => query.position(&self.conn, entry_id),
+            None => Ok(None),
+        }
+    }
+
+    /// selects the entry at `idx` of the entries list, loading the entries around it
+    fn select_entry(&mut self, idx: Option<usize>) -> Result<()> {
+        let Some(query) = self.entries_query() else {
+            self.entries.state.select(idx);
+            return Ok(());
+        };
+        let conn = &self.conn;
+        self.entries.select(idx, |request| {
+            Ok(query
+                .page(conn, request.map(|entry| entry.id))?
+                .into_iter()
+                .map(|(_, entry)| entry)
+                .collect())
+        })
+    }
+
+    /// selects the entry at `idx` of the combined unread view, loading the entries around it
+    fn select_combined_entry(&mut self, idx: Option<usize>) -> Result<()> {
+        let query = crate::rss::EntryQuery::combined_unread(self.combined_sort);
+        let conn = &self.conn;
+        self.combined_entries.select(idx, |request| {
+            query.page(conn, request.map(|(_, entry)| entry.id))
+        })
+    }
+
+    /// reloads the combined unread view, selecting `idx`, or the last entry if there are fewer
+    fn update_combined_entries(&mut self, idx: usize) -> Result<()> {
+        let len = crate::rss::EntryQuery::combined_unread(self.combined_sort).count(&self.conn)?;
+        self.combined_entries = util::PagedList::new(len);
+        match len.checked_sub(1) {
+            Some(last) => self.select_combined_entry(Some(idx.min(last))),
+            None => {
+                self.combined_entries.unselect();
+                Ok(())
             }
         }
-        Ok(())
     }
 
     fn update_entry_selection_position(&mut self) {
-        if self.entries.items.is_empty() {
+        if self.entries.is_empty() {
             self.entry_selection_position = 0
-        } else if self.entry_selection_position > self.entries.items.len() - 1 {
-            self.entry_selection_position = self.entries.items.len() - 1
+        } else if self.entry_selection_position > self.entries.len() - 1 {
+            self.entry_selection_position = self.entries.len() - 1
         };
     }
 
     fn get_selected_entry_content(&self) -> Option<Result<crate::rss::EntryContent>> {
-        self.entries.state.selected().and_then(|selected_idx| {
-            self.entries
-                .items
-                .get(selected_idx)
-                .map(|item| item.id)
-                .map(|entry_id| crate::rss::get_entry_content(&self.conn, entry_id))
-        })
+        self.entries
+            .selected_item()
+            .map(|item| item.id)
+            .map(|entry_id| crate::rss::get_entry_content(&self.conn, entry_id))
     }
 
     fn get_selected_entry_meta(&self) -> Option<Result<crate::rss::EntryMetadata>> {
         match &self.selected {
             Selected::CombinedUnread => self
                 .combined_entries
-                .state
-                .selected()
-                .and_then(|i| self.combined_entries.items.get(i))
+                .selected_item()
                 .map(|(_, e)| Ok(e.clone())),
-            _ => self.entries.state.selected().and_then(|selected_idx| {
-                self.entries
-                    .items
-                    .get(selected_idx)
-                    .map(|item| item.id)
-                    .map(|entry_id| crate::rss::get_entry_meta(&self.conn, entry_id))
-            }),
+            _ => self
+                .entries
+                .selected_item()
+                .map(|item| item.id)
+                .map(|entry_id| crate::rss::get_entry_meta(&self.conn, entry_id)),
         }
     }
 
@@ -601,6 +1580,42 @@ impl AppImpl {
         }
     }
 
+    /// the entry shown in the preview pane: the highlighted one,
+    /// or the newest of the current feed while the feeds list is focused
+    pub fn preview_entry(&self) -> Option<&crate::rss::EntryMetadata> {
+        match &self.selected {
+            Selected::Entry(entry) => Some(entry),
+            Selected::Entries | Selected::CombinedUnread => self.current_entry_meta.as_ref(),
+            Selected::Feeds => self.entries.get(0),
+            Selected::Health | Selected::Trash | Selected::Profiles | Selected::None => None,
+        }
+    }
+
+    /// renders the preview entry, unless it was already rendered at this width
+    fn update_preview(&mut self) -> Result<()> {
+        let Some(entry_id) = self.preview_entry().map(|entry| entry.id) else {
+            self.preview_text = String::new();
+            self.preview_rendered_for = None;
+            return Ok(());
+        };
+
+        let rendered_for = Some((entry_id, self.entry_column_width));
+        if self.preview_rendered_for == rendered_for {
+            return Ok(());
+        }
+
+        let entry = crate::rss::get_entry_content(&self.conn, entry_id)?;
+        (self.preview_text, _) = render_entry_text(&entry, self.entry_column_width)?;
+        self.preview_rendered_for = rendered_for;
+
+        Ok(())
+    }
+
+    pub fn cycle_layout(&mut self) {
+        self.layout = self.layout.next();
+        self.flash = Some(format!("Layout: {}", self.layout.name()));
+    }
+
     pub(crate) fn select_and_show_current_entry(&mut self) -> Result<()> {
         if matches!(self.selected, Selected::CombinedUnread) {
             return self.select_and_show_combined_entry();
@@ -609,35 +1624,15 @@ impl AppImpl {
             let entry_meta = entry_meta.clone();
 
             if let Some(entry) = self.get_selected_entry_content() {
-                let entry = entry?;
-                let empty_string = String::from("No content or description tag provided.");
-
-                // try content tag first,
-                // if there is not content tag,
-                // go to description tag,
-                // if no description tag,
-                // use empty string.
-                // TODO figure out what to actually do if there are neither
-                let entry_html = entry
-                    .content
-                    .as_ref()
-                    .or(entry.description.as_ref())
-                    .or(Some(&empty_string));
-
-                // minimum is 1
-                let line_length = if self.entry_column_width >= 5 {
-                    self.entry_column_width - 2
-                } else {
-                    1
-                };
+                let (text, lines_len) = render_entry_text(&entry?, self.entry_column_width)?;
+                self.entry_lines_len = lines_len;
+                self.current_entry_text = text;
+            }
 
-                if let Some(html) = entry_html {
-                    let text = html2text::from_read(html.as_bytes(), line_length.into())?;
-                    self.entry_lines_len = text.matches('\n').count();
-                    self.current_entry_text = sanitize_for_display(&text);
-                } else {
-                    self.current_entry_text = String::new();
-                }
+            // redraws after a resize come through here too, they keep the current position
+            if !matches!(self.selected, Selected::Entry(_)) {
+                self.entry_scroll_position =
+                    crate::rss::get_entry_scroll_position(&self.conn, entry_meta.id)?;
             }
 
             self.came_from_combined_unread = false;
@@ -653,6 +1648,12 @@ impl AppImpl {
         Ok(())
     }
 
+    /// stops the running refresh after the feeds that are being fetched
+    pub(crate) fn cancel_refresh(&self) -> Result<()> {
+        self.io_tx.send(crate::io::Action::CancelRefresh)?;
+        Ok(())
+    }
+
     pub(crate) fn subscribe_to_feed(&self) -> Result<()> {
         let feed_subscription_input = self.feed_subscription_input();
         self.io_tx
@@ -693,7 +1694,22 @@ impl AppImpl {
                 return Ok(());
             }
 
-            crate::rss::update_feed_title(&mut self.conn, feed_id, new_title.trim().to_string())?;
+            let previous_title = self
+                .feeds
+                .items
+                .iter()
+                .find(|f| f.id == feed_id)
+                .and_then(|f| f.title.clone());
+
+            crate::rss::update_feed_title(
+                &mut self.conn,
+                feed_id,
+                Some(new_title.trim().to_string()),
+            )?;
+            self.push_undo(Undo::Rename {
+                feed_id,
+                previous_title,
+            });
 
             // update the feed in app state
             if let Some(feed) = self.feeds.items.iter_mut().find(|f| f.id == feed_id) {
@@ -765,14 +1781,8 @@ impl AppImpl {
 
     /// switch to combined unread view: all unread entries from all feeds as "[feed-name]: title"
     pub fn show_combined_unread(&mut self) -> Result<()> {
-        let items = crate::rss::get_all_unread_entries_with_feed_name(&self.conn)?;
-        self.combined_entries = items.into();
+        self.update_combined_entries(0)?;
         self.selected = Selected::CombinedUnread;
-        if !self.combined_entries.items.is_empty() {
-            self.combined_entries.reset();
-        } else {
-            self.combined_entries.unselect();
-        }
         self.update_current_entry_meta()?;
         Ok(())
     }
@@ -781,46 +1791,26 @@ impl AppImpl {
     fn select_and_show_combined_entry(&mut self) -> Result<()> {
         let (_, entry_meta) = self
             .combined_entries
-            .state
-            .selected()
-            .and_then(|i| self.combined_entries.items.get(i))
+            .selected_item()
             .cloned()
             .ok_or_else(|| anyhow::anyhow!("no combined entry selected"))?;
         let feed = crate::rss::get_feed(&self.conn, entry_meta.feed_id)?;
         self.current_feed = Some(feed);
         self.update_current_entries()?;
         let idx = self
-            .entries
-            .items
-            .iter()
-            .position(|e| e.id == entry_meta.id)
+            .entry_position(entry_meta.id)?
             .ok_or_else(|| anyhow::anyhow!("entry not in feed"))?;
         self.entry_selection_position = idx;
-        self.entries.state.select(Some(idx));
+        self.select_entry(Some(idx))?;
         self.update_current_entry_meta()?;
         self.current_entry_meta = Some(entry_meta.clone());
         if let Some(entry) = self.get_selected_entry_content() {
-            let entry = entry?;
-            let empty_string = String::from("No content or description tag provided.");
-            let entry_html = entry
-                .content
-                .as_ref()
-                .or(entry.description.as_ref())
-                .or(Some(&empty_string));
-            let line_length = if self.entry_column_width >= 5 {
-                self.entry_column_width - 2
-            } else {
-                1
-            };
-            if let Some(html) = entry_html {
-                let text = html2text::from_read(html.as_bytes(), line_length.into())?;
-                self.entry_lines_len = text.matches('\n').count();
-                self.current_entry_text = sanitize_for_display(&text);
-            } else {
-                self.current_entry_text = String::new();
-            }
+            let (text, lines_len) = render_entry_text(&entry?, self.entry_column_width)?;
+            self.entry_lines_len = lines_len;
+            self.current_entry_text = text;
         }
-        self.entry_scroll_position = 0;
+        self.entry_scroll_position =
+            crate::rss::get_entry_scroll_position(&self.conn, entry_meta.id)?;
         self.came_from_combined_unread = true;
         self.selected = Selected::Entry(entry_meta);
         Ok(())
@@ -830,6 +1820,10 @@ impl AppImpl {
         self.selected.clone()
     }
 
+    pub fn current_feed_id(&self) -> Option<crate::rss::FeedId> {
+        self.current_feed.as_ref().map(|feed| feed.id)
+    }
+
     pub fn selected_feed_id(&self) -> crate::rss::FeedId {
         let selected_idx = self.feeds.state.selected().unwrap();
         self.feeds.items[selected_idx].id
@@ -841,9 +1835,26 @@ impl AppImpl {
     }
 
     pub fn toggle_read(&mut self) -> Result<()> {
+        let toggled = match &self.selected {
+            Selected::Entry(entry) => Some((entry.id, entry.read_at)),
+            Selected::Entries | Selected::CombinedUnread => self
+                .current_entry_meta
+                .as_ref()
+                .map(|entry| (entry.id, entry.read_at)),
+            _ => None,
+        };
+        // only undoable once the toggle is in the database
+        let push_undo = |app: &mut Self| {
+            if let Some((entry_id, read_at)) = toggled {
+                app.push_undo(Undo::ToggleRead(entry_id, read_at));
+            }
+        };
+
         match &self.selected {
             Selected::Entry(entry) => {
+                self.remember_entry_scroll_position()?;
                 entry.toggle_read(&self.conn)?;
+                push_undo(self);
                 self.selected = Selected::Entries;
                 self.update_current_entries()?;
                 self.update_current_entry_meta()?;
@@ -852,6 +1863,7 @@ impl AppImpl {
             Selected::Entries => {
                 if let Some(entry_meta) = &self.current_entry_meta {
                     entry_meta.toggle_read(&self.conn)?;
+                    push_undo(self);
                     self.update_current_entries()?;
                     self.update_current_entry_meta()?;
                     self.update_entry_selection_position();
@@ -860,28 +1872,28 @@ impl AppImpl {
             Selected::CombinedUnread => {
                 if let Some(entry_meta) = &self.current_entry_meta {
                     entry_meta.toggle_read(&self.conn)?;
-                    let items = crate::rss::get_all_unread_entries_with_feed_name(&self.conn)?;
-                    self.combined_entries = items.into();
-                    if !self.combined_entries.items.is_empty() {
-                        let idx = self.combined_entries.state.selected().unwrap_or(0);
-                        let idx = idx.min(self.combined_entries.items.len().saturating_sub(1));
-                        self.combined_entries.state.select(Some(idx));
-                    } else {
-                        self.combined_entries.unselect();
+                    push_undo(self);
+                    let idx = self.combined_entries.state.selected().unwrap_or(0);
+                    self.update_combined_entries(idx)?;
+                    if self.combined_entries.is_empty() {
                         self.selected = Selected::Feeds;
                     }
                     self.update_current_entry_meta()?;
                 }
             }
-            Selected::Feeds => (),
+            Selected::Feeds | Selected::Health | Selected::Trash | Selected::Profiles => (),
             Selected::None => (),
         }
 
+        if toggled.is_some() {
+            self.update_feed_summaries()?;
+        }
+
         Ok(())
     }
 
-    pub fn http_client(&self) -> ureq::Agent {
-        // this is cheap because it only clones a struct containing two Arcs
+    pub fn http_client(&self) -> crate::http::HttpClient {
+        // this is cheap because the agents inside are only Arcs
         self.http_client.clone()
     }
 
@@ -905,8 +1917,8 @@ impl AppImpl {
         }
         self.update_current_entries()?;
 
-        if !self.entries.items.is_empty() {
-            self.entries.reset();
+        if !self.entries.is_empty() {
+            self.select_entry(Some(0))?;
         } else {
             self.entries.unselect();
         }
@@ -927,8 +1939,8 @@ impl AppImpl {
             self.read_mode = mode;
             self.update_current_entries()?;
 
-            if !self.entries.items.is_empty() {
-                self.entries.reset();
+            if !self.entries.is_empty() {
+                self.select_entry(Some(0))?;
             } else {
                 self.entries.unselect();
             }
@@ -946,17 +1958,33 @@ impl AppImpl {
                 .and_then(|feed| feed.link.as_deref().or(feed.feed_link.as_deref())),
             Selected::Entries => self
                 .entries
-                .items
                 .get(self.entry_selection_position)
                 .and_then(|entry| entry.link.as_deref()),
             Selected::CombinedUnread => self
                 .combined_entries
-                .state
-                .selected()
-                .and_then(|i| self.combined_entries.items.get(i))
+                .selected_item()
                 .and_then(|(_, e)| e.link.as_deref()),
             Selected::Entry(e) => e.link.as_deref(),
-            Selected::None => None,
+            Selected::Health => self
+                .feed_health
+                .state
+                .selected()
+                .and_then(|i| self.feed_health.items.get(i))
+                .and_then(|health| {
+                    health
+                        .feed
+                        .link
+                        .as_deref()
+                        .or(health.feed.feed_link.as_deref())
+                }),
+            Selected::Trash => self.selected_trashed_feed().and_then(|trashed| {
+                trashed
+                    .feed
+                    .link
+                    .as_deref()
+                    .or(trashed.feed.feed_link.as_deref())
+            }),
+            Selected::Profiles | Selected::None => None,
         }
     }
 
@@ -1061,18 +2089,17 @@ impl AppImpl {
             Selected::CombinedUnread => {
                 self.selected = Selected::Feeds;
             }
+            Selected::Health | Selected::Trash | Selected::Profiles => {
+                self.cancel_pending_deletion();
+                self.selected = Selected::Feeds;
+            }
             Selected::Entry(_) => {
+                self.remember_entry_scroll_position()?;
                 self.entry_scroll_position = 0;
                 self.current_entry_text = String::new();
                 if self.came_from_combined_unread {
                     self.came_from_combined_unread = false;
-                    let items = crate::rss::get_all_unread_entries_with_feed_name(&self.conn)?;
-                    self.combined_entries = items.into();
-                    if !self.combined_entries.items.is_empty() {
-                        self.combined_entries.reset();
-                    } else {
-                        self.combined_entries.unselect();
-                    }
+                    self.update_combined_entries(0)?;
                     self.update_current_entry_meta()?;
                     self.selected = Selected::CombinedUnread;
                 } else {
@@ -1104,18 +2131,31 @@ impl AppImpl {
                 self.update_current_feed_and_entries()?;
             }
             Selected::Entries => {
-                if !self.entries.items.is_empty() {
-                    self.entries.previous();
+                if !self.entries.is_empty() {
+                    self.select_entry(self.entries.previous_index())?;
                     self.entry_selection_position = self.entries.state.selected().unwrap();
                     self.update_current_entry_meta()?;
                 }
             }
             Selected::CombinedUnread => {
-                if !self.combined_entries.items.is_empty() {
-                    self.combined_entries.previous();
+                if !self.combined_entries.is_empty() {
+                    self.select_combined_entry(self.combined_entries.previous_index())?;
                     self.update_current_entry_meta()?;
                 }
             }
+            Selected::Health => {
+                if !self.feed_health.items.is_empty() {
+                    self.feed_health.previous();
+                    self.sync_current_feed_to_health_selection()?;
+                }
+            }
+            Selected::Trash => {
+                if !self.trash.items.is_empty() {
+                    self.cancel_pending_deletion();
+                    self.trash.previous();
+                }
+            }
+            Selected::Profiles => self.profiles.previous(),
             Selected::Entry(_) => {
                 if let Some(n) = self.entry_scroll_position.checked_sub(1) {
                     self.entry_scroll_position = n
@@ -1130,18 +2170,26 @@ impl AppImpl {
     pub fn on_right(&mut self) -> Result<()> {
         match self.selected {
             Selected::Feeds => {
-                if !self.entries.items.is_empty() {
+                if !self.entries.is_empty() {
                     self.cancel_pending_deletion();
                     self.cancel_rename_feed();
                     self.selected = Selected::Entries;
-                    self.entries.reset();
+                    self.select_entry(Some(0))?;
                     self.update_current_entry_meta()?;
                 }
                 Ok(())
             }
             Selected::Entries => self.select_and_show_current_entry(),
             Selected::CombinedUnread => self.select_and_show_current_entry(),
-            Selected::Entry(_) => Ok(()),
+            // jump to the selected feed in the feeds list
+            Selected::Health => {
+                self.cancel_pending_deletion();
+                self.sync_current_feed_to_health_selection()?;
+                self.selected = Selected::Feeds;
+                Ok(())
+            }
+            Selected::Profiles => self.switch_profile(),
+            Selected::Entry(_) | Selected::Trash => Ok(()),
             Selected::None => Ok(()),
         }
     }
@@ -1165,18 +2213,31 @@ impl AppImpl {
                 self.update_current_feed_and_entries()?;
             }
             Selected::Entries => {
-                if !self.entries.items.is_empty() {
-                    self.entries.next();
+                if !self.entries.is_empty() {
+                    self.select_entry(self.entries.next_index())?;
                     self.entry_selection_position = self.entries.state.selected().unwrap();
                     self.update_current_entry_meta()?;
                 }
             }
             Selected::CombinedUnread => {
-                if !self.combined_entries.items.is_empty() {
-                    self.combined_entries.next();
+                if !self.combined_entries.is_empty() {
+                    self.select_combined_entry(self.combined_entries.next_index())?;
                     self.update_current_entry_meta()?;
                 }
             }
+            Selected::Health => {
+                if !self.feed_health.items.is_empty() {
+                    self.feed_health.next();
+                    self.sync_current_feed_to_health_selection()?;
+                }
+            }
+            Selected::Trash => {
+                if !self.trash.items.is_empty() {
+                    self.cancel_pending_deletion();
+                    self.trash.next();
+                }
+            }
+            Selected::Profiles => self.profiles.next(),
             Selected::Entry(_) => {
                 if let Some(n) = self.entry_scroll_position.checked_add(1) {
                     self.entry_scroll_position = n
@@ -1188,12 +2249,174 @@ impl AppImpl {
         Ok(())
     }
 
+    /// selects the feed, entry or tab under the mouse, or opens the link under it in an article.
+    /// Clicking what is already selected opens it, like pressing enter
+    pub fn on_click(&mut self, column: u16, row: u16) -> Result<()> {
+        let position = ratatui::layout::Position::new(column, row);
+        let targets = self.click_targets.clone();
+
+        if let Some((_, read_mode)) = targets
+            .tabs
+            .iter()
+            .find(|(area, _)| area.contains(position))
+        {
+            return self.set_read_mode(read_mode.clone());
+        }
+
+        if let Some(article) = targets.article
+            && article.contains(position)
+        {
+            let link = util::link_at(
+                &targets.article_rows,
+                (row - article.y).into(),
+                (column - article.x).into(),
+            );
+            if let Some(link) = link {
+                webbrowser::open(&link).map_err(|e| anyhow::anyhow!(e))?;
+            }
+            return Ok(());
+        }
+
+        let clicked = |target: &Option<crate::ui::ListTarget>, offset: usize| {
+            target
+                .as_ref()
+                .and_then(|target| target.item_at(position, offset))
+        };
+
+        if let Some(idx) = clicked(&targets.feeds, self.feeds.state.offset()) {
+            if matches!(self.selected, Selected::Feeds) && self.feeds.state.selected() == Some(idx)
+            {
+                return self.on_right();
+            }
+            self.leave_entry()?;
+            if self.feeds.state.selected() != Some(idx) {
+                self.cancel_pending_deletion();
+                self.cancel_rename_feed();
+                self.feeds.state.select(Some(idx));
+                self.update_current_feed_and_entries()?;
+            }
+            self.selected = Selected::Feeds;
+        } else if let Some(idx) = clicked(&targets.entries, self.entries.state.offset()) {
+            let was_reading = matches!(self.selected, Selected::Entry(_));
+            if matches!(self.selected, Selected::Entries)
+                && self.entries.state.selected() == Some(idx)
+            {
+                return self.select_and_show_current_entry();
+            }
+            self.leave_entry()?;
+            self.selected = Selected::Entries;
+            self.select_entry(Some(idx))?;
+            self.entry_selection_position = idx;
+            self.update_current_entry_meta()?;
+            // next to the article, clicking another entry opens it right away
+            if was_reading {
+                self.select_and_show_current_entry()?;
+            }
+        } else if let Some(idx) = clicked(
+            &targets.combined_entries,
+            self.combined_entries.state.offset(),
+        ) {
+            let was_reading = matches!(self.selected, Selected::Entry(_));
+            if matches!(self.selected, Selected::CombinedUnread)
+                && self.combined_entries.state.selected() == Some(idx)
+            {
+                return self.select_and_show_current_entry();
+            }
+            self.leave_entry()?;
+            self.selected = Selected::CombinedUnread;
+            self.select_combined_entry(Some(idx))?;
+            self.update_current_entry_meta()?;
+            if was_reading {
+                self.select_and_show_current_entry()?;
+            }
+        } else if let Some(idx) = clicked(&targets.feed_health, self.feed_health.state.offset()) {
+            if self.feed_health.state.selected() == Some(idx) {
+                return self.on_right();
+            }
+            self.feed_health.state.select(Some(idx));
+            self.sync_current_feed_to_health_selection()?;
+        } else if let Some(idx) = clicked(&targets.trash, self.trash.state.offset()) {
+            self.cancel_pending_deletion();
+            self.trash.state.select(Some(idx));
+        } else if let Some(idx) = clicked(&targets.profiles, self.profiles.state.offset()) {
+            if self.profiles.state.selected() == Some(idx) {
+                return self.switch_profile();
+            }
+            self.profiles.state.select(Some(idx));
+        }
+
+        Ok(())
+    }
+
+    /// closes the open entry, keeping its scroll position, without changing the selection
+    fn leave_entry(&mut self) -> Result<()> {
+        if matches!(self.selected, Selected::Entry(_)) {
+            self.remember_entry_scroll_position()?;
+            self.entry_scroll_position = 0;
+            self.current_entry_text = String::new();
+            self.came_from_combined_unread = false;
+        }
+        Ok(())
+    }
+
+    /// the mouse wheel moves through the focused list, or scrolls the open entry
+    pub fn on_scroll(&mut self, down: bool) -> Result<()> {
+        let steps = if matches!(self.selected, Selected::Entry(_)) {
+            MOUSE_SCROLL_LINES
+        } else {
+            1
+        };
+
+        for _ in 0..steps {
+            if self.scrolled_to_the_end(down) {
+                break;
+            }
+            if down {
+                self.on_down()?;
+            } else {
+                self.on_up()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// moving with the keys wraps around the focused list, the mouse wheel stops at its ends
+    fn scrolled_to_the_end(&self, down: bool) -> bool {
+        let (selected, len) = match self.selected {
+            Selected::Feeds => (self.feeds.state.selected(), self.feeds.items.len()),
+            Selected::Entries => (self.entries.state.selected(), self.entries.len()),
+            Selected::CombinedUnread => (
+                self.combined_entries.state.selected(),
+                self.combined_entries.len(),
+            ),
+            Selected::Health => (
+                self.feed_health.state.selected(),
+                self.feed_health.items.len(),
+            ),
+            Selected::Trash => (self.trash.state.selected(), self.trash.items.len()),
+            Selected::Profiles => (self.profiles.state.selected(), self.profiles.items.len()),
+            // scrolling an entry already stops at its ends
+            Selected::Entry(_) | Selected::None => return false,
+        };
+
+        match selected {
+            Some(selected) if down => selected + 1 >= len,
+            Some(selected) => selected == 0,
+            None => false,
+        }
+    }
+
     pub fn mode(&self) -> Mode {
         self.mode
     }
 
+    /// the screen is only redrawn when something changes,
+    /// so changes made off the main thread have to ask for it
     pub fn force_redraw(&self) -> Result<()> {
-        self.event_tx.send(crate::Event::Tick).map_err(|e| e.into())
+        self.event_tx
+            .send(crate::Event::StateChanged)
+            .map_err(|e| e.into())
     }
 
     pub fn cycle_theme(&mut self) {
@@ -1223,3 +2446,31 @@ impl AppImpl {
         self.feed_errors.contains_key(&feed_id)
     }
 }
+
+/// the entry as plain text wrapped to fit a column `column_width` wide,
+/// and how many lines it takes
+fn render_entry_text(
+    entry: &crate::rss::EntryContent,
+    column_width: u16,
+) -> Result<(String, usize)> {
+    // try content tag first,
+    // if there is not content tag,
+    // go to description tag,
+    // if no description tag,
+    // use a placeholder
+    let html = entry
+        .content
+        .as_deref()
+        .or(entry.description.as_deref())
+        .unwrap_or("No content or description tag provided.");
+
+    // minimum is 1
+    let line_length = if column_width >= 5 {
+        column_width - 2
+    } else {
+        1
+    };
+
+    let text = html2text::from_read(html.as_bytes(), line_length.into())?;
+    Ok((sanitize_for_display(&text), text.matches('\n').count()))
+}
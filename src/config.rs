@@ -0,0 +1,279 @@
+// user configuration, read from a TOML file
+
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// the profile used without `--profile`, with the database in the default location
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Settings read from `config.toml`.
+/// Every section is optional, a missing file is the same as an empty one.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub network: NetworkConfig,
+    pub refresh: RefreshConfig,
+    pub trash: TrashConfig,
+    /// `[profiles.<name>]`, separate databases with their own settings
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+/// A named database, e.g. for keeping work and personal feeds apart.
+/// The sections it has replace the top-level ones, the rest are shared
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// defaults to `profiles/<name>/feeds.db` in the default database directory
+    pub database_path: Option<PathBuf>,
+    pub network: Option<NetworkConfig>,
+    pub refresh: Option<RefreshConfig>,
+}
+
+/// How feeds are fetched: proxies, extra trusted CAs and client certificates.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// `http://host:port` or `socks5://host:port`.
+    /// When unset, `HTTPS_PROXY`/`HTTP_PROXY`/`ALL_PROXY` from the environment are used.
+    pub proxy: Option<String>,
+    /// hosts (and their subdomains) that are fetched without a proxy.
+    /// When unset, `NO_PROXY` from the environment is used.
+    pub no_proxy: Option<Vec<String>>,
+    /// PEM files with extra root certificates to trust, on top of the built-in roots
+    pub ca_bundles: Vec<PathBuf>,
+    /// PEM file with a client certificate chain, for servers that require mutual TLS
+    pub client_certificate: Option<PathBuf>,
+    /// PEM file with the client certificate's private key,
+    /// if it is not in `client_certificate` itself
+    pub client_key: Option<PathBuf>,
+    /// per-feed proxies, e.g. sending `.onion` feeds through Tor
+    pub proxy_overrides: Vec<ProxyOverride>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyOverride {
+    /// a host suffix like `.onion`, or the start of a feed url
+    pub pattern: String,
+    /// the proxy to use for matching feeds, or `none` to connect directly
+    pub proxy: String,
+}
+
+/// How politely feeds are refreshed: requests to the same host are capped and spaced out.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefreshConfig {
+    /// requests to one host that may be in flight at once
+    pub per_host_concurrency: usize,
+    /// minimum time between starting two requests to the same host
+    pub per_host_delay_ms: u64,
+    /// per-host limits, e.g. for sites that rate limit harder than most
+    pub host_overrides: Vec<HostOverride>,
+}
+
+impl Default for RefreshConfig {
+    fn default() -> Self {
+        Self {
+            per_host_concurrency: 2,
+            per_host_delay_ms: 500,
+            host_overrides: vec![],
+        }
+    }
+}
+
+/// How long deleted feeds wait in the trash
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrashConfig {
+    /// days a deleted feed can be restored for, before it is purged for good
+    pub retention_days: u32,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostOverride {
+    /// a host like `reddit.com`, matching its subdomains too.
+    /// All matching feeds share the limits, whatever their subdomain
+    pub host: String,
+    /// falls back to `per_host_concurrency`
+    pub concurrency: Option<usize>,
+    /// falls back to `per_host_delay_ms`
+    pub delay_ms: Option<u64>,
+}
+
+impl Config {
+    /// Loads `$RSS_TUI_CONFIG`, or `config.toml` in the platform config directory
+    /// (`$XDG_CONFIG_HOME/rss-tui/config.toml` on Linux).
+    pub fn load() -> Result<Self> {
+        match default_config_path() {
+            Some(path) => Self::load_from(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &std::path::Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(s) => toml::from_str(&s).with_context(|| format!("invalid config file {path:?}")),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("unable to read config file {path:?}")),
+        }
+    }
+
+    /// the settings of `profile`, with its own sections in place of the top-level ones
+    pub fn for_profile(&self, profile: &str) -> Result<Self> {
+        let Some(profile_config) = self.profiles.get(profile) else {
+            if profile == DEFAULT_PROFILE {
+                return Ok(self.clone());
+            }
+            bail!(
+                "Unknown profile '{profile}', add a [profiles.{profile}] section to config.toml. Known profiles: {}",
+                self.profile_names().join(", ")
+            );
+        };
+
+        let mut config = self.clone();
+        if let Some(network) = &profile_config.network {
+            config.network = network.clone();
+        }
+        if let Some(refresh) = &profile_config.refresh {
+            config.refresh = refresh.clone();
+        }
+
+        Ok(config)
+    }
+
+    /// the default profile first, then the configured ones by name
+    pub fn profile_names(&self) -> Vec<String> {
+        std::iter::once(DEFAULT_PROFILE.to_string())
+            .chain(
+                self.profiles
+                    .keys()
+                    .filter(|name| name.as_str() != DEFAULT_PROFILE)
+                    .cloned(),
+            )
+            .collect()
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("RSS_TUI_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+
+    directories::ProjectDirs::from("", "", "rss-tui")
+        .map(|dirs| dirs.config_dir().join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_network_section() {
+        let config: Config = toml::from_str(
+            r#"
+[network]
+proxy = "http://proxy.example.com:3128"
+no_proxy = ["localhost", ".internal.example.com"]
+ca_bundles = ["/etc/ssl/corp.pem"]
+
+[[network.proxy_overrides]]
+pattern = ".onion"
+proxy = "socks5h://127.0.0.1:9050"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.network.proxy.as_deref(),
+            Some("http://proxy.example.com:3128")
+        );
+        assert_eq!(config.network.no_proxy.unwrap().len(), 2);
+        assert_eq!(config.network.ca_bundles.len(), 1);
+        assert_eq!(config.network.proxy_overrides[0].pattern, ".onion");
+    }
+
+    #[test]
+    fn empty_config_is_default() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.network.proxy.is_none());
+        assert!(config.network.proxy_overrides.is_empty());
+        assert_eq!(config.refresh.per_host_concurrency, 2);
+        assert!(config.refresh.host_overrides.is_empty());
+        assert_eq!(config.trash.retention_days, 30);
+        let config: Config = toml::from_str("[trash]\nretention_days = 7").unwrap();
+        assert_eq!(config.trash.retention_days, 7);
+    }
+
+    #[test]
+    fn parses_refresh_section() {
+        let config: Config = toml::from_str(
+            r#"
+[refresh]
+per_host_delay_ms = 250
+
+[[refresh.host_overrides]]
+host = "reddit.com"
+concurrency = 1
+delay_ms = 2000
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.refresh.per_host_concurrency, 2);
+        assert_eq!(config.refresh.per_host_delay_ms, 250);
+        assert_eq!(config.refresh.host_overrides[0].host, "reddit.com");
+        assert_eq!(config.refresh.host_overrides[0].concurrency, Some(1));
+        assert_eq!(config.refresh.host_overrides[0].delay_ms, Some(2000));
+    }
+
+    #[test]
+    fn profiles_replace_the_sections_they_have() {
+        let config: Config = toml::from_str(
+            r#"
+[network]
+proxy = "http://proxy.example.com:3128"
+
+[refresh]
+per_host_concurrency = 4
+
+[profiles.work]
+database_path = "/home/me/work.db"
+
+[profiles.work.network]
+proxy = "http://work-proxy.example.com:8080"
+
+[profiles.personal]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.profile_names(), vec!["default", "personal", "work"]);
+
+        let work = config.for_profile("work").unwrap();
+        assert_eq!(
+            work.network.proxy.as_deref(),
+            Some("http://work-proxy.example.com:8080")
+        );
+        assert_eq!(work.refresh.per_host_concurrency, 4);
+        assert_eq!(
+            work.profiles["work"].database_path,
+            Some(PathBuf::from("/home/me/work.db"))
+        );
+
+        let default = config.for_profile(DEFAULT_PROFILE).unwrap();
+        assert_eq!(
+            default.network.proxy.as_deref(),
+            Some("http://proxy.example.com:3128")
+        );
+
+        assert!(config.for_profile("play").is_err());
+    }
+}
@@ -0,0 +1,453 @@
+// database maintenance: `rss-tui db ...`
+
+use crate::DbOptions;
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum DbCommand {
+    /// Print the number of feeds and entries, and how much space each feed takes
+    Stats,
+    /// Rebuild the database file, giving the space of deleted entries back to the filesystem
+    Vacuum,
+    /// Run SQLite's integrity check
+    Check,
+    /// Copy the database to a new file. Safe while `rss-tui read` is running
+    Backup {
+        /// where to write the copy, which must not exist yet
+        path: PathBuf,
+    },
+    /// Replace the database with a backup, after checking that it is an rss-tui database.
+    /// The database being replaced is backed up next to itself first.
+    /// Refused while `rss-tui read` or `rss-tui refresh` has the database open
+    Restore {
+        /// the backup to restore
+        path: PathBuf,
+    },
+}
+
+/// How big one feed is
+#[derive(Debug, PartialEq)]
+pub struct FeedStats {
+    pub title: String,
+    pub entries: usize,
+    pub unread: usize,
+    /// the size of its entries' text, not counting sqlite's own overhead
+    pub bytes: u64,
+    /// the `pub_date` (or when it was fetched) of the oldest entry
+    pub oldest_entry: Option<String>,
+    pub deleted: bool,
+}
+
+pub(crate) fn run(options: DbOptions) -> Result<()> {
+    let path = &options.database_path;
+
+    match &options.command {
+        DbCommand::Stats => {
+            // only looks, so an old database is neither migrated nor backed up
+            let conn = open_read_only(path)?;
+            let version = crate::migrations::validate_schema(&conn)?;
+            if version < crate::migrations::LATEST_VERSION {
+                bail!(
+                    "{path:?} is at schema version {version}, older than this rss-tui's {}. \
+                    Open it with `rss-tui read` to migrate it first",
+                    crate::migrations::LATEST_VERSION
+                );
+            }
+            print_stats(&conn, path)
+        }
+        DbCommand::Vacuum => {
+            let conn = open_existing(path)?;
+            let before = file_size(&conn)?;
+            conn.execute_batch("VACUUM")?;
+            println!(
+                "{path:?}: {} -> {}",
+                human_bytes(before),
+                human_bytes(file_size(&conn)?)
+            );
+            Ok(())
+        }
+        DbCommand::Check => {
+            let conn = open_existing(path)?;
+            let problems = integrity_check(&conn)?;
+            if !problems.is_empty() {
+                for problem in &problems {
+                    println!("{problem}");
+                }
+                bail!(
+                    "{path:?}: the integrity check found {} problems",
+                    problems.len()
+                );
+            }
+            println!("{path:?}: ok");
+            Ok(())
+        }
+        DbCommand::Backup { path: backup_path } => {
+            if backup_path.exists() {
+                bail!("{backup_path:?} already exists");
+            }
+            let conn = open_existing(path)?;
+            backup_to(&conn, backup_path)?;
+            println!("{path:?}: backed up to {backup_path:?}");
+            Ok(())
+        }
+        DbCommand::Restore { path: backup_path } => restore(path, backup_path),
+    }
+}
+
+/// so that a mistyped path is not silently created as a new, empty database
+pub(crate) fn open_existing(path: &Path) -> Result<rusqlite::Connection> {
+    if !path.exists() {
+        bail!("There is no database at {path:?}");
+    }
+    Ok(rusqlite::Connection::open(path)?)
+}
+
+fn open_read_only(path: &Path) -> Result<rusqlite::Connection> {
+    if !path.exists() {
+        bail!("There is no database at {path:?}");
+    }
+    Ok(rusqlite::Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )?)
+}
+
+/// Takes `<database>.<name>.lock` for this process alone, `None` when another process holds it.
+/// The lock goes away with the process, however it ends
+pub(crate) fn try_lock(database_path: &Path, name: &str) -> Result<Option<std::fs::File>> {
+    lock_file(database_path, name, std::fs::File::try_lock)
+}
+
+/// Takes `<database>.<name>.lock` alongside any other shared holders,
+/// `None` while a process holds it for itself
+pub(crate) fn try_lock_shared(database_path: &Path, name: &str) -> Result<Option<std::fs::File>> {
+    lock_file(database_path, name, std::fs::File::try_lock_shared)
+}
+
+fn lock_file(
+    database_path: &Path,
+    name: &str,
+    try_lock: fn(&std::fs::File) -> Result<(), std::fs::TryLockError>,
+) -> Result<Option<std::fs::File>> {
+    let mut lock_path = database_path.as_os_str().to_owned();
+    lock_path.push(format!(".{name}.lock"));
+    let lock_path = PathBuf::from(lock_path);
+
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("unable to open the lock file {lock_path:?}"))?;
+
+    match try_lock(&file) {
+        Ok(()) => Ok(Some(file)),
+        Err(std::fs::TryLockError::WouldBlock) => Ok(None),
+        Err(std::fs::TryLockError::Error(e)) => {
+            Err(e).with_context(|| format!("unable to lock {lock_path:?}"))
+        }
+    }
+}
+
+fn print_stats(conn: &rusqlite::Connection, path: &Path) -> Result<()> {
+    let feeds = feed_stats(conn)?;
+    let free_bytes = conn.pragma_query_value(None, "freelist_count", |row| row.get::<_, u64>(0))?
+        * conn.pragma_query_value(None, "page_size", |row| row.get::<_, u64>(0))?;
+
+    println!(
+        "{path:?}: {}, {} free, schema version {}",
+        human_bytes(file_size(conn)?),
+        human_bytes(free_bytes),
+        crate::migrations::schema_version(conn)?
+    );
+    println!(
+        "{} feeds ({} in the trash), {} entries ({} unread), oldest entry {}",
+        feeds.len(),
+        feeds.iter().filter(|feed| feed.deleted).count(),
+        feeds.iter().map(|feed| feed.entries).sum::<usize>(),
+        feeds.iter().map(|feed| feed.unread).sum::<usize>(),
+        feeds
+            .iter()
+            .filter_map(|feed| feed.oldest_entry.as_deref())
+            .min()
+            .map(date)
+            .unwrap_or("-"),
+    );
+
+    if feeds.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "{:>10} {:>8} {:>8}  {:<10}  feed",
+        "size", "entries", "unread", "oldest"
+    );
+    for feed in &feeds {
+        println!(
+            "{:>10} {:>8} {:>8}  {:<10}  {}{}",
+            human_bytes(feed.bytes),
+            feed.entries,
+            feed.unread,
+            feed.oldest_entry.as_deref().map(date).unwrap_or("-"),
+            feed.title,
+            if feed.deleted { " (trash)" } else { "" }
+        );
+    }
+
+    Ok(())
+}
+
+/// every feed, biggest first
+pub fn feed_stats(conn: &rusqlite::Connection) -> Result<Vec<FeedStats>> {
+    let mut statement = conn.prepare(
+        "SELECT
+          coalesce(feeds.title, feeds.feed_link, ''),
+          count(entries.id),
+          count(entries.id) - count(entries.read_at),
+          coalesce(sum(
+            length(CAST(coalesce(entries.title, '') AS BLOB))
+            + length(CAST(coalesce(entries.author, '') AS BLOB))
+            + length(CAST(coalesce(entries.description, '') AS BLOB))
+            + length(CAST(coalesce(entries.content, '') AS BLOB))
+            + length(CAST(coalesce(entries.link, '') AS BLOB))
+          ), 0),
+          min(coalesce(entries.pub_date, entries.inserted_at)),
+          feeds.deleted_at IS NOT NULL
+        FROM feeds
+        LEFT JOIN entries ON entries.feed_id = feeds.id
+        GROUP BY feeds.id
+        ORDER BY 4 DESC, 1",
+    )?;
+
+    let stats = statement
+        .query_map([], |row| {
+            Ok(FeedStats {
+                title: row.get(0)?,
+                entries: row.get(1)?,
+                unread: row.get(2)?,
+                bytes: row.get(3)?,
+                oldest_entry: row.get(4)?,
+                deleted: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(stats)
+}
+
+/// the date part of a stored timestamp
+fn date(timestamp: &str) -> &str {
+    timestamp.get(..10).unwrap_or(timestamp)
+}
+
+fn file_size(conn: &rusqlite::Connection) -> Result<u64> {
+    Ok(
+        conn.pragma_query_value(None, "page_count", |row| row.get::<_, u64>(0))?
+            * conn.pragma_query_value(None, "page_size", |row| row.get::<_, u64>(0))?,
+    )
+}
+
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+
+    format!("{size:.1} {unit}")
+}
+
+/// what `PRAGMA integrity_check` found, empty when the database is fine
+fn integrity_check(conn: &rusqlite::Connection) -> Result<Vec<String>> {
+    let mut statement = conn.prepare("PRAGMA integrity_check")?;
+    let problems = statement
+        .query_map([], |row| row.get::<_, String>(0))?
+        .filter(|row| !matches!(row.as_deref(), Ok("ok")))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(problems)
+}
+
+/// Copies the database to `path` with SQLite's online backup API,
+/// so the copy is consistent even while another rss-tui has the database open.
+pub fn backup_to(conn: &rusqlite::Connection, path: &Path) -> Result<()> {
+    let mut backup_conn = rusqlite::Connection::open(path)
+        .with_context(|| format!("Unable to create database backup {path:?}"))?;
+    rusqlite::backup::Backup::new(conn, &mut backup_conn)?
+        .run_to_completion(256, std::time::Duration::from_millis(10), None)
+        .with_context(|| format!("Unable to back up the database to {path:?}"))?;
+
+    Ok(())
+}
+
+/// Backs the database up to `<name>.<label>-<timestamp>.bak` next to it
+pub fn backup_next_to(conn: &rusqlite::Connection, path: &Path, label: &str) -> Result<PathBuf> {
+    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "feeds.db".to_string());
+    let backup_path = path.with_file_name(format!("{file_name}.{label}-{timestamp}.bak"));
+
+    backup_to(conn, &backup_path)?;
+
+    Ok(backup_path)
+}
+
+/// Replaces the database at `path` with the one at `backup_path`.
+/// A reader or refresh with the database open would carry on with what it had loaded
+/// and write it back over the restored database, so that is refused.
+/// The locks are held until the restore is done, so neither can start in the meantime
+fn restore(path: &Path, backup_path: &Path) -> Result<()> {
+    let Some(_reader_lock) = try_lock(path, "reader")? else {
+        bail!("rss-tui read has {path:?} open, close it before restoring");
+    };
+    let Some(_refresh_lock) = try_lock(path, "refresh")? else {
+        bail!("A refresh of {path:?} is running, wait for it to finish before restoring");
+    };
+
+    let backup = rusqlite::Connection::open_with_flags(
+        backup_path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )
+    .with_context(|| format!("Unable to open {backup_path:?}"))?;
+
+    let problems =
+        integrity_check(&backup).with_context(|| format!("{backup_path:?} is not a database"))?;
+    if !problems.is_empty() {
+        bail!(
+            "{backup_path:?} is damaged, the integrity check found: {}",
+            problems.join("; ")
+        );
+    }
+    let version = crate::migrations::validate_schema(&backup)
+        .with_context(|| format!("Not restoring {backup_path:?}"))?;
+
+    let mut conn = rusqlite::Connection::open(path)?;
+    if crate::migrations::schema_version(&conn)? > 0 {
+        let replaced = backup_next_to(&conn, path, "before-restore")?;
+        println!("{path:?}: backed up to {replaced:?}");
+    }
+
+    rusqlite::backup::Backup::new(&backup, &mut conn)?
+        .run_to_completion(256, std::time::Duration::from_millis(10), None)
+        .with_context(|| format!("Unable to restore {backup_path:?} to {path:?}"))?;
+
+    println!("{path:?}: restored from {backup_path:?} (schema version {version})");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_count_entries_per_feed() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO feeds (id, title) VALUES (1, 'big'), (2, 'empty');
+            INSERT INTO feeds (id, title, deleted_at) VALUES (3, 'gone', '2024-01-01');
+            INSERT INTO entries (feed_id, title, content, pub_date, read_at)
+            VALUES
+              (1, 'a', 'xxxxxxxxxx', '2021-03-04T00:00:00Z', NULL),
+              (1, 'b', NULL, '2020-01-02T00:00:00Z', '2024-01-01'),
+              (3, 'c', NULL, '2022-01-01T00:00:00Z', NULL);",
+        )
+        .unwrap();
+
+        let stats = feed_stats(&conn).unwrap();
+
+        assert_eq!(
+            stats,
+            vec![
+                FeedStats {
+                    title: "big".to_string(),
+                    entries: 2,
+                    unread: 1,
+                    bytes: 12,
+                    oldest_entry: Some("2020-01-02T00:00:00Z".to_string()),
+                    deleted: false,
+                },
+                FeedStats {
+                    title: "gone".to_string(),
+                    entries: 1,
+                    unread: 1,
+                    bytes: 1,
+                    oldest_entry: Some("2022-01-01T00:00:00Z".to_string()),
+                    deleted: true,
+                },
+                FeedStats {
+                    title: "empty".to_string(),
+                    entries: 0,
+                    unread: 0,
+                    bytes: 0,
+                    oldest_entry: None,
+                    deleted: false,
+                },
+            ]
+        );
+        assert_eq!(human_bytes(1536), "1.5 KiB");
+    }
+
+    #[test]
+    fn restores_only_rss_tui_databases() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-db-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("feeds.db");
+        let backup_path = dir.join("backup.db");
+
+        let mut conn = rusqlite::Connection::open(&path).unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+        conn.execute("INSERT INTO feeds (title) VALUES ('before')", [])
+            .unwrap();
+        backup_to(&conn, &backup_path).unwrap();
+        conn.execute("UPDATE feeds SET title = 'after'", [])
+            .unwrap();
+
+        let other_path = dir.join("other.db");
+        rusqlite::Connection::open(&other_path)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (text TEXT); PRAGMA user_version = 3;")
+            .unwrap();
+        let error = format!("{:#}", restore(&path, &other_path).unwrap_err());
+        assert!(error.contains("Not an rss-tui database"), "{error}");
+
+        let reader_lock = try_lock_shared(&path, "reader").unwrap();
+        assert!(reader_lock.is_some());
+        let error = restore(&path, &backup_path).unwrap_err().to_string();
+        assert!(error.contains("close it before restoring"), "{error}");
+        drop(reader_lock);
+
+        restore(&path, &backup_path).unwrap();
+
+        let title: String = conn
+            .query_row("SELECT title FROM feeds", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(title, "before");
+        // the replaced database was kept
+        assert!(std::fs::read_dir(&dir).unwrap().any(|entry| {
+            entry
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with("feeds.db.before-restore-")
+        }));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,559 @@
+// export entries to json lines, csv, markdown and mbox
+
+use crate::ExportEntriesOptions;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The formats `export-entries` writes
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum EntryFormat {
+    /// one json object per line
+    Jsonl,
+    /// a header row, then one row per entry
+    Csv,
+    /// a file per entry with yaml front matter, into the `--output` directory
+    Markdown,
+    /// one message per entry, readable by mail clients
+    Mbox,
+}
+
+/// Which entries to export
+#[derive(Clone, Debug, Default)]
+pub struct EntryFilter {
+    /// feed ids or urls, every feed when empty
+    pub feeds: Vec<String>,
+    /// published (or fetched) on or after this day
+    pub since: Option<NaiveDate>,
+    /// published (or fetched) on or before this day
+    pub until: Option<NaiveDate>,
+    pub read: Option<bool>,
+    pub flagged: bool,
+}
+
+/// An entry as it is exported, dates in RFC 3339
+#[derive(Debug, serde::Serialize)]
+struct ExportedEntry {
+    id: i64,
+    feed_id: i64,
+    feed_title: Option<String>,
+    feed_url: Option<String>,
+    title: Option<String>,
+    author: Option<String>,
+    link: Option<String>,
+    guid: Option<String>,
+    published: Option<String>,
+    inserted_at: Option<String>,
+    read_at: Option<String>,
+    flagged_at: Option<String>,
+    /// html, the description when the feed had no content
+    content: Option<String>,
+}
+
+const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "feed_id",
+    "feed_title",
+    "feed_url",
+    "title",
+    "author",
+    "link",
+    "guid",
+    "published",
+    "inserted_at",
+    "read_at",
+    "flagged_at",
+    "content",
+];
+
+pub(crate) fn export_entries(options: ExportEntriesOptions) -> Result<()> {
+    let mut conn = crate::db::open_existing(&options.database_path)?;
+
+    crate::rss::initialize_db(&mut conn)?;
+
+    let exported = match (options.format, &options.output) {
+        (EntryFormat::Markdown, Some(dir)) => {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("unable to create directory {dir:?}"))?;
+            for_each_entry(&conn, &options.filter, |entry| {
+                write_markdown_file(dir, &entry)
+            })?
+        }
+        (EntryFormat::Markdown, None) => {
+            bail!("markdown writes a file per entry, pass the directory for them with --output")
+        }
+        (format, output) => {
+            let out: Box<dyn Write> = match output {
+                Some(path) => Box::new(
+                    std::fs::File::create(path)
+                        .with_context(|| format!("unable to create {path:?}"))?,
+                ),
+                None => Box::new(std::io::stdout().lock()),
+            };
+            let mut out = std::io::BufWriter::new(out);
+
+            if format == EntryFormat::Csv {
+                write_csv_row(&mut out, CSV_COLUMNS.iter().map(|column| Some(*column)))?;
+            }
+            let exported = for_each_entry(&conn, &options.filter, |entry| match format {
+                EntryFormat::Jsonl => {
+                    serde_json::to_writer(&mut out, &entry)?;
+                    writeln!(out)?;
+                    Ok(())
+                }
+                EntryFormat::Csv => write_csv_entry(&mut out, &entry),
+                EntryFormat::Mbox => write_mbox_message(&mut out, &entry),
+                EntryFormat::Markdown => unreachable!("markdown is written to a directory"),
+            })?;
+            out.flush()?;
+            exported
+        }
+    };
+
+    match &options.output {
+        Some(path) => eprintln!("Exported {exported} entries to {path:?}"),
+        None => eprintln!("Exported {exported} entries"),
+    }
+
+    Ok(())
+}
+
+/// Runs `f` on each entry that passes the filter, oldest first, a row at a time.
+/// Returns how many there were
+fn for_each_entry<F>(conn: &rusqlite::Connection, filter: &EntryFilter, mut f: F) -> Result<usize>
+where
+    F: FnMut(ExportedEntry) -> Result<()>,
+{
+    let mut query =
+        "SELECT e.id, e.feed_id, f.title, f.feed_link, e.title, e.author, e.link, e.guid,
+        e.pub_date, e.inserted_at, e.read_at, e.flagged_at, COALESCE(e.content, e.description)
+        FROM entries e
+        JOIN feeds f ON f.id = e.feed_id
+        WHERE f.deleted_at IS NULL"
+            .to_string();
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![];
+
+    if !filter.feeds.is_empty() {
+        let feed_ids = filter
+            .feeds
+            .iter()
+            .map(|feed| crate::rss::find_feed_id(conn, feed))
+            .collect::<Result<Vec<_>>>()?;
+        query.push_str(&format!(
+            "\nAND e.feed_id IN ({})",
+            vec!["?"; feed_ids.len()].join(", ")
+        ));
+        for feed_id in feed_ids {
+            params.push(Box::new(feed_id));
+        }
+    }
+    if let Some(since) = filter.since {
+        query.push_str("\nAND COALESCE(e.pub_date, e.inserted_at) >= ?");
+        params.push(Box::new(since.and_time(chrono::NaiveTime::MIN).and_utc()));
+    }
+    if let Some(until) = filter.until {
+        query.push_str("\nAND COALESCE(e.pub_date, e.inserted_at) < ?");
+        params.push(Box::new(
+            (until + chrono::Days::new(1))
+                .and_time(chrono::NaiveTime::MIN)
+                .and_utc(),
+        ));
+    }
+    match filter.read {
+        Some(true) => query.push_str("\nAND e.read_at IS NOT NULL"),
+        Some(false) => query.push_str("\nAND e.read_at IS NULL"),
+        None => {}
+    }
+    if filter.flagged {
+        query.push_str("\nAND e.flagged_at IS NOT NULL");
+    }
+    query.push_str("\nORDER BY COALESCE(e.pub_date, e.inserted_at), e.id");
+
+    let mut statement = conn.prepare(&query)?;
+    let mut rows = statement.query(rusqlite::params_from_iter(params))?;
+
+    let rfc3339 = |date: Option<DateTime<Utc>>| date.map(|date| date.to_rfc3339());
+    let mut exported = 0;
+    while let Some(row) = rows.next()? {
+        f(ExportedEntry {
+            id: row.get(0)?,
+            feed_id: row.get(1)?,
+            feed_title: row.get(2)?,
+            feed_url: row.get(3)?,
+            title: row.get(4)?,
+            author: row.get(5)?,
+            link: row.get(6)?,
+            guid: row.get(7)?,
+            published: rfc3339(row.get(8)?),
+            inserted_at: rfc3339(row.get(9)?),
+            read_at: rfc3339(row.get(10)?),
+            flagged_at: rfc3339(row.get(11)?),
+            content: row.get(12)?,
+        })?;
+        exported += 1;
+    }
+
+    Ok(exported)
+}
+
+fn write_csv_entry(out: &mut impl Write, entry: &ExportedEntry) -> Result<()> {
+    let (id, feed_id) = (entry.id.to_string(), entry.feed_id.to_string());
+    write_csv_row(
+        out,
+        [
+            Some(id.as_str()),
+            Some(feed_id.as_str()),
+            entry.feed_title.as_deref(),
+            entry.feed_url.as_deref(),
+            entry.title.as_deref(),
+            entry.author.as_deref(),
+            entry.link.as_deref(),
+            entry.guid.as_deref(),
+            entry.published.as_deref(),
+            entry.inserted_at.as_deref(),
+            entry.read_at.as_deref(),
+            entry.flagged_at.as_deref(),
+            entry.content.as_deref(),
+        ],
+    )
+}
+
+/// RFC 4180: fields with a comma, quote or line break are quoted, with quotes doubled
+fn write_csv_row<'a>(
+    out: &mut impl Write,
+    fields: impl IntoIterator<Item = Option<&'a str>>,
+) -> Result<()> {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            out.write_all(b",")?;
+        }
+        let field = field.unwrap_or_default();
+        if field.contains([',', '"', '\n', '\r']) {
+            write!(out, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            out.write_all(field.as_bytes())?;
+        }
+    }
+    out.write_all(b"\r\n")?;
+    Ok(())
+}
+
+/// `<date>-<title>-<id>.md`, so the files sort by date and never collide
+fn markdown_file_name(entry: &ExportedEntry) -> String {
+    let date = entry
+        .published
+        .as_deref()
+        .or(entry.inserted_at.as_deref())
+        .and_then(|date| date.get(..10))
+        .unwrap_or("undated");
+
+    let mut slug = String::new();
+    for c in entry.title.as_deref().unwrap_or_default().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.chars().count() >= 60 {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    if slug.is_empty() {
+        format!("{date}-{}.md", entry.id)
+    } else {
+        format!("{date}-{slug}-{}.md", entry.id)
+    }
+}
+
+fn write_markdown_file(dir: &Path, entry: &ExportedEntry) -> Result<()> {
+    let path: PathBuf = dir.join(markdown_file_name(entry));
+    let mut out = std::io::BufWriter::new(
+        std::fs::File::create(&path).with_context(|| format!("unable to create {path:?}"))?,
+    );
+    write_markdown(&mut out, entry)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_markdown(out: &mut impl Write, entry: &ExportedEntry) -> Result<()> {
+    writeln!(out, "---")?;
+    for (key, value) in [
+        ("title", &entry.title),
+        ("feed", &entry.feed_title),
+        ("feed_url", &entry.feed_url),
+        ("link", &entry.link),
+        ("author", &entry.author),
+        ("guid", &entry.guid),
+        ("published", &entry.published),
+        ("read_at", &entry.read_at),
+        ("flagged_at", &entry.flagged_at),
+    ] {
+        if let Some(value) = value {
+            // a json string is a valid yaml scalar, whatever is in it
+            writeln!(out, "{key}: {}", serde_json::to_string(value)?)?;
+        }
+    }
+    writeln!(out, "---")?;
+
+    if let Some(title) = &entry.title {
+        writeln!(out)?;
+        writeln!(out, "# {}", title.replace('\n', " "))?;
+    }
+    if let Some(content) = &entry.content {
+        writeln!(out)?;
+        write!(out, "{}", html2text::from_read(content.as_bytes(), 80)?)?;
+    }
+
+    Ok(())
+}
+
+/// mboxrd: lines starting with `From `, after any `>`s, get one more `>`
+fn write_mbox_message(out: &mut impl Write, entry: &ExportedEntry) -> Result<()> {
+    let date = entry
+        .published
+        .as_deref()
+        .or(entry.inserted_at.as_deref())
+        .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+        .unwrap_or_default();
+
+    writeln!(out, "From rss-tui {}", date.format("%a %b %e %H:%M:%S %Y"))?;
+    writeln!(
+        out,
+        "From: {} <rss-tui@localhost>",
+        display_name(entry.feed_title.as_deref().unwrap_or("rss-tui"))
+    )?;
+    writeln!(
+        out,
+        "Subject: {}",
+        encode_header(entry.title.as_deref().unwrap_or_default())
+    )?;
+    writeln!(out, "Date: {}", date.to_rfc2822())?;
+    writeln!(out, "Message-ID: <{}.{}@rss-tui>", entry.id, entry.feed_id)?;
+    if let Some(link) = &entry.link {
+        writeln!(out, "X-RSS-Link: {}", link.replace(['\r', '\n'], ""))?;
+    }
+    if let Some(feed_url) = &entry.feed_url {
+        writeln!(out, "X-RSS-Feed: {}", feed_url.replace(['\r', '\n'], ""))?;
+    }
+    // the flags mutt and friends read from mbox files
+    writeln!(
+        out,
+        "Status: {}",
+        if entry.read_at.is_some() { "RO" } else { "O" }
+    )?;
+    if entry.flagged_at.is_some() {
+        writeln!(out, "X-Status: F")?;
+    }
+    writeln!(out, "MIME-Version: 1.0")?;
+    writeln!(out, "Content-Type: text/html; charset=utf-8")?;
+    writeln!(out, "Content-Transfer-Encoding: 8bit")?;
+    writeln!(out)?;
+
+    let mut body = String::new();
+    if let Some(link) = &entry.link {
+        body.push_str(&format!(
+            "<p><a href=\"{}\">{}</a></p>\n",
+            html_escape::encode_double_quoted_attribute(link),
+            html_escape::encode_text(link)
+        ));
+    }
+    body.push_str(entry.content.as_deref().unwrap_or_default());
+
+    for line in body.lines() {
+        if line.trim_start_matches('>').starts_with("From ") {
+            write!(out, ">")?;
+        }
+        writeln!(out, "{line}")?;
+    }
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// the name in `From: name <address>`, quoted so commas and brackets in it are kept
+fn display_name(name: &str) -> String {
+    if name.is_ascii() {
+        format!(
+            "\"{}\"",
+            encode_header(name)
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+        )
+    } else {
+        encode_header(name)
+    }
+}
+
+/// a header value on one line, RFC 2047 encoded when it is not plain ascii
+fn encode_header(value: &str) -> String {
+    let value = value.replace(['\r', '\n'], " ");
+    if value.is_ascii() {
+        value
+    } else {
+        use base64::Engine;
+        format!(
+            "=?UTF-8?B?{}?=",
+            base64::engine::general_purpose::STANDARD.encode(value)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database() -> rusqlite::Connection {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO feeds (id, title, feed_link) VALUES
+                (1, 'One', 'https://one.example.com/feed'),
+                (2, 'Two', 'https://two.example.com/feed');
+            INSERT INTO entries (id, feed_id, title, link, pub_date, read_at, flagged_at, content) VALUES
+                (1, 1, 'old, \"quoted\"', 'https://one.example.com/1', '2023-12-31 23:00:00+00:00', '2024-01-01 00:00:00+00:00', NULL, '<p>one</p>
+From here'),
+                (2, 1, 'new', 'https://one.example.com/2', '2024-02-01 00:00:00+00:00', NULL, '2024-02-02 00:00:00+00:00', '<p>two</p>'),
+                (3, 2, 'other', 'https://two.example.com/3', '2024-02-15 00:00:00+00:00', NULL, NULL, NULL);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn ids(conn: &rusqlite::Connection, filter: EntryFilter) -> Vec<i64> {
+        let mut ids = vec![];
+        for_each_entry(conn, &filter, |entry| {
+            ids.push(entry.id);
+            Ok(())
+        })
+        .unwrap();
+        ids
+    }
+
+    #[test]
+    fn filters_entries() {
+        let conn = database();
+
+        assert_eq!(ids(&conn, EntryFilter::default()), vec![1, 2, 3]);
+        assert_eq!(
+            ids(
+                &conn,
+                EntryFilter {
+                    feeds: vec!["https://two.example.com/feed".to_string(), "1".to_string()],
+                    ..Default::default()
+                }
+            ),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            ids(
+                &conn,
+                EntryFilter {
+                    feeds: vec!["2".to_string()],
+                    ..Default::default()
+                }
+            ),
+            vec![3]
+        );
+        assert_eq!(
+            ids(
+                &conn,
+                EntryFilter {
+                    since: NaiveDate::from_ymd_opt(2024, 1, 1),
+                    until: NaiveDate::from_ymd_opt(2024, 2, 1),
+                    ..Default::default()
+                }
+            ),
+            vec![2]
+        );
+        assert_eq!(
+            ids(
+                &conn,
+                EntryFilter {
+                    read: Some(false),
+                    ..Default::default()
+                }
+            ),
+            vec![2, 3]
+        );
+        assert_eq!(
+            ids(
+                &conn,
+                EntryFilter {
+                    flagged: true,
+                    ..Default::default()
+                }
+            ),
+            vec![2]
+        );
+
+        let error = for_each_entry(
+            &conn,
+            &EntryFilter {
+                feeds: vec!["https://nowhere.example.com".to_string()],
+                ..Default::default()
+            },
+            |_| Ok(()),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("No feed"), "{error}");
+    }
+
+    #[test]
+    fn writes_each_format() {
+        let conn = database();
+        let mut entries = vec![];
+        for_each_entry(&conn, &EntryFilter::default(), |entry| {
+            entries.push(entry);
+            Ok(())
+        })
+        .unwrap();
+
+        let mut csv = vec![];
+        write_csv_entry(&mut csv, &entries[0]).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(
+            csv.starts_with("1,1,One,https://one.example.com/feed,\"old, \"\"quoted\"\"\","),
+            "{csv}"
+        );
+
+        let json = serde_json::to_value(&entries[1]).unwrap();
+        assert_eq!(json["flagged_at"], "2024-02-02T00:00:00+00:00");
+        assert_eq!(json["read_at"], serde_json::Value::Null);
+
+        let mut mbox = vec![];
+        write_mbox_message(&mut mbox, &entries[0]).unwrap();
+        let mbox = String::from_utf8(mbox).unwrap();
+        assert!(
+            mbox.starts_with("From rss-tui Sun Dec 31 23:00:00 2023\n"),
+            "{mbox}"
+        );
+        assert!(
+            mbox.contains("From: \"One\" <rss-tui@localhost>\n"),
+            "{mbox}"
+        );
+        assert!(mbox.contains("Status: RO\n"));
+        assert!(mbox.contains("\n<p>one</p>\n>From here\n"), "{mbox}");
+        assert_eq!(
+            encode_header("caf\u{e9}"),
+            "=?UTF-8?B?Y2Fmw6k=?=".to_string()
+        );
+
+        assert_eq!(
+            markdown_file_name(&entries[0]),
+            "2023-12-31-old-quoted-1.md"
+        );
+        let mut markdown = vec![];
+        write_markdown(&mut markdown, &entries[1]).unwrap();
+        let markdown = String::from_utf8(markdown).unwrap();
+        assert!(
+            markdown.starts_with("---\ntitle: \"new\"\nfeed: \"One\"\n"),
+            "{markdown}"
+        );
+        assert!(
+            markdown.contains("flagged_at: \"2024-02-02T00:00:00+00:00\"\n---\n\n# new\n\ntwo")
+        );
+    }
+}
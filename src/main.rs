@@ -4,7 +4,10 @@ use crate::modes::{Mode, Selected};
 use anyhow::Result;
 use app::App;
 use clap::{Parser, Subcommand};
-use crossterm::event::{self, KeyEvent, KeyEventKind};
+use crossterm::event::{
+    self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, KeyEvent,
+    KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
+};
 use crossterm::event::{Event as CEvent, KeyCode, KeyModifiers};
 use crossterm::execute;
 use crossterm::terminal::{
@@ -18,21 +21,39 @@ use std::sync::mpsc;
 use std::{thread, time};
 
 mod app;
+mod config;
+mod credentials;
+mod db;
+mod exporters;
+mod http;
+mod importers;
 mod io;
+mod migrations;
 mod modes;
 mod opml;
+mod refresh;
 mod rss;
+mod scrape;
+mod session;
+mod source;
 mod ui;
 mod util;
 
 fn main() -> Result<()> {
     let options = Options::parse();
 
-    let validated_options = options.subcommand.validate()?;
+    let validated_options = options.subcommand.validate(options.profile.as_deref())?;
 
     match validated_options {
         ValidatedOptions::Import(options) => crate::opml::import(options),
+        ValidatedOptions::ImportFrom(options) => crate::importers::import(options),
         ValidatedOptions::Export(options) => crate::opml::export(options),
+        ValidatedOptions::ExportEntries(options) => crate::exporters::export_entries(options),
+        ValidatedOptions::Scrape(options) => crate::scrape::subscribe(options),
+        ValidatedOptions::ScrapePreview(options) => crate::scrape::preview(options),
+        ValidatedOptions::Db(options) => crate::db::run(options),
+        ValidatedOptions::Credentials(options) => crate::credentials::run(options),
+        ValidatedOptions::Refresh(options) => crate::refresh::run(options),
         ValidatedOptions::Read(options) => run_reader(options),
     }
 }
@@ -43,6 +64,9 @@ fn main() -> Result<()> {
 struct Options {
     #[command(subcommand)]
     subcommand: Command,
+    /// use the database and settings of a `[profiles.<name>]` section in config.toml
+    #[arg(long, global = true)]
+    profile: Option<String>,
 }
 
 /// Only used to take input at the boundary.
@@ -57,15 +81,25 @@ enum Command {
         /// On Windows it will be at `{FOLDERID_LocalAppData}/rss-tui/data/feeds.db`.
         #[arg(short, long)]
         database_path: Option<PathBuf>,
-        /// time in ms between two ticks
-        #[arg(short, long, default_value = "250")]
-        tick_rate: u64,
+        /// no longer used, the screen is redrawn when something changes.
+        /// Still accepted so existing scripts keep working
+        #[arg(short, long, hide = true)]
+        tick_rate: Option<u64>,
         /// number of seconds to show the flash message before clearing it
         #[arg(short, long, default_value = "4", value_parser = parse_seconds)]
         flash_display_duration_seconds: time::Duration,
-        /// RSS/Atom network request timeout in seconds
+        /// RSS/Atom network request timeout in seconds, also the limit for `exec:` and `filter:` commands
         #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
         network_timeout: time::Duration,
+        /// start at the top of the feeds list with the default settings,
+        /// instead of where you left off last time
+        #[arg(long)]
+        fresh: bool,
+        /// leave the mouse to the terminal, so text can be selected with it.
+        /// By default clicks select feeds, entries and tabs, open links,
+        /// and the wheel scrolls
+        #[arg(long)]
+        no_mouse: bool,
     },
     /// Import feeds from an OPML document
     Import {
@@ -77,7 +111,25 @@ enum Command {
         database_path: Option<PathBuf>,
         #[arg(short, long)]
         opml_path: PathBuf,
-        /// RSS/Atom network request timeout in seconds
+        /// RSS/Atom network request timeout in seconds, also the limit for `exec:` and `filter:` commands
+        #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
+        network_timeout: time::Duration,
+    },
+    /// Import feeds, with what you have read and flagged, from another feed reader
+    ImportFrom {
+        /// Override where `rss-tui` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/rss-tui/feeds.db` or `$HOME/.local/share/rss-tui/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/rss-tui/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/rss-tui/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        #[arg(value_enum)]
+        reader: crate::importers::Reader,
+        /// the files to import: newsboat's `urls` and `cache.db`, a russ database,
+        /// or Miniflux/FreshRSS json exports
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// RSS/Atom network request timeout in seconds, also the limit for `exec:` and `filter:` commands
         #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
         network_timeout: time::Duration,
     },
@@ -92,24 +144,143 @@ enum Command {
         #[arg(short, long)]
         opml_path: PathBuf,
     },
+    /// Export entries to JSON Lines, CSV, Markdown files or an mbox
+    ExportEntries {
+        /// Override where `rss-tui` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/rss-tui/feeds.db` or `$HOME/.local/share/rss-tui/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/rss-tui/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/rss-tui/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        #[arg(short, long, value_enum, default_value = "jsonl")]
+        format: crate::exporters::EntryFormat,
+        /// the file to write, or the directory for markdown files. Standard output when left out
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// only entries of this feed, by id or url. Can be repeated
+        #[arg(long = "feed")]
+        feeds: Vec<String>,
+        /// only entries published on or after this day, as YYYY-MM-DD
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
+        /// only entries published on or before this day, as YYYY-MM-DD
+        #[arg(long)]
+        until: Option<chrono::NaiveDate>,
+        /// only read entries
+        #[arg(long, conflicts_with = "unread")]
+        read: bool,
+        /// only unread entries
+        #[arg(long)]
+        unread: bool,
+        /// only flagged entries
+        #[arg(long, visible_alias = "starred")]
+        flagged: bool,
+    },
+    /// Subscribe to an html page that has no feed, finding entries with css selectors
+    Scrape {
+        /// Override where `rss-tui` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/rss-tui/feeds.db` or `$HOME/.local/share/rss-tui/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/rss-tui/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/rss-tui/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// the page to scrape
+        url: String,
+        #[command(flatten)]
+        selectors: crate::scrape::ScrapeSelectors,
+        /// network request timeout in seconds
+        #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
+        network_timeout: time::Duration,
+    },
+    /// Print the entries `scrape` would find on a page, to tune the selectors before subscribing
+    ScrapePreview {
+        /// Override where `rss-tui` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/rss-tui/feeds.db` or `$HOME/.local/share/rss-tui/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/rss-tui/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/rss-tui/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// the page to scrape
+        url: String,
+        #[command(flatten)]
+        selectors: crate::scrape::ScrapeSelectors,
+        /// network request timeout in seconds
+        #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
+        network_timeout: time::Duration,
+    },
+    /// Refresh feeds without opening the reader, for cron and systemd timers
+    Refresh {
+        /// Override where `rss-tui` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/rss-tui/feeds.db` or `$HOME/.local/share/rss-tui/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/rss-tui/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/rss-tui/data/feeds.db`.
+        #[arg(short, long)]
+        database_path: Option<PathBuf>,
+        /// only refresh this feed, by id or url. Can be repeated
+        #[arg(long = "feed")]
+        feeds: Vec<String>,
+        /// how many feeds to refresh at once. Twice the number of cpus, at most 16, by default
+        #[arg(short, long)]
+        jobs: Option<usize>,
+        /// print the results as json instead of a line per feed
+        #[arg(long)]
+        json: bool,
+        /// exit with an error only when more than this fraction of the feeds fail
+        #[arg(long, default_value = "0.2", value_parser = parse_fraction)]
+        max_failed: f64,
+        /// RSS/Atom network request timeout in seconds, also the limit for `exec:` and `filter:` commands
+        #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
+        network_timeout: time::Duration,
+    },
+    /// Inspect and maintain the feeds database
+    Db {
+        /// Override where `rss-tui` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/rss-tui/feeds.db` or `$HOME/.local/share/rss-tui/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/rss-tui/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/rss-tui/data/feeds.db`.
+        #[arg(short, long, global = true)]
+        database_path: Option<PathBuf>,
+        #[command(subcommand)]
+        command: crate::db::DbCommand,
+    },
+    /// Show, set and clear the credentials and headers sent with a feed
+    Credentials {
+        /// Override where `rss-tui` stores and reads feeds.
+        /// By default, the feeds database on Linux this will be at `XDG_DATA_HOME/rss-tui/feeds.db` or `$HOME/.local/share/rss-tui/feeds.db`.
+        /// On MacOS it will be at `$HOME/Library/Application Support/rss-tui/feeds.db`.
+        /// On Windows it will be at `{FOLDERID_LocalAppData}/rss-tui/data/feeds.db`.
+        #[arg(short, long, global = true)]
+        database_path: Option<PathBuf>,
+        #[command(subcommand)]
+        command: crate::credentials::CredentialsCommand,
+    },
 }
 
 impl Command {
-    fn validate(&self) -> std::io::Result<ValidatedOptions> {
+    fn validate(&self, profile: Option<&str>) -> Result<ValidatedOptions> {
         match self {
             Command::Read {
                 database_path,
-                tick_rate,
+                tick_rate: _,
                 flash_display_duration_seconds,
                 network_timeout,
+                fresh,
+                no_mouse,
             } => {
-                let database_path = get_database_path(database_path)?;
+                let Profile {
+                    name,
+                    database_path,
+                    config,
+                } = get_profile(database_path, profile)?;
 
                 Ok(ValidatedOptions::Read(ReadOptions {
+                    profile: name,
                     database_path,
-                    tick_rate: *tick_rate,
                     flash_display_duration_seconds: *flash_display_duration_seconds,
                     network_timeout: *network_timeout,
+                    fresh: *fresh,
+                    mouse: !*no_mouse,
+                    config,
                 }))
             }
             Command::Import {
@@ -117,23 +288,153 @@ impl Command {
                 opml_path,
                 network_timeout,
             } => {
-                let database_path = get_database_path(database_path)?;
+                let Profile {
+                    database_path,
+                    config,
+                    ..
+                } = get_profile(database_path, profile)?;
                 Ok(ValidatedOptions::Import(ImportOptions {
                     database_path,
                     opml_path: opml_path.to_owned(),
                     network_timeout: *network_timeout,
+                    config,
+                }))
+            }
+            Command::ImportFrom {
+                database_path,
+                reader,
+                paths,
+                network_timeout,
+            } => {
+                let Profile {
+                    database_path,
+                    config,
+                    ..
+                } = get_profile(database_path, profile)?;
+                Ok(ValidatedOptions::ImportFrom(ImportFromOptions {
+                    database_path,
+                    reader: *reader,
+                    paths: paths.to_owned(),
+                    network_timeout: *network_timeout,
+                    config,
                 }))
             }
             Command::Export {
                 database_path,
                 opml_path,
             } => {
-                let database_path = get_database_path(database_path)?;
+                let database_path = get_profile(database_path, profile)?.database_path;
                 Ok(ValidatedOptions::Export(ExportOptions {
                     database_path,
                     opml_path: opml_path.to_owned(),
                 }))
             }
+            Command::ExportEntries {
+                database_path,
+                format,
+                output,
+                feeds,
+                since,
+                until,
+                read,
+                unread,
+                flagged,
+            } => {
+                let database_path = get_profile(database_path, profile)?.database_path;
+                Ok(ValidatedOptions::ExportEntries(ExportEntriesOptions {
+                    database_path,
+                    format: *format,
+                    output: output.to_owned(),
+                    filter: crate::exporters::EntryFilter {
+                        feeds: feeds.to_owned(),
+                        since: *since,
+                        until: *until,
+                        read: (*read || *unread).then_some(*read),
+                        flagged: *flagged,
+                    },
+                }))
+            }
+            Command::Scrape {
+                database_path,
+                url,
+                selectors,
+                network_timeout,
+            } => {
+                let Profile {
+                    database_path,
+                    config,
+                    ..
+                } = get_profile(database_path, profile)?;
+                Ok(ValidatedOptions::Scrape(ScrapeOptions {
+                    database_path,
+                    url: url.to_owned(),
+                    selectors: selectors.to_owned(),
+                    network_timeout: *network_timeout,
+                    config,
+                }))
+            }
+            Command::ScrapePreview {
+                database_path,
+                url,
+                selectors,
+                network_timeout,
+            } => {
+                let Profile {
+                    database_path,
+                    config,
+                    ..
+                } = get_profile(database_path, profile)?;
+                Ok(ValidatedOptions::ScrapePreview(ScrapePreviewOptions {
+                    database_path,
+                    url: url.to_owned(),
+                    selectors: selectors.to_owned(),
+                    network_timeout: *network_timeout,
+                    config,
+                }))
+            }
+            Command::Refresh {
+                database_path,
+                feeds,
+                jobs,
+                json,
+                max_failed,
+                network_timeout,
+            } => {
+                let Profile {
+                    database_path,
+                    config,
+                    ..
+                } = get_profile(database_path, profile)?;
+                Ok(ValidatedOptions::Refresh(RefreshOptions {
+                    database_path,
+                    feeds: feeds.to_owned(),
+                    jobs: jobs.unwrap_or_else(crate::io::default_refresh_workers),
+                    json: *json,
+                    max_failed: *max_failed,
+                    network_timeout: *network_timeout,
+                    config,
+                }))
+            }
+            Command::Db {
+                database_path,
+                command,
+            } => {
+                let database_path = get_profile(database_path, profile)?.database_path;
+                Ok(ValidatedOptions::Db(DbOptions {
+                    database_path,
+                    command: command.to_owned(),
+                }))
+            }
+            Command::Credentials {
+                database_path,
+                command,
+            } => {
+                let database_path = get_profile(database_path, profile)?.database_path;
+                Ok(ValidatedOptions::Credentials(CredentialsOptions {
+                    database_path,
+                    command: command.to_owned(),
+                }))
+            }
         }
     }
 }
@@ -143,20 +444,59 @@ fn parse_seconds(s: &str) -> Result<time::Duration, std::num::ParseIntError> {
     Ok(time::Duration::from_secs(as_u64))
 }
 
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(fraction) if (0.0..=1.0).contains(&fraction) => Ok(fraction),
+        _ => Err(format!("{s:?} is not a fraction between 0 and 1")),
+    }
+}
+
 /// internal, validated options for the normal reader mode
 #[derive(Debug)]
 enum ValidatedOptions {
     Read(ReadOptions),
     Import(ImportOptions),
+    ImportFrom(ImportFromOptions),
     Export(ExportOptions),
+    ExportEntries(ExportEntriesOptions),
+    Scrape(ScrapeOptions),
+    ScrapePreview(ScrapePreviewOptions),
+    Db(DbOptions),
+    Credentials(CredentialsOptions),
+    Refresh(RefreshOptions),
 }
 
 #[derive(Clone, Debug)]
 struct ReadOptions {
+    /// the name of the profile, `config::DEFAULT_PROFILE` without `--profile`
+    profile: String,
     database_path: PathBuf,
-    tick_rate: u64,
     flash_display_duration_seconds: time::Duration,
     network_timeout: time::Duration,
+    /// ignore the saved session
+    fresh: bool,
+    /// capture mouse events
+    mouse: bool,
+    config: crate::config::Config,
+}
+
+impl ReadOptions {
+    /// the same options for another profile, picking up changes to config.toml
+    fn with_profile(&self, profile: &str) -> Result<Self> {
+        let Profile {
+            name,
+            database_path,
+            config,
+        } = get_profile(&None, Some(profile))?;
+
+        Ok(ReadOptions {
+            profile: name,
+            database_path,
+            fresh: false,
+            config,
+            ..self.clone()
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -164,6 +504,16 @@ struct ImportOptions {
     database_path: PathBuf,
     opml_path: PathBuf,
     network_timeout: time::Duration,
+    config: crate::config::Config,
+}
+
+#[derive(Debug)]
+struct ImportFromOptions {
+    database_path: PathBuf,
+    reader: crate::importers::Reader,
+    paths: Vec<PathBuf>,
+    network_timeout: time::Duration,
+    config: crate::config::Config,
 }
 
 #[derive(Debug)]
@@ -172,36 +522,141 @@ pub struct ExportOptions {
     pub opml_path: PathBuf,
 }
 
-fn get_database_path(database_path: &Option<PathBuf>) -> std::io::Result<PathBuf> {
-    let database_path = if let Some(database_path) = database_path {
-        database_path.to_owned()
-    } else {
-        let mut database_path = directories::ProjectDirs::from("", "", "rss-tui")
-            .expect("unable to find home directory. if you like, you can provide a database path directly by passing the -d option.")
-            .data_local_dir()
-            .to_path_buf();
+#[derive(Debug)]
+struct ExportEntriesOptions {
+    database_path: PathBuf,
+    format: crate::exporters::EntryFormat,
+    output: Option<PathBuf>,
+    filter: crate::exporters::EntryFilter,
+}
+
+#[derive(Debug)]
+struct ScrapeOptions {
+    database_path: PathBuf,
+    url: String,
+    selectors: crate::scrape::ScrapeSelectors,
+    network_timeout: time::Duration,
+    config: crate::config::Config,
+}
+
+#[derive(Debug)]
+struct ScrapePreviewOptions {
+    database_path: PathBuf,
+    url: String,
+    selectors: crate::scrape::ScrapeSelectors,
+    network_timeout: time::Duration,
+    config: crate::config::Config,
+}
+
+#[derive(Debug)]
+struct DbOptions {
+    database_path: PathBuf,
+    command: crate::db::DbCommand,
+}
+
+#[derive(Debug)]
+struct CredentialsOptions {
+    database_path: PathBuf,
+    command: crate::credentials::CredentialsCommand,
+}
+
+#[derive(Debug)]
+struct RefreshOptions {
+    database_path: PathBuf,
+    /// feed ids or urls, every feed when empty
+    feeds: Vec<String>,
+    jobs: usize,
+    json: bool,
+    /// the fraction of feeds that may fail before the exit status says so
+    max_failed: f64,
+    network_timeout: time::Duration,
+    config: crate::config::Config,
+}
 
-        std::fs::create_dir_all(&database_path)?;
+struct Profile {
+    name: String,
+    database_path: PathBuf,
+    config: crate::config::Config,
+}
 
-        database_path.push("feeds.db");
+/// The database and settings of `profile`, or of the default profile.
+/// `-d` wins over the database of the profile
+fn get_profile(database_path: &Option<PathBuf>, profile: Option<&str>) -> Result<Profile> {
+    let name = profile.unwrap_or(crate::config::DEFAULT_PROFILE);
+    let config = crate::config::Config::load()?.for_profile(name)?;
 
-        database_path
+    let database_path = match database_path.clone().or_else(|| {
+        config
+            .profiles
+            .get(name)
+            .and_then(|profile| profile.database_path.clone())
+    }) {
+        Some(database_path) => database_path,
+        None => get_database_path(name)?,
     };
 
+    Ok(Profile {
+        name: name.to_string(),
+        database_path,
+        config,
+    })
+}
+
+/// where the profile's database goes when it is not configured
+fn get_database_path(profile: &str) -> std::io::Result<PathBuf> {
+    let mut database_path = directories::ProjectDirs::from("", "", "rss-tui")
+        .expect("unable to find home directory. if you like, you can provide a database path directly by passing the -d option.")
+        .data_local_dir()
+        .to_path_buf();
+
+    if profile != crate::config::DEFAULT_PROFILE {
+        database_path.push("profiles");
+        database_path.push(profile);
+    }
+
+    std::fs::create_dir_all(&database_path)?;
+
+    database_path.push("feeds.db");
+
     Ok(database_path)
 }
 
 pub enum Event<I> {
     Input(I),
-    Tick,
+    Mouse(MouseEvent),
+    /// the terminal changed size
+    Resize,
+    FocusGained,
+    FocusLost,
+    /// the io thread changed something on screen
+    StateChanged,
 }
 
 fn run_reader(options: ReadOptions) -> Result<()> {
+    // before the terminal is taken over, so a failed or refused migration can be read
+    rss::initialize_db(&mut rusqlite::Connection::open(&options.database_path)?)?;
+
     enable_raw_mode()?;
 
     let mut stdout = stdout();
 
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableFocusChange)?;
+
+    let mouse = options.mouse;
+    if mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
+
+    // give the terminal back before the panic message is printed, so it can be read.
+    // refresh workers catch their panics and report them as the feed's error,
+    // so those must not tear the reader down or print over it
+    let panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if std::thread::current().name() == Some("main") {
+            let _ = restore_terminal(mouse);
+            panic_hook(info);
+        }
+    }));
 
     let backend = CrosstermBackend::new(stdout);
 
@@ -213,23 +668,20 @@ fn run_reader(options: ReadOptions) -> Result<()> {
 
     let event_tx_clone = event_tx.clone();
 
-    let tick_rate = time::Duration::from_millis(options.tick_rate);
-
+    // blocks until there is input, so an idle reader uses no cpu
     thread::spawn(move || {
-        let mut last_tick = time::Instant::now();
         loop {
-            // poll for tick rate duration, if no events, sent tick event.
-            if event::poll(tick_rate - last_tick.elapsed())
-                .expect("Unable to poll for Crossterm event")
-                && let CEvent::Key(key) = event::read().expect("Unable to read Crossterm event")
-            {
-                event_tx
-                    .send(Event::Input(key))
-                    .expect("Unable to send Crossterm Key input event");
-            }
-            if last_tick.elapsed() >= tick_rate {
-                event_tx.send(Event::Tick).expect("Unable to send tick");
-                last_tick = time::Instant::now();
+            let event = match event::read().expect("Unable to read Crossterm event") {
+                CEvent::Key(key) => Event::Input(key),
+                CEvent::Mouse(mouse_event) => Event::Mouse(mouse_event),
+                CEvent::Resize(_, _) => Event::Resize,
+                CEvent::FocusGained => Event::FocusGained,
+                CEvent::FocusLost => Event::FocusLost,
+                CEvent::Paste(_) => continue,
+            };
+            // the reader has quit
+            if event_tx.send(event).is_err() {
+                break;
             }
         }
     });
@@ -248,7 +700,7 @@ fn run_reader(options: ReadOptions) -> Result<()> {
 
     // spawn this thread to handle receiving messages to performing blocking network and db IO
     let io_thread = thread::spawn(move || -> Result<()> {
-        io::io_loop(cloned_app, io_tx_clone, io_rx, &options_clone)
+        io::io_loop(cloned_app, io_tx_clone, io_rx, options_clone)
     });
 
     // this is basically "the Elm Architecture".
@@ -257,30 +709,72 @@ fn run_reader(options: ReadOptions) -> Result<()> {
     // ui <- current_state
     // action <- current_state + event
     // new_state <- current_state + action
-    loop {
-        app.draw(&mut terminal)?;
+    //
+    // the screen is only redrawn when an event could have changed it
+    app.draw(&mut terminal)?;
 
+    loop {
         let event = event_rx.recv()?;
 
-        let action = get_action(&app, event);
+        let mut needs_redraw = match &event {
+            // the next draw resizes to the new terminal size
+            Event::Resize => true,
+            Event::FocusGained => true,
+            // save where we are, in case the terminal is closed without quitting
+            Event::FocusLost => match app.save_session() {
+                Ok(()) => false,
+                Err(e) => {
+                    app.push_error_flash(e.context("Unable to save the session"));
+                    true
+                }
+            },
+            Event::StateChanged => true,
+            // key releases and mouse moves are not actions, and don't change anything
+            Event::Input(_) | Event::Mouse(_) => false,
+        };
 
-        if let Some(action) = action {
-            update(&mut app, action)?;
+        if let Some(action) = get_action(&app, event) {
+            // a broken feed or entry is shown in the error flash, instead of ending the reader
+            if let Err(e) = update(&mut app, action) {
+                app.push_error_flash(e);
+            }
+            needs_redraw = true;
         }
 
         if app.should_quit() {
             app.break_io_thread()?;
-            disable_raw_mode()?;
-            execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+            restore_terminal(mouse)?;
             terminal.show_cursor()?;
             break;
         }
+
+        if needs_redraw {
+            app.draw(&mut terminal)?;
+        }
     }
 
     io_thread
         .join()
         .expect("Unable to join IO thread to main thread")?;
 
+    app.save_session()?;
+
+    Ok(())
+}
+
+/// undoes what `run_reader` did to the terminal
+fn restore_terminal(mouse: bool) -> Result<()> {
+    disable_raw_mode()?;
+    let mut stdout = stdout();
+    if mouse {
+        execute!(stdout, DisableMouseCapture)?;
+    }
+    execute!(
+        stdout,
+        DisableFocusChange,
+        LeaveAlternateScreen,
+        crossterm::cursor::Show
+    )?;
     Ok(())
 }
 
@@ -294,13 +788,13 @@ enum Action {
     PageDown,
     RefreshAll,
     RefreshFeed,
+    CancelRefresh,
     ToggleHelp,
     ToggleReadMode,
     SetReadMode(modes::ReadMode),
     EnterEditingMode,
     OpenLinkInBrowser,
     CopyLinkToClipboard,
-    Tick,
     SubscribeToFeed,
     PushInputChar(char),
     DeleteInputChar,
@@ -316,6 +810,24 @@ enum Action {
     ToggleReadStatus,
     CycleTheme,
     ShowCombinedUnread,
+    ToggleDiagnostics,
+    ShowFeedHealth,
+    CycleSort,
+    MoveFeedUp,
+    MoveFeedDown,
+    ToggleHealthMark,
+    ExportUnhealthyFeeds,
+    ShowTrash,
+    RestoreFromTrash,
+    ShowProfiles,
+    SwitchProfile,
+    Undo,
+    MarkFeedRead,
+    CycleLayout,
+    ResizeFeedsPane(i16),
+    ResizeEntriesPane(i16),
+    Click { column: u16, row: u16 },
+    Scroll { down: bool },
 }
 
 fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
@@ -334,9 +846,11 @@ fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
                     }
                     (KeyCode::Char('r'), KeyModifiers::NONE) => match app.selected() {
                         Selected::Feeds => Some(Action::RefreshFeed),
+                        Selected::Trash => Some(Action::RestoreFromTrash),
                         _ => Some(Action::ToggleReadStatus),
                     },
                     (KeyCode::Char('x'), KeyModifiers::NONE) => Some(Action::RefreshAll),
+                    (KeyCode::Char('X'), _) => Some(Action::CancelRefresh),
                     (KeyCode::Left, _) | (KeyCode::Char('h'), _) => Some(Action::MoveLeft),
                     (KeyCode::Right, _) | (KeyCode::Char('l'), _) => Some(Action::MoveRight),
                     (KeyCode::Down, _) | (KeyCode::Char('j'), _) => Some(Action::MoveDown),
@@ -347,7 +861,11 @@ fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
                     (KeyCode::PageDown, _) | (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
                         Some(Action::PageDown)
                     }
+                    (KeyCode::Char('u'), _) => Some(Action::Undo),
                     (KeyCode::Enter, _) => match app.selected() {
+                        Selected::Health => Some(Action::MoveRight),
+                        Selected::Trash => Some(Action::RestoreFromTrash),
+                        Selected::Profiles => Some(Action::SwitchProfile),
                         Selected::Entries | Selected::Entry(_) | Selected::CombinedUnread => {
                             if app.has_entries() && app.has_current_entry() {
                                 Some(Action::SelectAndShowCurrentEntry)
@@ -368,11 +886,16 @@ fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
                     (KeyCode::Char('c'), _) => Some(Action::CopyLinkToClipboard),
                     (KeyCode::Char('o'), _) => Some(Action::OpenLinkInBrowser),
                     (KeyCode::Char('d'), _) => match app.selected() {
-                        Selected::Feeds => Some(Action::DeleteFeed),
+                        Selected::Feeds | Selected::Health | Selected::Trash => {
+                            Some(Action::DeleteFeed)
+                        }
                         _ => None,
                     },
                     (KeyCode::Char('n'), _) => Some(Action::CancelPendingDeletion),
-                    (KeyCode::Char('E'), _) => Some(Action::ExportFeeds),
+                    (KeyCode::Char('E'), _) => match app.selected() {
+                        Selected::Health => Some(Action::ExportUnhealthyFeeds),
+                        _ => Some(Action::ExportFeeds),
+                    },
                     (KeyCode::Char('1'), _) => {
                         Some(Action::SetReadMode(modes::ReadMode::ShowUnread))
                     }
@@ -380,11 +903,57 @@ fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
                     (KeyCode::Char('3'), _) => Some(Action::SetReadMode(modes::ReadMode::ShowRead)),
                     (KeyCode::Char('t'), _) => Some(Action::CycleTheme),
                     (KeyCode::Char('A'), _) => Some(Action::ShowCombinedUnread),
+                    (KeyCode::Char('H'), _) => Some(Action::ShowFeedHealth),
+                    (KeyCode::Char('T'), _) => Some(Action::ShowTrash),
+                    (KeyCode::Char('P'), _) => Some(Action::ShowProfiles),
+                    (KeyCode::Char('M'), _) => match app.selected() {
+                        Selected::Feeds | Selected::Entries => Some(Action::MarkFeedRead),
+                        _ => None,
+                    },
+                    (KeyCode::Char('s'), _) => Some(Action::CycleSort),
+                    (KeyCode::Char('K'), _) => match app.selected() {
+                        Selected::Feeds => Some(Action::MoveFeedUp),
+                        _ => None,
+                    },
+                    (KeyCode::Char('J'), _) => match app.selected() {
+                        Selected::Feeds => Some(Action::MoveFeedDown),
+                        _ => None,
+                    },
+                    (KeyCode::Char(' '), _) => match app.selected() {
+                        Selected::Health => Some(Action::ToggleHealthMark),
+                        _ => None,
+                    },
+                    (KeyCode::Char('L'), _) => Some(Action::CycleLayout),
+                    (KeyCode::Char('<'), _) => {
+                        Some(Action::ResizeFeedsPane(-modes::PaneRatios::STEP))
+                    }
+                    (KeyCode::Char('>'), _) => {
+                        Some(Action::ResizeFeedsPane(modes::PaneRatios::STEP))
+                    }
+                    (KeyCode::Char('['), _) => {
+                        Some(Action::ResizeEntriesPane(-modes::PaneRatios::STEP))
+                    }
+                    (KeyCode::Char(']'), _) => {
+                        Some(Action::ResizeEntriesPane(modes::PaneRatios::STEP))
+                    }
+                    (KeyCode::Char('D'), _) => match app.selected() {
+                        Selected::Feeds => Some(Action::ToggleDiagnostics),
+                        _ => None,
+                    },
                     _ => None,
                 }
             }
             Event::Input(_) => None,
-            Event::Tick => Some(Action::Tick),
+            Event::Mouse(mouse_event) => match mouse_event.kind {
+                MouseEventKind::Down(MouseButton::Left) => Some(Action::Click {
+                    column: mouse_event.column,
+                    row: mouse_event.row,
+                }),
+                MouseEventKind::ScrollDown => Some(Action::Scroll { down: true }),
+                MouseEventKind::ScrollUp => Some(Action::Scroll { down: false }),
+                _ => None,
+            },
+            Event::Resize | Event::FocusGained | Event::FocusLost | Event::StateChanged => None,
         },
         Mode::Editing => match event {
             Event::Input(key_event) if key_event.kind == KeyEventKind::Press => {
@@ -411,18 +980,18 @@ fn get_action(app: &App, event: Event<KeyEvent>) -> Option<Action> {
                     _ => None,
                 }
             }
-            Event::Input(_) => None,
-            Event::Tick => Some(Action::Tick),
+            Event::Input(_) | Event::Mouse(_) => None,
+            Event::Resize | Event::FocusGained | Event::FocusLost | Event::StateChanged => None,
         },
     }
 }
 
 fn update(app: &mut App, action: Action) -> Result<()> {
     match action {
-        Action::Tick => (),
         Action::Quit => app.set_should_quit(true),
         Action::RefreshAll => app.refresh_feeds()?,
         Action::RefreshFeed => app.refresh_feed()?,
+        Action::CancelRefresh => app.cancel_refresh()?,
         Action::MoveLeft => app.on_left()?,
         Action::MoveDown => app.on_down()?,
         Action::MoveUp => app.on_up()?,
@@ -445,6 +1014,24 @@ fn update(app: &mut App, action: Action) -> Result<()> {
         Action::DeleteFeed => app.delete_feed()?,
         Action::CancelPendingDeletion => app.cancel_pending_deletion(),
         Action::ExportFeeds => app.export_feeds()?,
+        Action::ToggleDiagnostics => app.toggle_diagnostics()?,
+        Action::ShowFeedHealth => app.show_feed_health()?,
+        Action::CycleSort => app.cycle_sort()?,
+        Action::MoveFeedUp => app.move_feed_up()?,
+        Action::MoveFeedDown => app.move_feed_down()?,
+        Action::ToggleHealthMark => app.toggle_health_mark(),
+        Action::ExportUnhealthyFeeds => app.export_unhealthy_feeds()?,
+        Action::ShowTrash => app.show_trash()?,
+        Action::RestoreFromTrash => app.restore_from_trash()?,
+        Action::ShowProfiles => app.show_profiles()?,
+        Action::SwitchProfile => app.switch_profile()?,
+        Action::Undo => app.undo()?,
+        Action::MarkFeedRead => app.mark_feed_read()?,
+        Action::CycleLayout => app.cycle_layout(),
+        Action::ResizeFeedsPane(delta) => app.resize_feeds_pane(delta),
+        Action::ResizeEntriesPane(delta) => app.resize_entries_pane(delta),
+        Action::Click { column, row } => app.on_click(column, row)?,
+        Action::Scroll { down } => app.on_scroll(down)?,
         Action::EmailArticle => app.email_article()?,
         Action::RenameFeed => app.start_rename_feed()?,
         Action::ConfirmRenameFeed => app.confirm_rename_feed()?,
@@ -0,0 +1,354 @@
+// the database schema, as an ordered list of migrations
+
+use anyhow::{Context, Result, bail};
+use std::path::PathBuf;
+
+/// One step of the schema. `MIGRATIONS[n]` takes a database from `user_version` `n` to `n + 1`.
+struct Migration {
+    name: &'static str,
+    up: fn(&rusqlite::Transaction) -> rusqlite::Result<()>,
+}
+
+/// Every schema change rss-tui has made, oldest first.
+/// Only ever append: released databases are at every version in between
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        name: "create feeds and entries",
+        up: |tx| {
+            tx.execute_batch(
+                "CREATE TABLE IF NOT EXISTS feeds (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        title TEXT,
+        feed_link TEXT,
+        link TEXT,
+        feed_kind TEXT,
+        refreshed_at TIMESTAMP,
+        inserted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS entries (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        feed_id INTEGER,
+        title TEXT,
+        author TEXT,
+        pub_date TIMESTAMP,
+        description TEXT,
+        content TEXT,
+        link TEXT,
+        read_at TIMESTAMP,
+        inserted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE INDEX IF NOT EXISTS entries_feed_id_and_pub_date_and_inserted_at_index
+        ON entries (feed_id, pub_date, inserted_at);",
+            )
+        },
+    },
+    Migration {
+        name: "add feeds.latest_etag",
+        up: |tx| tx.execute_batch("ALTER TABLE feeds ADD COLUMN latest_etag TEXT"),
+    },
+    Migration {
+        name: "make feed links unique",
+        up: |tx| {
+            tx.execute_batch(
+                "CREATE UNIQUE INDEX IF NOT EXISTS feeds_feed_link ON feeds (feed_link)",
+            )
+        },
+    },
+    Migration {
+        name: "add feeds.parse_warnings",
+        up: |tx| tx.execute_batch("ALTER TABLE feeds ADD COLUMN parse_warnings TEXT"),
+    },
+    Migration {
+        name: "add feeds.scrape_selectors",
+        up: |tx| tx.execute_batch("ALTER TABLE feeds ADD COLUMN scrape_selectors TEXT"),
+    },
+    Migration {
+        name: "create refresh_log",
+        up: |tx| {
+            tx.execute_batch(
+                "CREATE TABLE IF NOT EXISTS refresh_log (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        feed_id INTEGER,
+        attempted_at TIMESTAMP,
+        http_status INTEGER,
+        bytes INTEGER,
+        duration_ms INTEGER,
+        new_entries INTEGER,
+        error TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS refresh_log_feed_id_index ON refresh_log (feed_id);",
+            )
+        },
+    },
+    Migration {
+        name: "add feeds.deleted_at",
+        up: |tx| tx.execute_batch("ALTER TABLE feeds ADD COLUMN deleted_at TIMESTAMP"),
+    },
+    Migration {
+        name: "create session_state, add entries.scroll_position",
+        up: |tx| {
+            tx.execute_batch(
+                "CREATE TABLE IF NOT EXISTS session_state (
+        key TEXT PRIMARY KEY,
+        value TEXT
+        );
+
+        ALTER TABLE entries ADD COLUMN scroll_position INTEGER;",
+            )
+        },
+    },
+    Migration {
+        name: "add feeds.entry_sort and feeds.position",
+        up: |tx| {
+            tx.execute_batch(
+                "ALTER TABLE feeds ADD COLUMN entry_sort TEXT;
+        ALTER TABLE feeds ADD COLUMN position INTEGER;",
+            )
+        },
+    },
+    Migration {
+        name: "add entries.guid and entries.flagged_at, create feed_tags",
+        up: |tx| {
+            tx.execute_batch(
+                "ALTER TABLE entries ADD COLUMN guid TEXT;
+        ALTER TABLE entries ADD COLUMN flagged_at TIMESTAMP;
+
+        CREATE TABLE IF NOT EXISTS feed_tags (
+        feed_id INTEGER,
+        tag TEXT,
+        PRIMARY KEY (feed_id, tag)
+        );",
+            )
+        },
+    },
+];
+
+/// the schema version this rss-tui writes
+pub const LATEST_VERSION: usize = MIGRATIONS.len();
+
+pub fn schema_version(conn: &rusqlite::Connection) -> Result<usize> {
+    let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    Ok(version.max(0) as usize)
+}
+
+/// Brings the database up to `LATEST_VERSION`.
+/// An existing database is backed up next to itself first, and all pending migrations
+/// run in one transaction, so a failed migration leaves the database as it was.
+/// Returns the backup, if there was anything to migrate
+pub fn migrate(conn: &mut rusqlite::Connection) -> Result<Option<PathBuf>> {
+    migrate_to(conn, LATEST_VERSION, true)
+}
+
+fn migrate_to(
+    conn: &mut rusqlite::Connection,
+    target: usize,
+    backup: bool,
+) -> Result<Option<PathBuf>> {
+    let version = schema_version(conn)?;
+    refuse_newer_versions(conn, version)?;
+
+    if version >= target {
+        return Ok(None);
+    }
+
+    // a brand new database has nothing to lose
+    let backup_path = match database_path(conn) {
+        Some(path) if backup && version > 0 => Some(crate::db::backup_next_to(
+            conn,
+            &path,
+            &format!("v{version}"),
+        )?),
+        _ => None,
+    };
+
+    let tx = conn.transaction()?;
+    for (from, migration) in MIGRATIONS.iter().enumerate().take(target).skip(version) {
+        (migration.up)(&tx).with_context(|| {
+            format!(
+                "Migration {} ({}) failed, the database is unchanged",
+                from + 1,
+                migration.name
+            )
+        })?;
+    }
+    tx.pragma_update(None, "user_version", target as i64)?;
+    tx.commit()?;
+
+    Ok(backup_path)
+}
+
+fn refuse_newer_versions(conn: &rusqlite::Connection, version: usize) -> Result<()> {
+    if version > LATEST_VERSION {
+        bail!(
+            "The database{} is at schema version {version}, from a newer rss-tui. \
+            This rss-tui only knows schema versions up to {LATEST_VERSION}; \
+            upgrade rss-tui to open it.",
+            database_path(conn)
+                .map(|path| format!(" {path:?}"))
+                .unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that the database is one rss-tui wrote, with every table and column
+/// its schema version should have. Returns the schema version
+pub fn validate_schema(conn: &rusqlite::Connection) -> Result<usize> {
+    let version = schema_version(conn)?;
+    if version == 0 {
+        bail!("Not an rss-tui database, it has no schema version");
+    }
+    refuse_newer_versions(conn, version)?;
+
+    let mut expected = rusqlite::Connection::open_in_memory()?;
+    migrate_to(&mut expected, version, false)?;
+
+    let actual = columns(conn)?;
+    let missing = columns(&expected)?
+        .into_iter()
+        .filter(|column| !actual.contains(column))
+        .map(|(table, column)| format!("{table}.{column}"))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        bail!(
+            "Not an rss-tui database at schema version {version}, missing {}",
+            missing.join(", ")
+        );
+    }
+
+    Ok(version)
+}
+
+/// every `(table, column)`
+fn columns(conn: &rusqlite::Connection) -> Result<Vec<(String, String)>> {
+    let mut statement = conn.prepare(
+        "SELECT m.name, p.name
+        FROM sqlite_master m, pragma_table_info(m.name) p
+        WHERE m.type = 'table'
+        ORDER BY m.name, p.cid",
+    )?;
+    let columns = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    Ok(columns)
+}
+
+/// the file behind the connection, `None` for in-memory databases
+fn database_path(conn: &rusqlite::Connection) -> Option<PathBuf> {
+    conn.path()
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexes(conn: &rusqlite::Connection) -> Vec<String> {
+        let mut statement = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'index' ORDER BY name")
+            .unwrap();
+        statement
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn migrates_from_every_schema_version() {
+        let mut latest = rusqlite::Connection::open_in_memory().unwrap();
+        migrate(&mut latest).unwrap();
+        assert_eq!(schema_version(&latest).unwrap(), LATEST_VERSION);
+
+        for version in 0..LATEST_VERSION {
+            let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+            migrate_to(&mut conn, version, false).unwrap();
+            assert_eq!(schema_version(&conn).unwrap(), version);
+
+            if version > 0 {
+                conn.execute(
+                    "INSERT INTO feeds (title, feed_link) VALUES ('old', 'https://example.com/feed')",
+                    [],
+                )
+                .unwrap();
+            }
+
+            migrate(&mut conn).unwrap();
+
+            assert_eq!(
+                schema_version(&conn).unwrap(),
+                LATEST_VERSION,
+                "from {version}"
+            );
+            assert_eq!(
+                columns(&conn).unwrap(),
+                columns(&latest).unwrap(),
+                "from {version}"
+            );
+            assert_eq!(indexes(&conn), indexes(&latest), "from {version}");
+            if version > 0 {
+                let title: String = conn
+                    .query_row("SELECT title FROM feeds", [], |row| row.get(0))
+                    .unwrap();
+                assert_eq!(title, "old", "from {version}");
+            }
+        }
+    }
+
+    #[test]
+    fn refuses_databases_from_newer_versions() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", LATEST_VERSION as i64 + 1)
+            .unwrap();
+
+        let error = migrate(&mut conn).unwrap_err().to_string();
+        assert!(error.contains("newer rss-tui"), "{error}");
+    }
+
+    #[test]
+    fn backs_up_before_migrating() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-migrations-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("feeds.db");
+
+        let mut conn = rusqlite::Connection::open(&path).unwrap();
+        migrate_to(&mut conn, 1, false).unwrap();
+        conn.execute("INSERT INTO feeds (title) VALUES ('kept')", [])
+            .unwrap();
+        migrate(&mut conn).unwrap();
+        // nothing to migrate, no second backup
+        migrate(&mut conn).unwrap();
+
+        let backups = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "bak"))
+            .collect::<Vec<_>>();
+        assert_eq!(backups.len(), 1);
+        assert!(
+            backups[0]
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("feeds.db.v1-")
+        );
+
+        let backup = rusqlite::Connection::open(&backups[0]).unwrap();
+        assert_eq!(schema_version(&backup).unwrap(), 1);
+        let title: String = backup
+            .query_row("SELECT title FROM feeds", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(title, "kept");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
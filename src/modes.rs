@@ -8,6 +8,12 @@ pub enum Selected {
     Entry(crate::rss::EntryMetadata),
     /// combined view of all unread entries across feeds ("[feed-name]: title")
     CombinedUnread,
+    /// dashboard of every feed by health: errors, staleness, posting frequency
+    Health,
+    /// deleted feeds, restorable until they are purged
+    Trash,
+    /// the profiles in config.toml, to switch databases
+    Profiles,
     None,
 }
 
@@ -17,9 +23,209 @@ pub enum Mode {
     Normal,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum ReadMode {
     ShowRead,
     ShowUnread,
     All,
 }
+
+/// how the feed health dashboard is ordered
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HealthSort {
+    /// erroring, then dead, then stale, then healthy
+    Status,
+    ConsecutiveErrors,
+    DaysSinceLastEntry,
+    Frequency,
+    Title,
+}
+
+impl HealthSort {
+    pub fn next(self) -> Self {
+        match self {
+            HealthSort::Status => HealthSort::ConsecutiveErrors,
+            HealthSort::ConsecutiveErrors => HealthSort::DaysSinceLastEntry,
+            HealthSort::DaysSinceLastEntry => HealthSort::Frequency,
+            HealthSort::Frequency => HealthSort::Title,
+            HealthSort::Title => HealthSort::Status,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HealthSort::Status => "status",
+            HealthSort::ConsecutiveErrors => "errors",
+            HealthSort::DaysSinceLastEntry => "last entry",
+            HealthSort::Frequency => "frequency",
+            HealthSort::Title => "title",
+        }
+    }
+}
+
+/// how the feeds list is ordered
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum FeedSort {
+    Title,
+    /// most unread entries first
+    UnreadCount,
+    /// the feed with the newest entry first
+    LastEntry,
+    /// most recently refreshed first
+    LastRefresh,
+    /// the order feeds were moved into with `J`/`K`
+    Manual,
+}
+
+impl FeedSort {
+    pub fn next(self) -> Self {
+        match self {
+            FeedSort::Title => FeedSort::UnreadCount,
+            FeedSort::UnreadCount => FeedSort::LastEntry,
+            FeedSort::LastEntry => FeedSort::LastRefresh,
+            FeedSort::LastRefresh => FeedSort::Manual,
+            FeedSort::Manual => FeedSort::Title,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FeedSort::Title => "title",
+            FeedSort::UnreadCount => "unread",
+            FeedSort::LastEntry => "last entry",
+            FeedSort::LastRefresh => "last refresh",
+            FeedSort::Manual => "manual",
+        }
+    }
+}
+
+/// how entries are ordered, set per feed for the entries list
+/// and once for the combined unread view.
+/// Stored by `id`, the aliases read sessions saved before that
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntrySort {
+    #[default]
+    #[serde(alias = "NewestFirst")]
+    NewestFirst,
+    /// for serialized fiction and podcasts, read from the start
+    #[serde(alias = "OldestFirst")]
+    OldestFirst,
+    #[serde(alias = "Title")]
+    Title,
+    /// grouped by feed title, only meaningful in the combined unread view
+    #[serde(alias = "Feed")]
+    Feed,
+}
+
+impl EntrySort {
+    /// `with_feed` includes sorting by feed, for views that mix feeds
+    pub fn next(self, with_feed: bool) -> Self {
+        match self {
+            EntrySort::NewestFirst => EntrySort::OldestFirst,
+            EntrySort::OldestFirst => EntrySort::Title,
+            EntrySort::Title if with_feed => EntrySort::Feed,
+            EntrySort::Title | EntrySort::Feed => EntrySort::NewestFirst,
+        }
+    }
+
+    /// for showing, see `id` for storing
+    pub fn name(self) -> &'static str {
+        match self {
+            EntrySort::NewestFirst => "newest first",
+            EntrySort::OldestFirst => "oldest first",
+            EntrySort::Title => "title",
+            EntrySort::Feed => "feed",
+        }
+    }
+
+    /// what is stored in `feeds.entry_sort`, the same as its serde name
+    pub fn id(self) -> &'static str {
+        match self {
+            EntrySort::NewestFirst => "newest_first",
+            EntrySort::OldestFirst => "oldest_first",
+            EntrySort::Title => "title",
+            EntrySort::Feed => "feed",
+        }
+    }
+}
+
+impl std::str::FromStr for EntrySort {
+    type Err = anyhow::Error;
+
+    /// the `id`, or the `name` that earlier versions stored
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newest_first" | "newest first" => Ok(EntrySort::NewestFirst),
+            "oldest_first" | "oldest first" => Ok(EntrySort::OldestFirst),
+            "title" => Ok(EntrySort::Title),
+            "feed" => Ok(EntrySort::Feed),
+            _ => Err(anyhow::anyhow!("{s} is not a valid entry sort")),
+        }
+    }
+}
+
+/// how the screen is split between the feeds column, the entries and the article
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum PaneLayout {
+    /// feeds on the left, entries or the article on the right
+    #[default]
+    TwoPane,
+    /// feeds | entries | a preview of the selected entry
+    ThreePane,
+    /// feeds above entries, for narrow terminals
+    Stacked,
+    /// like two panes, but the feeds column is hidden while reading
+    Zen,
+}
+
+impl PaneLayout {
+    pub fn next(self) -> Self {
+        match self {
+            PaneLayout::TwoPane => PaneLayout::ThreePane,
+            PaneLayout::ThreePane => PaneLayout::Stacked,
+            PaneLayout::Stacked => PaneLayout::Zen,
+            PaneLayout::Zen => PaneLayout::TwoPane,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PaneLayout::TwoPane => "two pane",
+            PaneLayout::ThreePane => "three pane",
+            PaneLayout::Stacked => "stacked",
+            PaneLayout::Zen => "zen",
+        }
+    }
+}
+
+/// pane sizes in percent, changed with `<`/`>` and `[`/`]`
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct PaneRatios {
+    /// the feeds column's share of the width, or of the height when stacked
+    pub feeds: u16,
+    /// the entries list's share of what the feeds leave, in the three pane layout
+    pub entries: u16,
+}
+
+impl Default for PaneRatios {
+    fn default() -> Self {
+        Self {
+            feeds: 30,
+            entries: 40,
+        }
+    }
+}
+
+impl PaneRatios {
+    pub const STEP: i16 = 5;
+
+    pub fn resize_feeds(&mut self, delta: i16) {
+        self.feeds = self.feeds.saturating_add_signed(delta).clamp(10, 70);
+    }
+
+    pub fn resize_entries(&mut self, delta: i16) {
+        self.entries = self.entries.saturating_add_signed(delta).clamp(20, 80);
+    }
+}
@@ -1,24 +1,36 @@
 // import and export OPML of feeds
 
+use crate::credentials::CredentialStore;
+use crate::rss::FeedId;
+use crate::scrape::ScrapeSelectors;
 use crate::{ExportOptions, ImportOptions};
 use anyhow::{Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+
+/// The outline attribute a scraped feed's selectors are kept in, as JSON.
+/// OPML 2.0 allows any attribute on an outline, so other readers just ignore it
+const SCRAPE_SELECTORS_ATTRIBUTE: &str = "rssTuiScrapeSelectors";
 
 pub(crate) fn import(options: ImportOptions) -> Result<()> {
-    let mut conn = rusqlite::Connection::open(options.database_path)?;
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
 
     crate::rss::initialize_db(&mut conn)?;
 
-    let opml_file =
-        std::fs::File::open(options.opml_path).context("must provide a valid OPML file")?;
+    let mut credentials = CredentialStore::for_database(&options.database_path)?;
+    let mut credentials_changed = false;
 
-    let mut opml_reader = std::io::BufReader::new(opml_file);
+    let opml_xml =
+        std::fs::read_to_string(options.opml_path).context("must provide a valid OPML file")?;
 
     let opml_document =
-        opml::OPML::from_reader(&mut opml_reader).context("unable to parse provided OPML file")?;
+        opml::OPML::from_str(&opml_xml).context("unable to parse provided OPML file")?;
+    let scrape_selectors =
+        read_scrape_selectors(&opml_xml).context("unable to parse provided OPML file")?;
 
-    let http_client = ureq::AgentBuilder::new()
-        .timeout_read(options.network_timeout)
-        .build();
+    let http_client =
+        crate::http::HttpClient::new(options.network_timeout, &options.config.network)?;
+    http_client.print_warnings();
 
     let feed_urls = get_feed_urls(&opml_document);
 
@@ -27,11 +39,48 @@ pub(crate) fn import(options: ImportOptions) -> Result<()> {
 
     for feed_url in feed_urls {
         eprintln!(">>>>>>>>>>");
+
+        let selectors = scrape_selectors.get(&feed_url);
+
+        // keep any `user:pass@` in the OPML out of the feeds table
+        let (feed_url, basic_auth) = match crate::credentials::split_url_credentials(&feed_url) {
+            Ok(url_and_credentials) => url_and_credentials,
+            Err(e) => {
+                eprintln!("ERROR: {e:?}");
+                failed_imports.push(feed_url);
+                eprintln!("<<<<<<<<<<");
+                continue;
+            }
+        };
+        // an OPML file from elsewhere should not be able to run commands on refresh
+        if crate::source::FeedSource::parse(&feed_url).is_ok_and(|source| source.runs_command()) {
+            eprintln!("{feed_url}: skipped, feeds that run commands can't be imported");
+            failed_imports.push(feed_url);
+            eprintln!("<<<<<<<<<<");
+            continue;
+        }
+
+        let has_basic_auth = basic_auth.is_some();
+        if let Some(basic_auth) = basic_auth {
+            credentials.set_basic(&feed_url, basic_auth);
+        }
+
         eprintln!("{feed_url}: starting import");
-        match crate::rss::subscribe_to_feed(&http_client, &mut conn, &feed_url) {
+        let subscribed = match selectors {
+            Some(selectors) => crate::rss::subscribe_to_scraped_feed(
+                &http_client,
+                &mut conn,
+                &credentials,
+                &feed_url,
+                selectors,
+            ),
+            None => crate::rss::subscribe_to_feed(&http_client, &mut conn, &credentials, &feed_url),
+        };
+        match subscribed {
             Ok(_feed_id) => {
                 eprintln!("{feed_url}: OK");
                 successful_imports += 1;
+                credentials_changed |= has_basic_auth;
             }
             Err(e) => {
                 eprintln!("ERROR: {e:?}");
@@ -41,6 +90,10 @@ pub(crate) fn import(options: ImportOptions) -> Result<()> {
         eprintln!("<<<<<<<<<<");
     }
 
+    if credentials_changed {
+        credentials.save()?;
+    }
+
     eprintln!();
     eprintln!("{successful_imports} feeds imported");
     eprintln!("{} feeds failed to import", failed_imports.len());
@@ -73,12 +126,109 @@ fn get_feed_urls(opml_document: &opml::OPML) -> Vec<String> {
     feed_urls
 }
 
+/// the selectors of the scraped feeds in the document, by their `xmlUrl`
+fn read_scrape_selectors(opml_xml: &str) -> Result<HashMap<String, ScrapeSelectors>> {
+    let mut reader = quick_xml::Reader::from_str(opml_xml);
+    let mut selectors = HashMap::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"outline" => {
+                if let (Some(xml_url), Some(json)) = (
+                    attribute(&e, "xmlUrl")?,
+                    attribute(&e, SCRAPE_SELECTORS_ATTRIBUTE)?,
+                ) {
+                    let feed_selectors = serde_json::from_str(&json)
+                        .with_context(|| format!("invalid scrape selectors for {xml_url}"))?;
+                    selectors.insert(xml_url, feed_selectors);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+
+    Ok(selectors)
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Result<Option<String>> {
+    match e.try_get_attribute(name)? {
+        Some(attribute) => {
+            let value = std::str::from_utf8(&attribute.value)?;
+            Ok(Some(quick_xml::escape::unescape(value)?.into_owned()))
+        }
+        None => Ok(None),
+    }
+}
+
+/// adds the selectors to the outlines of scraped feeds, found by their `xmlUrl`
+fn write_scrape_selectors(opml_xml: &str, selectors: &HashMap<String, String>) -> Result<String> {
+    let mut reader = quick_xml::Reader::from_str(opml_xml);
+    let mut writer = quick_xml::Writer::new(vec![]);
+
+    let with_selectors = |e: BytesStart| -> Result<BytesStart> {
+        let json = attribute(&e, "xmlUrl")?.and_then(|xml_url| selectors.get(&xml_url));
+        let mut e = e.into_owned();
+        if let Some(json) = json {
+            e.push_attribute((SCRAPE_SELECTORS_ATTRIBUTE, json.as_str()));
+        }
+        Ok(e)
+    };
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == b"outline" => {
+                writer.write_event(Event::Start(with_selectors(e)?))?
+            }
+            Event::Empty(e) if e.local_name().as_ref() == b"outline" => {
+                writer.write_event(Event::Empty(with_selectors(e)?))?
+            }
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+    }
+
+    Ok(String::from_utf8(writer.into_inner())?)
+}
+
 pub(crate) fn export(options: ExportOptions) -> Result<()> {
     let mut conn = rusqlite::Connection::open(&options.database_path)?;
 
     crate::rss::initialize_db(&mut conn)?;
 
     let feeds = crate::rss::get_feeds(&conn)?;
+    let feeds_len = feeds.len();
+    let tags = crate::rss::get_feed_tags(&conn)?;
+    let selectors = crate::rss::get_scrape_selectors(&conn)?;
+
+    write_feeds(
+        feeds,
+        &tags,
+        &selectors,
+        "rss-tui Feed Export",
+        &options.opml_path,
+    )?;
+
+    eprintln!("Exported {} feeds to {:?}", feeds_len, options.opml_path);
+
+    Ok(())
+}
+
+/// Writes `feeds` to an OPML document at `opml_path`, with their `tags` as categories
+/// and the `selectors` of scraped feeds, so importing it scrapes them the same way
+pub(crate) fn write_feeds(
+    feeds: Vec<crate::rss::Feed>,
+    tags: &HashMap<FeedId, Vec<String>>,
+    selectors: &HashMap<FeedId, ScrapeSelectors>,
+    title: &str,
+    opml_path: &std::path::Path,
+) -> Result<()> {
+    let mut selectors_by_url = HashMap::new();
+    for feed in &feeds {
+        if let (Some(feed_link), Some(selectors)) = (&feed.feed_link, selectors.get(&feed.id)) {
+            selectors_by_url.insert(feed_link.clone(), serde_json::to_string(selectors)?);
+        }
+    }
 
     // create outlines for each feed
     let outlines: Vec<opml::Outline> = feeds
@@ -91,6 +241,7 @@ pub(crate) fn export(options: ExportOptions) -> Result<()> {
                 r#type: Some("rss".to_string()),
                 xml_url: Some(feed_link),
                 html_url: feed.link,
+                category: tags.get(&feed.id).map(|tags| tags.join(",")),
                 outlines: vec![],
                 ..Default::default()
             })
@@ -101,27 +252,76 @@ pub(crate) fn export(options: ExportOptions) -> Result<()> {
     let opml_doc = opml::OPML {
         version: "2.0".to_string(),
         head: Some(opml::Head {
-            title: Some("rss-tui Feed Export".to_string()),
+            title: Some(title.to_string()),
             date_created: Some(chrono::Utc::now().to_rfc3339()),
             ..Default::default()
         }),
         body: opml::Body { outlines },
     };
 
-    // write to file
-    let opml_file = std::fs::File::create(&options.opml_path)
-        .with_context(|| format!("unable to create OPML file at {:?}", options.opml_path))?;
-
-    let mut opml_writer = std::io::BufWriter::new(opml_file);
-    opml_doc
-        .to_writer(&mut opml_writer)
+    let opml_xml = opml_doc
+        .to_string()
+        .with_context(|| "unable to write OPML document")?;
+    let opml_xml = write_scrape_selectors(&opml_xml, &selectors_by_url)
         .with_context(|| "unable to write OPML document")?;
 
-    eprintln!(
-        "Exported {} feeds to {:?}",
-        opml_doc.body.outlines.len(),
-        options.opml_path
-    );
+    std::fs::write(opml_path, opml_xml)
+        .with_context(|| format!("unable to create OPML file at {:?}", opml_path))?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrape_selectors_round_trip() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-opml-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let opml_path = dir.join("feeds.opml");
+
+        let selectors = ScrapeSelectors {
+            item: "ul.posts > li".to_string(),
+            title: Some("h2 \"quoted\"".to_string()),
+            ..Default::default()
+        };
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+        conn.execute(
+            "INSERT INTO feeds (title, feed_link, feed_kind, scrape_selectors) VALUES
+                ('news', 'https://example.com/news?a=1&b=2', 'Scraped', ?1),
+                ('feed', 'https://example.com/feed.xml', 'RSS', NULL)",
+            [serde_json::to_string(&selectors).unwrap()],
+        )
+        .unwrap();
+
+        write_feeds(
+            crate::rss::get_feeds(&conn).unwrap(),
+            &HashMap::new(),
+            &crate::rss::get_scrape_selectors(&conn).unwrap(),
+            "test",
+            &opml_path,
+        )
+        .unwrap();
+
+        let opml_xml = std::fs::read_to_string(&opml_path).unwrap();
+        let document = opml::OPML::from_str(&opml_xml).unwrap();
+        let mut feed_urls = get_feed_urls(&document);
+        feed_urls.sort();
+        assert_eq!(
+            feed_urls,
+            vec![
+                "https://example.com/feed.xml".to_string(),
+                "https://example.com/news?a=1&b=2".to_string(),
+            ]
+        );
+        assert_eq!(
+            read_scrape_selectors(&opml_xml).unwrap(),
+            HashMap::from([("https://example.com/news?a=1&b=2".to_string(), selectors)])
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
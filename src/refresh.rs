@@ -0,0 +1,233 @@
+// refreshing feeds without the reader: `rss-tui refresh`, for cron and systemd timers
+
+use crate::RefreshOptions;
+use crate::rss::FeedId;
+use anyhow::{Result, bail};
+use std::path::Path;
+
+/// How one feed's refresh went
+#[derive(Debug, serde::Serialize)]
+struct FeedResult {
+    id: FeedId,
+    title: Option<String>,
+    url: Option<String>,
+    ok: bool,
+    new_entries: usize,
+    http_status: Option<u16>,
+    duration_ms: u128,
+    error: Option<String>,
+}
+
+/// What `--json` prints
+#[derive(Debug, serde::Serialize)]
+struct Summary {
+    feeds: Vec<FeedResult>,
+    total: usize,
+    failed: usize,
+    new_entries: usize,
+    elapsed_ms: u128,
+}
+
+pub(crate) fn run(options: RefreshOptions) -> Result<()> {
+    let mut conn = crate::db::open_existing(&options.database_path)?;
+
+    let Some(_lock) = lock(&options.database_path)? else {
+        eprintln!(
+            "Another refresh of {:?} is still running, skipping this one",
+            options.database_path
+        );
+        return Ok(());
+    };
+
+    crate::rss::initialize_db(&mut conn)?;
+
+    let feed_ids = if options.feeds.is_empty() {
+        crate::rss::get_feed_ids(&conn)?
+    } else {
+        options
+            .feeds
+            .iter()
+            .map(|feed| crate::rss::find_feed_id(&conn, feed))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let http_client =
+        crate::http::HttpClient::new(options.network_timeout, &options.config.network)?;
+    http_client.print_warnings();
+
+    let started = std::time::Instant::now();
+    let mut feeds = vec![];
+    crate::io::refresh_feeds(
+        &options.database_path,
+        &http_client,
+        &options.config.refresh,
+        options.jobs,
+        feed_ids,
+        |feed_id, result| {
+            let feed = feed_result(&conn, feed_id, result)?;
+            if !options.json {
+                print_feed_result(&feed);
+            }
+            feeds.push(feed);
+            Ok(())
+        },
+    )?;
+
+    let summary = Summary {
+        total: feeds.len(),
+        failed: feeds.iter().filter(|feed| !feed.ok).count(),
+        new_entries: feeds.iter().map(|feed| feed.new_entries).sum(),
+        elapsed_ms: started.elapsed().as_millis(),
+        feeds,
+    };
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        println!(
+            "Refreshed {}/{} feeds in {:?}, {} new entries",
+            summary.total - summary.failed,
+            summary.total,
+            started.elapsed(),
+            summary.new_entries
+        );
+    }
+
+    if failed_too_many(summary.failed, summary.total, options.max_failed) {
+        bail!(
+            "{} of {} feeds failed to refresh, more than --max-failed {} allows",
+            summary.failed,
+            summary.total,
+            options.max_failed
+        );
+    }
+
+    Ok(())
+}
+
+/// Takes `<database>.refresh.lock`, `None` when another refresh holds it,
+/// here or in the reader. The lock goes away with the process, however it ends
+pub(crate) fn lock(database_path: &Path) -> Result<Option<std::fs::File>> {
+    crate::db::try_lock(database_path, "refresh")
+}
+
+/// the feed with the details of its refresh from the refresh log
+fn feed_result(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    result: Result<()>,
+) -> Result<FeedResult> {
+    let feed = crate::rss::get_feed(conn, feed_id)?;
+    let attempt = crate::rss::get_refresh_log(conn, feed_id, 1)?
+        .into_iter()
+        .next();
+
+    Ok(FeedResult {
+        id: feed_id,
+        title: feed.title,
+        url: feed.feed_link,
+        ok: result.is_ok(),
+        new_entries: attempt.as_ref().map_or(0, |attempt| attempt.new_entries),
+        http_status: attempt.as_ref().and_then(|attempt| attempt.http_status),
+        duration_ms: attempt.map_or(0, |attempt| attempt.duration.as_millis()),
+        // the whole chain on one line
+        error: result.err().map(|e| format!("{e:#}")),
+    })
+}
+
+fn print_feed_result(feed: &FeedResult) {
+    let name = feed
+        .title
+        .as_deref()
+        .or(feed.url.as_deref())
+        .unwrap_or_default();
+    match &feed.error {
+        None => println!(
+            "ok     {:>4} new {:>6}ms  {name}",
+            feed.new_entries, feed.duration_ms
+        ),
+        Some(error) => println!("error  {name}: {error}"),
+    }
+}
+
+/// more than `max_failed` of the feeds failed, as a fraction
+fn failed_too_many(failed: usize, total: usize, max_failed: f64) -> bool {
+    total > 0 && failed as f64 / total as f64 > max_failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_one_refresh_holds_the_lock() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-refresh-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let database_path = dir.join("feeds.db");
+
+        let first = lock(&database_path).unwrap();
+        assert!(first.is_some());
+        assert!(lock(&database_path).unwrap().is_none());
+        drop(first);
+        assert!(lock(&database_path).unwrap().is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_failing_feed_fails_the_run_and_releases_the_lock() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-refresh-run-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let feed_path = dir.join("feed.xml");
+        std::fs::write(
+            &feed_path,
+            r#"<rss version="2.0"><channel><title>Gone</title>
+<item><title>one</title><link>https://example.com/1</link></item>
+</channel></rss>"#,
+        )
+        .unwrap();
+
+        let database_path = dir.join("feeds.db");
+        let mut conn = rusqlite::Connection::open(&database_path).unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
+        crate::rss::subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            &crate::credentials::CredentialStore::default(),
+            url::Url::from_file_path(&feed_path).unwrap().as_str(),
+        )
+        .unwrap();
+        std::fs::remove_file(&feed_path).unwrap();
+
+        let options = || RefreshOptions {
+            database_path: database_path.clone(),
+            feeds: vec![],
+            jobs: 2,
+            json: true,
+            max_failed: 0.0,
+            network_timeout: std::time::Duration::from_secs(5),
+            config: Default::default(),
+        };
+        let err = run(options()).unwrap_err();
+        assert!(err.to_string().contains("1 of 1 feeds failed"), "{err}");
+        // the next run is not skipped, and fails the same way
+        assert!(lock(&database_path).unwrap().is_some());
+        assert!(run(options()).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fails_only_past_the_allowed_fraction() {
+        assert!(!failed_too_many(0, 0, 0.0));
+        assert!(!failed_too_many(1, 5, 0.2));
+        assert!(failed_too_many(2, 5, 0.2));
+        assert!(failed_too_many(1, 5, 0.0));
+        assert!(!failed_too_many(5, 5, 1.0));
+    }
+}
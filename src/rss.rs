@@ -1,13 +1,15 @@
-// retrieving and storing (RSS and Atom) feeds in sqlite db
+// retrieving and storing (RSS, Atom and JSON Feed) feeds in sqlite db
 
-use crate::modes::ReadMode;
+use crate::credentials::{CredentialStore, FeedCredentials};
+use crate::modes::{EntrySort, FeedSort, ReadMode};
+use crate::scrape::{ScrapeSelectors, ScrapedItem, ScrapedPage};
+use crate::source::FeedSource;
+use crate::util::PageRequest;
 use anyhow::{Context, Result, bail};
-use atom_syndication as atom;
 use chrono::prelude::{DateTime, Utc};
 use html_escape::decode_html_entities_to_string;
 use quick_xml::Reader;
 use quick_xml::events::Event;
-use rss::Channel;
 use rusqlite::params;
 use rusqlite::types::{FromSql, ToSqlOutput};
 use std::collections::HashSet;
@@ -18,10 +20,12 @@ use std::str::FromStr;
 /// entries older than this are pruned on feed refresh to limit db size
 const ENTRY_RETENTION_DAYS: u32 = 365;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
 pub(crate) struct EntryId(i64);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
 pub(crate) struct FeedId(i64);
 
 impl From<i64> for EntryId {
@@ -76,6 +80,9 @@ impl Display for FeedId {
 pub enum FeedKind {
     Atom,
     Rss,
+    Json,
+    /// synthesized from an html page with css selectors
+    Scraped,
 }
 
 impl rusqlite::types::FromSql for FeedKind {
@@ -100,6 +107,8 @@ impl Display for FeedKind {
         let out = match self {
             FeedKind::Atom => "Atom",
             FeedKind::Rss => "RSS",
+            FeedKind::Json => "JSON",
+            FeedKind::Scraped => "Scraped",
         };
 
         write!(f, "{out}")
@@ -113,6 +122,8 @@ impl FromStr for FeedKind {
         match s {
             "Atom" => Ok(FeedKind::Atom),
             "RSS" => Ok(FeedKind::Rss),
+            "JSON" => Ok(FeedKind::Json),
+            "Scraped" => Ok(FeedKind::Scraped),
             _ => Err(anyhow::anyhow!(format!("{s} is not a valid FeedKind"))),
         }
     }
@@ -130,6 +141,8 @@ pub struct Feed {
     pub link: Option<String>,
     pub feed_kind: FeedKind,
     pub refreshed_at: Option<chrono::DateTime<Utc>>,
+    /// newline-separated problems the parser recovered from on the last fetch
+    pub parse_warnings: Option<String>,
     // these are currently unused:
     // pub inserted_at: chrono::DateTime<Utc>,
     // pub updated_at: chrono::DateTime<Utc>,
@@ -145,6 +158,10 @@ struct IncomingFeed {
     link: Option<String>,
     feed_kind: FeedKind,
     latest_etag: Option<String>,
+    /// problems the parser recovered from, shown in the feed info panel
+    parse_warnings: Vec<String>,
+    /// how entries are found, for feeds scraped from an html page
+    scrape_selectors: Option<ScrapeSelectors>,
 }
 
 /// This exists:
@@ -158,62 +175,8 @@ struct IncomingEntry {
     description: Option<String>,
     content: Option<String>,
     link: Option<String>,
-}
-
-impl From<&atom::Entry> for IncomingEntry {
-    fn from(entry: &atom::Entry) -> Self {
-        Self {
-            title: {
-                let mut title = String::new();
-                decode_html_entities_to_string(entry.title(), &mut title);
-                Some(title)
-            },
-            author: entry.authors().first().map(|entry_author| {
-                let mut author = String::new();
-                decode_html_entities_to_string(&entry_author.name, &mut author);
-                author
-            }),
-            pub_date: entry.published().map(|date| date.with_timezone(&Utc)),
-            description: None,
-            content: entry.content().and_then(|entry_content| {
-                entry_content.value().map(|entry_content| {
-                    let mut content = String::new();
-                    decode_html_entities_to_string(entry_content, &mut content);
-                    content
-                })
-            }),
-            link: entry.links().first().map(|link| link.href().to_string()),
-        }
-    }
-}
-
-impl From<&rss::Item> for IncomingEntry {
-    fn from(entry: &rss::Item) -> Self {
-        Self {
-            title: entry.title().map(|entry_title| {
-                let mut title = String::new();
-                decode_html_entities_to_string(entry_title, &mut title);
-                title
-            }),
-            author: entry.author().map(|entry_author| {
-                let mut author = String::new();
-                decode_html_entities_to_string(entry_author, &mut author);
-                author
-            }),
-            pub_date: entry.pub_date().and_then(parse_datetime),
-            description: entry.description().map(|entry_description| {
-                let mut description = String::new();
-                decode_html_entities_to_string(entry_description, &mut description);
-                description
-            }),
-            content: entry.content().map(|entry_content| {
-                let mut content = String::new();
-                decode_html_entities_to_string(entry_content, &mut content);
-                content
-            }),
-            link: entry.link().map(|link| link.to_owned()),
-        }
-    }
+    /// the rss `<guid>` or atom `<id>`, to recognize the entry in another reader's data
+    guid: Option<String>,
 }
 
 /// Metadata for an entry.
@@ -259,6 +222,47 @@ impl EntryMetadata {
     }
 }
 
+/// marks every unread entry of the feed as read,
+/// returning what they were before so it can be undone with `restore_read_state`
+pub fn mark_feed_read(
+    conn: &mut rusqlite::Connection,
+    feed_id: FeedId,
+) -> Result<Vec<(EntryId, Option<DateTime<Utc>>)>> {
+    in_transaction(conn, |tx| {
+        let unread = {
+            let mut statement =
+                tx.prepare("SELECT id FROM entries WHERE feed_id = ?1 AND read_at IS NULL")?;
+            statement
+                .query_map([feed_id], |row| row.get::<_, EntryId>(0))?
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        tx.execute(
+            "UPDATE entries SET read_at = ?2 WHERE feed_id = ?1 AND read_at IS NULL",
+            params![feed_id, Utc::now()],
+        )?;
+
+        Ok(unread
+            .into_iter()
+            .map(|entry_id| (entry_id, None))
+            .collect())
+    })
+}
+
+/// puts back the `read_at` of each entry, as it was before a (bulk) mark-read
+pub fn restore_read_state(
+    conn: &mut rusqlite::Connection,
+    read_state: &[(EntryId, Option<DateTime<Utc>>)],
+) -> Result<()> {
+    in_transaction(conn, |tx| {
+        let mut statement = tx.prepare("UPDATE entries SET read_at = ?2 WHERE id = ?1")?;
+        for (entry_id, read_at) in read_state {
+            statement.execute(params![entry_id, read_at])?;
+        }
+        Ok(())
+    })
+}
+
 pub struct EntryContent {
     pub content: Option<String>,
     pub description: Option<String>,
@@ -278,15 +282,212 @@ fn local_name(name: &[u8]) -> &[u8] {
     name.splitn(2, |&b| b == b':').last().unwrap_or(name)
 }
 
-// streaming parser for feeds using quick-xml
-fn parse_feed_streaming<R: Read>(mut reader: R, url: &str) -> Result<FeedAndEntries> {
+// attribute value matched by local name, so `type` and `atom:type` both match
+fn attribute_by_local_name(e: &quick_xml::events::BytesStart, key: &str) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| local_name(attr.key.as_ref()) == key.as_bytes())
+        .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+}
+
+// reads a feed body and parses it as JSON Feed or as RSS/Atom xml
+fn parse_feed_streaming<R: Read>(
+    mut reader: R,
+    url: &str,
+    content_type: Option<&str>,
+) -> Result<FeedAndEntries> {
     let mut buf = Vec::new();
     reader.read_to_end(&mut buf)?;
 
-    let content = String::from_utf8(buf)
-        .map_err(|e| anyhow::anyhow!("feed body is not valid utf-8: {}", e))?;
+    let (content, utf8_warning) = match String::from_utf8(buf) {
+        Ok(content) => (content, None),
+        Err(e) => {
+            let warning = format!("feed body is not valid utf-8, invalid bytes replaced: {e}");
+            (
+                String::from_utf8_lossy(e.as_bytes()).into_owned(),
+                Some(warning),
+            )
+        }
+    };
+
+    let mut feed_and_entries = if is_json_feed(content_type, &content) {
+        parse_json_feed(&content, url)?
+    } else {
+        parse_xml_feed(&content, url)?
+    };
+
+    if let Some(warning) = utf8_warning {
+        feed_and_entries.feed.parse_warnings.insert(0, warning);
+    }
+
+    Ok(feed_and_entries)
+}
+
+// json by content type (application/feed+json, application/json) or a leading `{`
+fn is_json_feed(content_type: Option<&str>, content: &str) -> bool {
+    content_type.is_some_and(|content_type| content_type.to_lowercase().contains("json"))
+        || content
+            .trim_start_matches('\u{FEFF}')
+            .trim_start()
+            .starts_with('{')
+}
+
+/// what one pass of the xml parser got through before finishing or failing.
+/// entries are only kept once their closing tag has been seen.
+struct XmlParse {
+    feed_kind: Option<FeedKind>,
+    title: Option<String>,
+    link: Option<String>,
+    entries: Vec<IncomingEntry>,
+    error: Option<String>,
+}
+
+// parses the xml, and if that fails, repairs the common problems and tries again.
+// anything that had to be fixed or skipped is reported as a warning instead of an error.
+fn parse_xml_feed(content: &str, url: &str) -> Result<FeedAndEntries> {
+    let mut warnings = vec![];
+
+    let mut parsed = parse_xml_events(content);
+
+    if let Some(error) = parsed.error.take() {
+        warnings.push(format!("xml parsing error: {error}"));
+
+        let (repaired, fixes) = repair_xml(content);
+
+        let mut reparsed = parse_xml_events(&repaired);
+        let reparse_error = reparsed.error.take();
+
+        // keep whichever pass got further, the fixes only apply to the repaired one
+        if reparse_error.is_some() && reparsed.entries.len() < parsed.entries.len() {
+            reparsed = parsed;
+        } else {
+            warnings.extend(fixes);
+        }
+
+        if let Some(error) = reparse_error {
+            warnings.push(format!(
+                "stopped at unrecoverable xml error: {error}. kept the {} entries parsed before it",
+                reparsed.entries.len()
+            ));
+        }
+
+        parsed = reparsed;
+    }
+
+    let feed_kind = parsed
+        .feed_kind
+        .ok_or_else(|| anyhow::anyhow!("could not determine feed type"))?;
+
+    Ok(FeedAndEntries {
+        feed: IncomingFeed {
+            title: parsed.title,
+            feed_link: Some(url.to_string()),
+            link: parsed.link,
+            feed_kind,
+            latest_etag: None,
+            parse_warnings: warnings,
+            scrape_selectors: None,
+        },
+        entries: parsed.entries,
+    })
+}
+
+/// Fixes what breaks real-world feeds most often: bare `&`, html-only entities
+/// like `&nbsp;` and control characters that are not allowed in xml.
+/// CDATA sections and comments are copied through untouched.
+/// Returns the repaired document and a description of each kind of fix.
+fn repair_xml(content: &str) -> (String, Vec<String>) {
+    const XML_ENTITIES: [&str; 5] = ["amp", "lt", "gt", "quot", "apos"];
+
+    let mut out = String::with_capacity(content.len());
+    let mut bare_ampersands = 0usize;
+    let mut html_entities = 0usize;
+    let mut control_chars = 0usize;
+
+    let mut rest = content;
+    while let Some(c) = rest.chars().next() {
+        if let Some(skip) = ["<![CDATA[", "<!--"]
+            .iter()
+            .zip(["]]>", "-->"])
+            .find(|(open, _)| rest.starts_with(**open))
+            .map(|(open, close)| {
+                rest[open.len()..]
+                    .find(close)
+                    .map(|i| open.len() + i + close.len())
+                    .unwrap_or(rest.len())
+            })
+        {
+            out.push_str(&rest[..skip]);
+            rest = &rest[skip..];
+            continue;
+        }
+
+        if c == '&' {
+            let reference = rest[1..]
+                .find(';')
+                .filter(|&i| i > 0 && i <= 32)
+                .map(|i| &rest[1..=i]);
+
+            match reference {
+                Some(name) if XML_ENTITIES.contains(&name) || is_numeric_char_reference(name) => {
+                    out.push('&');
+                }
+                Some(name) if name.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                    let entity = format!("&{name};");
+                    let decoded = html_escape::decode_html_entities(&entity);
+                    if decoded != entity {
+                        html_entities += 1;
+                        for decoded_char in decoded.chars() {
+                            out.push_str(&format!("&#{};", decoded_char as u32));
+                        }
+                        rest = &rest[entity.len()..];
+                        continue;
+                    }
+                    bare_ampersands += 1;
+                    out.push_str("&amp;");
+                }
+                _ => {
+                    bare_ampersands += 1;
+                    out.push_str("&amp;");
+                }
+            }
+        } else if c.is_control() && !matches!(c, '\t' | '\n' | '\r') {
+            control_chars += 1;
+        } else {
+            out.push(c);
+        }
+
+        rest = &rest[c.len_utf8()..];
+    }
+
+    let mut fixes = vec![];
+    if bare_ampersands > 0 {
+        fixes.push(format!("escaped {bare_ampersands} bare '&'"));
+    }
+    if html_entities > 0 {
+        fixes.push(format!("replaced {html_entities} html-only entities"));
+    }
+    if control_chars > 0 {
+        fixes.push(format!("removed {control_chars} control characters"));
+    }
+
+    (out, fixes)
+}
+
+// `#123` or `#x7b`, the part of `&#123;` between `&` and `;`
+fn is_numeric_char_reference(name: &str) -> bool {
+    match name.strip_prefix('#') {
+        Some(hex) if hex.starts_with(['x', 'X']) => {
+            hex.len() > 1 && hex[1..].chars().all(|c| c.is_ascii_hexdigit())
+        }
+        Some(dec) => !dec.is_empty() && dec.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
 
-    let mut xml_reader = Reader::from_str(&content);
+// streaming parser for feeds using quick-xml
+fn parse_xml_events(content: &str) -> XmlParse {
+    let mut xml_reader = Reader::from_str(content);
     xml_reader.config_mut().trim_text(true);
 
     let mut feed_type: Option<FeedKind> = None;
@@ -297,6 +498,8 @@ fn parse_feed_streaming<R: Read>(mut reader: R, url: &str) -> Result<FeedAndEntr
     let mut buf2 = Vec::new();
     let mut in_item = false;
     let mut in_entry = false;
+    // how deep in the current atom entry we are, 1 for the entry's own children
+    let mut entry_depth = 0usize;
 
     // temporary storage for current entry/item
     let mut current_entry = IncomingEntry {
@@ -306,14 +509,19 @@ fn parse_feed_streaming<R: Read>(mut reader: R, url: &str) -> Result<FeedAndEntr
         description: None,
         content: None,
         link: None,
+        guid: None,
     };
     let mut current_text = String::new();
     let mut current_link_href: Option<String> = None;
+    let mut error = None;
 
     loop {
         match xml_reader.read_event_into(&mut buf2) {
             Ok(Event::Start(e)) => {
                 let name = String::from_utf8_lossy(local_name(e.name().as_ref())).to_string();
+                if in_entry {
+                    entry_depth += 1;
+                }
 
                 // detect feed type
                 if feed_type.is_none() {
@@ -334,10 +542,12 @@ fn parse_feed_streaming<R: Read>(mut reader: R, url: &str) -> Result<FeedAndEntr
                             description: None,
                             content: None,
                             link: None,
+                            guid: None,
                         };
                     }
                     "entry" => {
                         in_entry = true;
+                        entry_depth = 0;
                         current_entry = IncomingEntry {
                             title: None,
                             author: None,
@@ -345,6 +555,7 @@ fn parse_feed_streaming<R: Read>(mut reader: R, url: &str) -> Result<FeedAndEntr
                             description: None,
                             content: None,
                             link: None,
+                            guid: None,
                         };
                     }
                     "link" => {
@@ -367,8 +578,35 @@ fn parse_feed_streaming<R: Read>(mut reader: R, url: &str) -> Result<FeedAndEntr
                         }
                         current_text.clear();
                     }
+                    // atom xhtml content is a nested <div> of real elements, not text,
+                    // so grab the raw inner markup and keep it as html for html2text
+                    "content" | "summary"
+                        if in_entry
+                            && attribute_by_local_name(&e, "type").as_deref() == Some("xhtml") =>
+                    {
+                        let end = e.to_end().into_owned();
+                        let inner = match xml_reader.read_text(end.name()) {
+                            Ok(inner) => inner,
+                            Err(err) => {
+                                error = Some(format!(
+                                    "{err} (at byte {})",
+                                    xml_reader.error_position()
+                                ));
+                                break;
+                            }
+                        };
+                        // read_text consumed the end tag as well
+                        entry_depth -= 1;
+                        let html = inner.trim();
+                        if !html.is_empty()
+                            && (name == "content" || current_entry.content.is_none())
+                        {
+                            current_entry.content = Some(html.to_string());
+                        }
+                        current_text.clear();
+                    }
                     "title" | "description" | "content" | "summary" | "author" | "name"
-                    | "pubDate" | "published" | "updated" | "dc:date" => {
+                    | "pubDate" | "published" | "updated" | "dc:date" | "guid" | "id" => {
                         current_text.clear();
                     }
                     _ => {}
@@ -401,16 +639,24 @@ fn parse_feed_streaming<R: Read>(mut reader: R, url: &str) -> Result<FeedAndEntr
                     }
                 }
             }
-            Ok(Event::Text(e)) => {
-                let text = e.unescape().unwrap_or_default();
-                current_text.push_str(&text);
-            }
+            Ok(Event::Text(e)) => match e.unescape() {
+                Ok(text) => current_text.push_str(&text),
+                // bare `&` or an html-only entity like `&nbsp;`, let the repair pass fix it
+                Err(e) => {
+                    error = Some(format!("{e} (at byte {})", xml_reader.buffer_position()));
+                    break;
+                }
+            },
             Ok(Event::CData(e)) => {
                 let text = String::from_utf8_lossy(&e);
                 current_text.push_str(&text);
             }
             Ok(Event::End(e)) => {
                 let name = String::from_utf8_lossy(local_name(e.name().as_ref())).to_string();
+                let entry_child = in_entry && entry_depth == 1;
+                if in_entry {
+                    entry_depth = entry_depth.saturating_sub(1);
+                }
 
                 match name.as_str() {
                     "item" => {
@@ -485,6 +731,15 @@ fn parse_feed_streaming<R: Read>(mut reader: R, url: &str) -> Result<FeedAndEntr
                         }
                         current_text.clear();
                     }
+                    "guid" | "id" => {
+                        // not the id of a nested <source>, which names the feed the entry came from
+                        if ((in_item && name == "guid") || (entry_child && name == "id"))
+                            && !current_text.is_empty()
+                        {
+                            current_entry.guid = Some(current_text.clone());
+                        }
+                        current_text.clear();
+                    }
                     "pubDate" | "published" | "updated" | "dc:date" => {
                         if (in_item || in_entry)
                             && current_entry.pub_date.is_none()
@@ -500,24 +755,22 @@ fn parse_feed_streaming<R: Read>(mut reader: R, url: &str) -> Result<FeedAndEntr
                 }
             }
             Ok(Event::Eof) => break,
-            Err(e) => return Err(anyhow::anyhow!("xml parsing error: {}", e)),
+            Err(e) => {
+                error = Some(format!("{e} (at byte {})", xml_reader.error_position()));
+                break;
+            }
             _ => {}
         }
         buf2.clear();
     }
 
-    let feed_kind = feed_type.ok_or_else(|| anyhow::anyhow!("could not determine feed type"))?;
-
-    Ok(FeedAndEntries {
-        feed: IncomingFeed {
-            title: feed_title,
-            feed_link: Some(url.to_string()),
-            link: feed_link,
-            feed_kind,
-            latest_etag: None,
-        },
+    XmlParse {
+        feed_kind: feed_type,
+        title: feed_title,
+        link: feed_link,
         entries,
-    })
+        error,
+    }
 }
 
 struct FeedAndEntries {
@@ -531,53 +784,169 @@ impl FeedAndEntries {
     }
 }
 
-impl FromStr for FeedAndEntries {
-    type Err = anyhow::Error;
+/// JSON Feed 1.0/1.1 document, see https://www.jsonfeed.org/version/1.1/
+#[derive(serde::Deserialize)]
+struct JsonFeed {
+    title: Option<String>,
+    home_page_url: Option<String>,
+    #[serde(default)]
+    items: Vec<JsonFeedItem>,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match atom::Feed::from_str(s) {
-            Ok(atom_feed) => {
-                let feed = IncomingFeed {
-                    title: Some(atom_feed.title.to_string()),
-                    feed_link: None,
-                    link: atom_feed.links.first().map(|link| link.href().to_string()),
-                    feed_kind: FeedKind::Atom,
-                    latest_etag: None,
-                };
+#[derive(serde::Deserialize)]
+struct JsonFeedItem {
+    // the spec says string, but some publishers emit numbers
+    id: Option<serde_json::Value>,
+    url: Option<String>,
+    external_url: Option<String>,
+    title: Option<String>,
+    content_html: Option<String>,
+    content_text: Option<String>,
+    summary: Option<String>,
+    date_published: Option<String>,
+    date_modified: Option<String>,
+    // 1.1 uses `authors`, 1.0 used a single `author`
+    #[serde(default)]
+    authors: Vec<JsonFeedAuthor>,
+    author: Option<JsonFeedAuthor>,
+    #[serde(default)]
+    attachments: Vec<JsonFeedAttachment>,
+}
 
-                let entries = atom_feed
-                    .entries()
-                    .iter()
-                    .map(|entry| entry.into())
-                    .collect::<Vec<_>>();
+#[derive(serde::Deserialize)]
+struct JsonFeedAuthor {
+    name: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct JsonFeedAttachment {
+    url: String,
+    mime_type: Option<String>,
+    title: Option<String>,
+}
+
+impl From<JsonFeedItem> for IncomingEntry {
+    fn from(item: JsonFeedItem) -> Self {
+        let id = match item.id {
+            Some(serde_json::Value::String(id)) => Some(id),
+            Some(serde_json::Value::Number(id)) => Some(id.to_string()),
+            _ => None,
+        };
 
-                Ok(FeedAndEntries { feed, entries })
+        // the link is our uniqueness key on refresh, so fall back to the item id
+        let link = item.url.or(item.external_url).or(id.clone());
+
+        let mut content = item
+            .content_html
+            .or_else(|| item.content_text.map(|text| text_to_html(&text)));
+
+        if !item.attachments.is_empty() {
+            let content = content.get_or_insert_with(String::new);
+            content.push_str("<ul>");
+            for attachment in &item.attachments {
+                let label = attachment
+                    .title
+                    .as_deref()
+                    .or(attachment.mime_type.as_deref())
+                    .unwrap_or(attachment.url.as_str());
+                content.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a></li>",
+                    html_escape::encode_double_quoted_attribute(&attachment.url),
+                    html_escape::encode_text(label)
+                ));
             }
+            content.push_str("</ul>");
+        }
 
-            Err(_e) => match Channel::from_str(s) {
-                Ok(channel) => {
-                    let feed = IncomingFeed {
-                        title: Some(channel.title().to_string()),
-                        feed_link: None,
-                        link: Some(channel.link().to_string()),
-                        feed_kind: FeedKind::Rss,
-                        latest_etag: None,
-                    };
-
-                    let entries = channel
-                        .items()
-                        .iter()
-                        .map(|item| item.into())
-                        .collect::<Vec<_>>();
-
-                    Ok(FeedAndEntries { feed, entries })
-                }
-                Err(e) => Err(e.into()),
-            },
+        Self {
+            title: item.title,
+            author: item
+                .authors
+                .into_iter()
+                .chain(item.author)
+                .find_map(|author| author.name),
+            pub_date: item
+                .date_published
+                .or(item.date_modified)
+                .as_deref()
+                .and_then(parse_datetime),
+            description: item.summary,
+            content,
+            link,
+            guid: id,
+        }
+    }
+}
+
+// content_text is plain text, so escape it and keep its paragraph breaks for html2text
+fn text_to_html(text: &str) -> String {
+    text.split("\n\n")
+        .map(|paragraph| {
+            format!(
+                "<p>{}</p>",
+                html_escape::encode_text(paragraph).replace('\n', "<br>")
+            )
+        })
+        .collect()
+}
+
+fn parse_json_feed(content: &str, url: &str) -> Result<FeedAndEntries> {
+    let json_feed: JsonFeed = serde_json::from_str(content.trim_start_matches('\u{FEFF}'))
+        .map_err(|e| anyhow::anyhow!("json feed parsing error: {}", e))?;
+
+    Ok(FeedAndEntries {
+        feed: IncomingFeed {
+            title: json_feed.title,
+            feed_link: Some(url.to_string()),
+            link: json_feed.home_page_url,
+            feed_kind: FeedKind::Json,
+            latest_etag: None,
+            parse_warnings: vec![],
+            scrape_selectors: None,
+        },
+        entries: json_feed
+            .items
+            .into_iter()
+            .map(IncomingEntry::from)
+            .collect(),
+    })
+}
+
+impl From<ScrapedItem> for IncomingEntry {
+    fn from(item: ScrapedItem) -> Self {
+        IncomingEntry {
+            title: item.title,
+            author: None,
+            pub_date: item.pub_date,
+            description: item.summary,
+            content: None,
+            link: item.link,
+            guid: None,
         }
     }
 }
 
+fn parse_scraped_feed(
+    content: &[u8],
+    url: &str,
+    selectors: &ScrapeSelectors,
+) -> Result<FeedAndEntries> {
+    let page = crate::scrape::scrape(&String::from_utf8_lossy(content), url, selectors)?;
+
+    Ok(FeedAndEntries {
+        feed: IncomingFeed {
+            title: page.title,
+            feed_link: Some(url.to_string()),
+            link: Some(url.to_string()),
+            feed_kind: FeedKind::Scraped,
+            latest_etag: None,
+            parse_warnings: page.warnings,
+            scrape_selectors: Some(selectors.clone()),
+        },
+        entries: page.items.into_iter().map(IncomingEntry::from).collect(),
+    })
+}
+
 pub fn validate_and_normalize_feed_url(raw: &str) -> Result<String> {
     let trimmed = raw.trim();
 
@@ -585,6 +954,12 @@ pub fn validate_and_normalize_feed_url(raw: &str) -> Result<String> {
         bail!("feed url cannot be empty");
     }
 
+    // commands are stored as typed, they are not urls
+    if trimmed.starts_with("exec:") || trimmed.starts_with("filter:") {
+        FeedSource::parse(trimmed)?;
+        return Ok(trimmed.to_string());
+    }
+
     // if no scheme, assume https
     let candidate = if !trimmed.contains("://") {
         format!("https://{}", trimmed)
@@ -596,10 +971,10 @@ pub fn validate_and_normalize_feed_url(raw: &str) -> Result<String> {
         url::Url::parse(&candidate).map_err(|e| anyhow::anyhow!("invalid feed url: {}", e))?;
 
     match url.scheme() {
-        "http" | "https" => Ok(url.to_string()),
+        "http" | "https" | "file" => Ok(url.to_string()),
         other => {
             bail!(
-                "unsupported url scheme '{}', only http and https are allowed",
+                "unsupported url scheme '{}', only http, https, file, exec: and filter: are allowed",
                 other
             );
         }
@@ -607,14 +982,84 @@ pub fn validate_and_normalize_feed_url(raw: &str) -> Result<String> {
 }
 
 pub fn subscribe_to_feed(
-    http_client: &ureq::Agent,
+    http_client: &crate::http::HttpClient,
+    conn: &mut rusqlite::Connection,
+    credentials: &CredentialStore,
+    url: &str,
+) -> Result<FeedId> {
+    subscribe(http_client, conn, credentials, url, None)
+}
+
+/// Subscribes to an html page, with `selectors` finding the entries on every refresh
+pub fn subscribe_to_scraped_feed(
+    http_client: &crate::http::HttpClient,
+    conn: &mut rusqlite::Connection,
+    credentials: &CredentialStore,
+    url: &str,
+    selectors: &ScrapeSelectors,
+) -> Result<FeedId> {
+    subscribe(http_client, conn, credentials, url, Some(selectors))
+}
+
+/// What `selectors` find on the page at `url`, to tune them before subscribing
+pub fn scrape_page(
+    http_client: &crate::http::HttpClient,
+    credentials: &CredentialStore,
+    url: &str,
+    selectors: &ScrapeSelectors,
+) -> Result<ScrapedPage> {
+    let source = FeedSource::parse(url)?;
+
+    let Some(body) = fetch_feed_body(http_client, &source, None, credentials.get(url))? else {
+        bail!("Did not expect page to be cached in this instance as we did not pass an etag")
+    };
+
+    crate::scrape::scrape(&String::from_utf8_lossy(&body.content), url, selectors)
+}
+
+fn subscribe(
+    http_client: &crate::http::HttpClient,
     conn: &mut rusqlite::Connection,
+    credentials: &CredentialStore,
     url: &str,
+    selectors: Option<&ScrapeSelectors>,
 ) -> Result<FeedId> {
-    let feed_and_entries = fetch_feed(http_client, url, None)?;
+    // feed links are unique, so a page can only be scraped with one set of selectors
+    if get_feed_id_by_url(conn, url)?.is_some() {
+        bail!("Already subscribed to {url}");
+    }
+    let trashed_feed_id = get_trashed_feed_id_by_url(conn, url)?;
+
+    let feed_and_entries = fetch_feed(http_client, url, None, credentials.get(url), selectors)?;
 
     match feed_and_entries {
-        FeedResponse::CacheMiss(feed_and_entries) => {
+        FeedResponse::CacheMiss {
+            feed_and_entries, ..
+        } => {
+            // subscribing again to a feed in the trash brings it back, read state and all,
+            // but as it is fetched now, with the selectors it is subscribed with now
+            if let Some(feed_id) = trashed_feed_id {
+                let items_to_add = new_entries(conn, feed_id, feed_and_entries.entries)?;
+                in_transaction(conn, |tx| {
+                    tx.execute(
+                        "UPDATE feeds
+                        SET deleted_at = NULL, link = ?2, feed_kind = ?3, scrape_selectors = ?4
+                        WHERE id = ?1",
+                        params![
+                            feed_id,
+                            feed_and_entries.feed.link,
+                            feed_and_entries.feed.feed_kind,
+                            selectors.map(serde_json::to_string).transpose()?
+                        ],
+                    )?;
+                    update_feed_etag(tx, feed_id, feed_and_entries.feed.latest_etag.clone())?;
+                    update_feed_parse_warnings(tx, feed_id, &feed_and_entries.feed.parse_warnings)?;
+                    add_entries_to_feed(tx, feed_id, &items_to_add)?;
+                    Ok(())
+                })?;
+                return Ok(feed_id);
+            }
+
             let feed_id = in_transaction(conn, |tx| {
                 let feed_id = create_feed(tx, &feed_and_entries.feed).with_context(|| {
                     format!(
@@ -644,7 +1089,11 @@ enum FeedResponse {
     /// The remote host returned a new feed.
     /// The data may not actually be new, as hosts
     /// seem to change etags for all kinds of reasons
-    CacheMiss(FeedAndEntries),
+    CacheMiss {
+        feed_and_entries: Box<FeedAndEntries>,
+        http_status: Option<u16>,
+        bytes: usize,
+    },
     /// the remote host indicated a cache hit,
     /// and did not return any new data
     CacheHit,
@@ -688,20 +1137,120 @@ fn http_status_error_message(status: u16, url: &str) -> String {
     }
 }
 
+/// The raw body of a feed, before parsing
+struct FeedBody {
+    content: Vec<u8>,
+    content_type: Option<String>,
+    etag: Option<String>,
+    /// `None` for feeds that don't come from http
+    http_status: Option<u16>,
+}
+
 fn fetch_feed(
-    http_client: &ureq::Agent,
+    http_client: &crate::http::HttpClient,
     url: &str,
     current_etag: Option<String>,
+    credentials: Option<&FeedCredentials>,
+    scrape_selectors: Option<&ScrapeSelectors>,
 ) -> Result<FeedResponse> {
-    let request = http_client.get(url);
+    let source = FeedSource::parse(url)?;
 
-    let request = if let Some(etag) = current_etag {
-        request.set("If-None-Match", &etag)
+    let Some(body) = fetch_feed_body(http_client, &source, current_etag, credentials)? else {
+        return Ok(FeedResponse::CacheHit);
+    };
+
+    let mut feed_and_entries = if let Some(scrape_selectors) = scrape_selectors {
+        parse_scraped_feed(&body.content, url, scrape_selectors)
+            .with_context(|| format!("failed to scrape entries from {}", url))?
     } else {
-        request
+        parse_feed_streaming(
+            body.content.as_slice(),
+            url,
+            body.content_type.as_deref(),
+        )
+        .with_context(|| {
+            format!(
+                "failed to parse feed from {}. the response is not valid rss, atom or json feed",
+                url
+            )
+        })?
+    };
+
+    feed_and_entries.set_latest_etag(body.etag);
+
+    Ok(FeedResponse::CacheMiss {
+        feed_and_entries: Box::new(feed_and_entries),
+        http_status: body.http_status,
+        bytes: body.content.len(),
+    })
+}
+
+/// `None` when the feed has not changed since `current_etag`
+fn fetch_feed_body(
+    http_client: &crate::http::HttpClient,
+    source: &FeedSource,
+    current_etag: Option<String>,
+    credentials: Option<&FeedCredentials>,
+) -> Result<Option<FeedBody>> {
+    match source {
+        FeedSource::Http(url) => fetch_http_body(http_client, url, current_etag, credentials),
+        FeedSource::File(path) => {
+            let content = std::fs::read(path)
+                .with_context(|| format!("unable to read feed file {}", path.display()))?;
+            Ok(Some(FeedBody {
+                content,
+                content_type: None,
+                etag: None,
+                http_status: None,
+            }))
+        }
+        FeedSource::Exec(command) => {
+            let content = crate::source::run_command(command, None, http_client.timeout())?;
+            Ok(Some(FeedBody {
+                content,
+                content_type: None,
+                etag: None,
+                http_status: None,
+            }))
+        }
+        FeedSource::Filter { command, source } => {
+            let Some(body) = fetch_feed_body(http_client, source, current_etag, credentials)?
+            else {
+                return Ok(None);
+            };
+            let content =
+                crate::source::run_command(command, Some(&body.content), http_client.timeout())?;
+            // the filter may well have changed the format, so sniff it again
+            Ok(Some(FeedBody {
+                content,
+                content_type: None,
+                etag: body.etag,
+                http_status: body.http_status,
+            }))
+        }
+    }
+}
+
+fn fetch_http_body(
+    http_client: &crate::http::HttpClient,
+    url: &str,
+    current_etag: Option<String>,
+    credentials: Option<&FeedCredentials>,
+) -> Result<Option<FeedBody>> {
+    let prepare = |request: ureq::Request, on_feed_host: bool| {
+        let request = match credentials {
+            Some(credentials) if on_feed_host => credentials.apply(request),
+            _ => request,
+        };
+
+        if let Some(etag) = &current_etag {
+            request.set("If-None-Match", etag)
+        } else {
+            request
+        }
     };
 
-    let response = request.call().with_context(|| {
+    let response = http_client.get(url, prepare).with_context(|| {
         format!(
             "network error fetching feed {}. check your internet connection and verify the url is accessible",
             url
@@ -723,21 +1272,23 @@ fn fetch_feed(
                 .and_then(|etag_header| response.header(etag_header))
                 .map(|etag| etag.to_owned());
 
-            let reader = response.into_reader();
-
-            let mut feed_and_entries = parse_feed_streaming(reader, url).with_context(|| {
-                format!(
-                    "failed to parse feed from {}. the response is not valid rss or atom xml",
-                    url
-                )
-            })?;
+            let content_type = response.content_type().to_owned();
 
-            feed_and_entries.set_latest_etag(etag);
+            let mut content = vec![];
+            response
+                .into_reader()
+                .read_to_end(&mut content)
+                .with_context(|| format!("network error reading feed {}", url))?;
 
-            Ok(FeedResponse::CacheMiss(feed_and_entries))
+            Ok(Some(FeedBody {
+                content,
+                content_type: Some(content_type),
+                etag,
+                http_status: Some(status),
+            }))
         }
         // the etags match, it is the same feed we already have
-        304 => Ok(FeedResponse::CacheHit),
+        304 => Ok(None),
         status => Err(anyhow::anyhow!(
             "{}",
             http_status_error_message(status, url)
@@ -752,20 +1303,79 @@ fn prune_old_entries_for_feed(
 ) -> Result<()> {
     let cutoff = Utc::now() - chrono::Duration::days(max_age_days as i64);
     tx.execute(
-        "DELETE FROM entries WHERE feed_id = ?1 AND COALESCE(pub_date, inserted_at) < ?2",
+        "DELETE FROM entries
+        WHERE feed_id = ?1 AND COALESCE(pub_date, inserted_at) < ?2",
         params![feed_id, cutoff],
     )?;
     Ok(())
 }
 
+/// fetches the feed and stores the new entries,
+/// recording the attempt in the refresh log whether it worked or not.
+pub fn refresh_feed(
+    client: &crate::http::HttpClient,
+    conn: &mut rusqlite::Connection,
+    credentials: &CredentialStore,
+    feed_id: FeedId,
+) -> Result<()> {
+    let started_at = std::time::Instant::now();
+
+    let result = fetch_and_store_feed(client, conn, credentials, feed_id);
+
+    let attempt = match &result {
+        Ok(outcome) => NewRefreshAttempt {
+            http_status: outcome.http_status,
+            bytes: outcome.bytes,
+            duration: started_at.elapsed(),
+            new_entries: outcome.new_entries,
+            error: None,
+        },
+        Err(e) => NewRefreshAttempt {
+            http_status: http_status_of_error(e),
+            bytes: None,
+            duration: started_at.elapsed(),
+            new_entries: 0,
+            error: Some(
+                e.chain()
+                    .map(|cause| cause.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        },
+    };
+
+    // a failed refresh is more interesting than failing to log it
+    let logged = record_refresh_attempt(conn, feed_id, &attempt)
+        .with_context(|| format!("Unable to record refresh of feed_id {feed_id}"));
+
+    result.and(logged)
+}
+
+/// What a successful refresh did, for the refresh log
+struct RefreshOutcome {
+    http_status: Option<u16>,
+    bytes: Option<usize>,
+    new_entries: usize,
+}
+
+/// the http status that made a request fail, if it got that far
+fn http_status_of_error(e: &anyhow::Error) -> Option<u16> {
+    e.chain()
+        .find_map(|cause| match cause.downcast_ref::<ureq::Error>() {
+            Some(ureq::Error::Status(status, _)) => Some(*status),
+            _ => None,
+        })
+}
+
 /// fetches the feed and stores the new entries
 /// uses the link as the uniqueness key.
 /// TODO hash the content to see if anything changed, and update that way.
-pub fn refresh_feed(
-    client: &ureq::Agent,
+fn fetch_and_store_feed(
+    client: &crate::http::HttpClient,
     conn: &mut rusqlite::Connection,
+    credentials: &CredentialStore,
     feed_id: FeedId,
-) -> Result<()> {
+) -> Result<RefreshOutcome> {
     let feed_url = get_feed_url(conn, feed_id)
         .with_context(|| format!("Unable to get url for feed id {feed_id} from the database",))?;
 
@@ -773,133 +1383,339 @@ pub fn refresh_feed(
         format!("Unable to get latest_etag for feed_id {feed_id} from the database")
     })?;
 
-    let remote_feed = fetch_feed(client, &feed_url, current_etag)
-        .with_context(|| format!("Failed to fetch feed {feed_url}"))?;
-
-    if let FeedResponse::CacheMiss(remote_feed) = remote_feed {
-        let remote_items = remote_feed.entries;
-        let remote_items_links = remote_items
-            .iter()
-            .flat_map(|item| &item.link)
-            .cloned()
-            .collect::<HashSet<String>>();
-
-        let local_entries_links = get_entries_links(conn, &ReadMode::All, feed_id)?
-            .into_iter()
-            .flatten()
-            .collect::<HashSet<_>>();
+    let scrape_selectors = get_feed_scrape_selectors(conn, feed_id).with_context(|| {
+        format!("Unable to get scrape selectors for feed_id {feed_id} from the database")
+    })?;
 
-        let difference = remote_items_links
-            .difference(&local_entries_links)
-            .cloned()
-            .collect::<HashSet<_>>();
-
-        let items_to_add = remote_items
-            .into_iter()
-            .filter(|item| match &item.link {
-                Some(link) => difference.contains(link.as_str()),
-                None => false,
-            })
-            .collect::<Vec<_>>();
+    let remote_feed = fetch_feed(
+        client,
+        &feed_url,
+        current_etag,
+        credentials.get(&feed_url),
+        scrape_selectors.as_ref(),
+    )
+    .with_context(|| format!("Failed to fetch feed {feed_url}"))?;
+
+    if let FeedResponse::CacheMiss {
+        feed_and_entries: remote_feed,
+        http_status,
+        bytes,
+    } = remote_feed
+    {
+        let items_to_add = new_entries(conn, feed_id, remote_feed.entries)?;
 
         in_transaction(conn, |tx| {
             add_entries_to_feed(tx, feed_id, &items_to_add)?;
             update_feed_refreshed_at(tx, feed_id)?;
             update_feed_etag(tx, feed_id, remote_feed.feed.latest_etag.clone())?;
+            update_feed_parse_warnings(tx, feed_id, &remote_feed.feed.parse_warnings)?;
             prune_old_entries_for_feed(tx, feed_id, ENTRY_RETENTION_DAYS)?;
             Ok(())
         })?;
+
+        Ok(RefreshOutcome {
+            http_status,
+            bytes: Some(bytes),
+            new_entries: items_to_add.len(),
+        })
     } else {
         in_transaction(conn, |tx| {
             update_feed_refreshed_at(tx, feed_id)?;
             prune_old_entries_for_feed(tx, feed_id, ENTRY_RETENTION_DAYS)?;
             Ok(())
         })?;
+
+        Ok(RefreshOutcome {
+            http_status: Some(304),
+            bytes: None,
+            new_entries: 0,
+        })
     }
+}
 
-    Ok(())
+/// the fetched entries whose links the feed does not have yet
+fn new_entries(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    remote_items: Vec<IncomingEntry>,
+) -> Result<Vec<IncomingEntry>> {
+    let remote_items_links = remote_items
+        .iter()
+        .flat_map(|item| &item.link)
+        .cloned()
+        .collect::<HashSet<String>>();
+
+    let local_entries_links = get_entries_links(conn, &ReadMode::All, feed_id)?
+        .into_iter()
+        .flatten()
+        .collect::<HashSet<_>>();
+
+    let difference = remote_items_links
+        .difference(&local_entries_links)
+        .cloned()
+        .collect::<HashSet<_>>();
+
+    Ok(remote_items
+        .into_iter()
+        .filter(|item| match &item.link {
+            Some(link) => difference.contains(link.as_str()),
+            None => false,
+        })
+        .collect())
 }
 
-pub fn initialize_db(conn: &mut rusqlite::Connection) -> Result<()> {
-    in_transaction(conn, |tx| {
-        let schema_version: u64 = tx.pragma_query_value(None, "user_version", |row| row.get(0))?;
+/// One row of the refresh log
+#[derive(Clone, Debug)]
+pub struct RefreshAttempt {
+    pub attempted_at: chrono::DateTime<Utc>,
+    pub http_status: Option<u16>,
+    pub bytes: Option<usize>,
+    pub duration: std::time::Duration,
+    pub new_entries: usize,
+    /// the full `anyhow` context chain, one cause per line
+    pub error: Option<String>,
+}
 
-        if schema_version == 0 {
-            tx.pragma_update(None, "user_version", 1)?;
+struct NewRefreshAttempt {
+    http_status: Option<u16>,
+    bytes: Option<usize>,
+    duration: std::time::Duration,
+    new_entries: usize,
+    error: Option<String>,
+}
 
-            tx.execute(
-                "CREATE TABLE IF NOT EXISTS feeds (
-        id INTEGER PRIMARY KEY AUTOINCREMENT,
-        title TEXT,
-        feed_link TEXT,
-        link TEXT,
-        feed_kind TEXT,
-        refreshed_at TIMESTAMP,
-        inserted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )",
-                [],
-            )?;
+/// only this many attempts are kept per feed
+const REFRESH_LOG_LIMIT: usize = 50;
 
-            tx.execute(
-                "CREATE TABLE IF NOT EXISTS entries (
-        id INTEGER PRIMARY KEY AUTOINCREMENT,
-        feed_id INTEGER,
-        title TEXT,
-        author TEXT,
-        pub_date TIMESTAMP,
-        description TEXT,
-        content TEXT,
-        link TEXT,
-        read_at TIMESTAMP,
-        inserted_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )",
-                [],
-            )?;
+fn record_refresh_attempt(
+    conn: &mut rusqlite::Connection,
+    feed_id: FeedId,
+    attempt: &NewRefreshAttempt,
+) -> Result<()> {
+    in_transaction(conn, |tx| {
+        tx.execute(
+            "INSERT INTO refresh_log (feed_id, attempted_at, http_status, bytes, duration_ms, new_entries, error)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                feed_id,
+                Utc::now(),
+                attempt.http_status,
+                attempt.bytes,
+                attempt.duration.as_millis() as u64,
+                attempt.new_entries,
+                attempt.error
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM refresh_log
+            WHERE feed_id = ?1
+            AND id NOT IN (SELECT id FROM refresh_log WHERE feed_id = ?1 ORDER BY id DESC LIMIT ?2)",
+            params![feed_id, REFRESH_LOG_LIMIT],
+        )?;
+        Ok(())
+    })
+}
 
-            tx.execute(
-                "CREATE INDEX IF NOT EXISTS entries_feed_id_and_pub_date_and_inserted_at_index
-        ON entries (feed_id, pub_date, inserted_at)",
-                [],
-            )?;
-        }
+/// the most recent refresh attempts of a feed, newest first
+pub fn get_refresh_log(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    limit: usize,
+) -> Result<Vec<RefreshAttempt>> {
+    let mut statement = conn.prepare(
+        "SELECT attempted_at, http_status, bytes, duration_ms, new_entries, error
+        FROM refresh_log
+        WHERE feed_id = ?1
+        ORDER BY id DESC
+        LIMIT ?2",
+    )?;
 
-        if schema_version <= 1 {
-            tx.pragma_update(None, "user_version", 2)?;
+    let attempts = statement
+        .query_map(params![feed_id, limit], |row| {
+            Ok(RefreshAttempt {
+                attempted_at: row.get(0)?,
+                http_status: row.get(1)?,
+                bytes: row.get(2)?,
+                duration: std::time::Duration::from_millis(row.get(3)?),
+                new_entries: row.get(4)?,
+                error: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
 
-            tx.execute("ALTER TABLE feeds ADD COLUMN latest_etag TEXT", [])?;
-        }
+    Ok(attempts)
+}
 
-        if schema_version <= 2 {
-            tx.pragma_update(None, "user_version", 3)?;
+/// the error of every feed whose most recent refresh failed
+pub fn get_failing_feeds(conn: &rusqlite::Connection) -> Result<Vec<(FeedId, String)>> {
+    let mut statement = conn.prepare(
+        "SELECT feed_id, error
+        FROM refresh_log
+        WHERE id IN (SELECT MAX(id) FROM refresh_log GROUP BY feed_id)
+        AND error IS NOT NULL
+        AND feed_id IN (SELECT id FROM feeds WHERE deleted_at IS NULL)",
+    )?;
 
-            tx.execute(
-                "CREATE UNIQUE INDEX IF NOT EXISTS feeds_feed_link ON feeds (feed_link)",
-                [],
-            )?;
-        }
+    let failing = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(())
-    })
+    Ok(failing)
+}
+
+/// Creates or migrates the schema, see `migrations`
+pub fn initialize_db(conn: &mut rusqlite::Connection) -> Result<()> {
+    if let Some(backup_path) = crate::migrations::migrate(conn)? {
+        eprintln!("Migrated the database, the old one is backed up at {backup_path:?}");
+    }
+    Ok(())
 }
 
 fn create_feed(tx: &rusqlite::Transaction, feed: &IncomingFeed) -> Result<FeedId> {
     let feed_id = tx.query_row::<FeedId, _, _>(
-        "INSERT INTO feeds (title, link, feed_link, feed_kind)
-        VALUES (?1, ?2, ?3, ?4)
+        "INSERT INTO feeds (title, link, feed_link, feed_kind, parse_warnings, scrape_selectors)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
         RETURNING id",
-        params![feed.title, feed.link, feed.feed_link, feed.feed_kind],
+        params![
+            feed.title,
+            feed.link,
+            feed.feed_link,
+            feed.feed_kind,
+            join_parse_warnings(&feed.parse_warnings),
+            feed.scrape_selectors
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?
+        ],
         |r| r.get(0),
     )?;
 
     Ok(feed_id)
 }
 
+/// moves the feed to the trash: it disappears from every list and is no longer refreshed,
+/// but its entries and their read state are kept until it is restored or purged
+pub fn trash_feed(conn: &mut rusqlite::Connection, feed_id: FeedId) -> Result<()> {
+    in_transaction(conn, |tx| {
+        tx.execute(
+            "UPDATE feeds SET deleted_at = ?2 WHERE id = ?1",
+            params![feed_id, Utc::now()],
+        )?;
+        Ok(())
+    })
+}
+
+pub fn restore_feed(conn: &mut rusqlite::Connection, feed_id: FeedId) -> Result<()> {
+    in_transaction(conn, |tx| {
+        tx.execute(
+            "UPDATE feeds SET deleted_at = NULL WHERE id = ?1",
+            [feed_id],
+        )?;
+        Ok(())
+    })
+}
+
+fn get_trashed_feed_id_by_url(conn: &rusqlite::Connection, url: &str) -> Result<Option<FeedId>> {
+    let mut statement =
+        conn.prepare("SELECT id FROM feeds WHERE feed_link = ?1 AND deleted_at IS NOT NULL")?;
+    let mut rows = statement.query_map([url], |row| row.get(0))?;
+    Ok(rows.next().transpose()?)
+}
+
+/// A feed in the trash
+#[derive(Clone, Debug)]
+pub struct TrashedFeed {
+    pub feed: Feed,
+    pub deleted_at: DateTime<Utc>,
+    pub entries: usize,
+    pub read_entries: usize,
+}
+
+impl TrashedFeed {
+    /// days until `purge_expired_trash` deletes it for good, when feeds are kept `retention_days`
+    pub fn days_until_purge(&self, retention_days: u32) -> i64 {
+        (self.deleted_at + chrono::Duration::days(retention_days.into()) - Utc::now())
+            .num_days()
+            .max(0)
+    }
+}
+
+/// the feeds in the trash, most recently deleted first
+pub fn get_trashed_feeds(conn: &rusqlite::Connection) -> Result<Vec<TrashedFeed>> {
+    let mut statement = conn.prepare(
+        "SELECT
+          id,
+          title,
+          feed_link,
+          link,
+          feed_kind,
+          refreshed_at,
+          parse_warnings,
+          deleted_at,
+          (SELECT COUNT(*) FROM entries WHERE entries.feed_id = feeds.id),
+          (SELECT COUNT(*) FROM entries WHERE entries.feed_id = feeds.id AND read_at IS NOT NULL)
+        FROM feeds
+        WHERE deleted_at IS NOT NULL
+        ORDER BY deleted_at DESC",
+    )?;
+
+    let trashed = statement
+        .query_map([], |row| {
+            Ok(TrashedFeed {
+                feed: Feed {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    feed_link: row.get(2)?,
+                    link: row.get(3)?,
+                    feed_kind: row.get(4)?,
+                    refreshed_at: row.get(5)?,
+                    parse_warnings: row.get(6)?,
+                },
+                deleted_at: row.get(7)?,
+                entries: row.get(8)?,
+                read_entries: row.get(9)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(trashed)
+}
+
+/// permanently deletes feeds that have been in the trash longer than `retention_days`,
+/// returning their urls so their credentials can be removed too
+pub fn purge_expired_trash(
+    conn: &mut rusqlite::Connection,
+    retention_days: u32,
+) -> Result<Vec<String>> {
+    let cutoff = Utc::now() - chrono::Duration::days(retention_days.into());
+
+    let expired = {
+        let mut statement = conn.prepare(
+            "SELECT id, feed_link FROM feeds WHERE deleted_at IS NOT NULL AND deleted_at < ?1",
+        )?;
+        statement
+            .query_map([cutoff], |row| {
+                Ok((row.get::<_, FeedId>(0)?, row.get::<_, Option<String>>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut urls = vec![];
+    for (feed_id, feed_link) in expired {
+        delete_feed(conn, feed_id)?;
+        urls.extend(feed_link);
+    }
+
+    Ok(urls)
+}
+
+/// deletes the feed, its entries and its refresh log for good
 pub fn delete_feed(conn: &mut rusqlite::Connection, feed_id: FeedId) -> Result<()> {
     in_transaction(conn, |tx| {
         tx.execute("DELETE FROM feeds WHERE id = ?1", [feed_id])?;
         tx.execute("DELETE FROM entries WHERE feed_id = ?1", [feed_id])?;
+        tx.execute("DELETE FROM refresh_log WHERE feed_id = ?1", [feed_id])?;
+        tx.execute("DELETE FROM feed_tags WHERE feed_id = ?1", [feed_id])?;
         Ok(())
     })
 }
@@ -913,7 +1729,7 @@ fn add_entries_to_feed(
         let now = Utc::now();
 
         let mut insert_statement = tx.prepare(
-            "INSERT INTO entries (feed_id, title, author, pub_date, description, content, link, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO entries (feed_id, title, author, pub_date, description, content, link, guid, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )?;
 
         // in most databases, doing this kind of "multiple inserts in a loop" thing would be bad and slow, but it's ok here because:
@@ -929,6 +1745,7 @@ fn add_entries_to_feed(
                 entry.description,
                 entry.content,
                 entry.link,
+                entry.guid,
                 now
             ])?;
         }
@@ -939,20 +1756,17 @@ fn add_entries_to_feed(
 
 pub fn get_feed(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<Feed> {
     let s = conn.query_row(
-        "SELECT id, title, feed_link, link, feed_kind, refreshed_at, inserted_at, updated_at, latest_etag FROM feeds WHERE id=?1",
+        "SELECT id, title, feed_link, link, feed_kind, refreshed_at, inserted_at, updated_at, latest_etag, parse_warnings FROM feeds WHERE id=?1",
         [feed_id],
         |row| {
-            let feed_kind_str: String = row.get(4)?;
-            let feed_kind: FeedKind = FeedKind::from_str(&feed_kind_str)
-                .unwrap_or_else(|_| panic!("FeedKind must be Atom or RSS, got {feed_kind_str}"));
-
             Ok(Feed {
                 id: row.get(0)?,
                 title: row.get(1)?,
                 feed_link: row.get(2)?,
                 link: row.get(3)?,
-                feed_kind,
+                feed_kind: row.get(4)?,
                 refreshed_at: row.get(5)?,
+                parse_warnings: row.get(9)?,
                 // inserted_at: row.get(6)?,
                 // updated_at: row.get(7)?,
                 // latest_etag: row.get(8)?,
@@ -972,6 +1786,27 @@ fn update_feed_refreshed_at(tx: &rusqlite::Transaction, feed_id: FeedId) -> Resu
     Ok(())
 }
 
+fn update_feed_parse_warnings(
+    tx: &rusqlite::Transaction,
+    feed_id: FeedId,
+    parse_warnings: &[String],
+) -> Result<()> {
+    tx.execute(
+        "UPDATE feeds SET parse_warnings = ?2 WHERE id = ?1",
+        params![feed_id, join_parse_warnings(parse_warnings)],
+    )?;
+
+    Ok(())
+}
+
+fn join_parse_warnings(parse_warnings: &[String]) -> Option<String> {
+    if parse_warnings.is_empty() {
+        None
+    } else {
+        Some(parse_warnings.join("\n"))
+    }
+}
+
 fn update_feed_etag(
     tx: &rusqlite::Transaction,
     feed_id: FeedId,
@@ -988,7 +1823,7 @@ fn update_feed_etag(
 pub fn update_feed_title(
     conn: &mut rusqlite::Connection,
     feed_id: FeedId,
-    new_title: String,
+    new_title: Option<String>,
 ) -> Result<()> {
     in_transaction(conn, |tx| {
         tx.execute(
@@ -999,6 +1834,159 @@ pub fn update_feed_title(
     })
 }
 
+/// the subscribed (not trashed) feed at `url`
+pub fn get_feed_id_by_url(conn: &rusqlite::Connection, url: &str) -> Result<Option<FeedId>> {
+    let mut statement =
+        conn.prepare("SELECT id FROM feeds WHERE feed_link = ?1 AND deleted_at IS NULL")?;
+    let mut rows = statement.query_map([url], |row| row.get(0))?;
+    Ok(rows.next().transpose()?)
+}
+
+/// the subscribed feed with this id or url, for the `--feed` arguments of subcommands
+pub fn find_feed_id(conn: &rusqlite::Connection, id_or_url: &str) -> Result<FeedId> {
+    let by_id = match id_or_url.parse::<i64>() {
+        Ok(id) => conn
+            .query_row(
+                "SELECT id FROM feeds WHERE id = ?1 AND deleted_at IS NULL",
+                [id],
+                |row| row.get(0),
+            )
+            .ok(),
+        Err(_) => None,
+    };
+
+    match by_id {
+        Some(feed_id) => Ok(feed_id),
+        None => get_feed_id_by_url(conn, id_or_url)?
+            .ok_or_else(|| anyhow::anyhow!("No feed with the id or url {id_or_url:?}")),
+    }
+}
+
+pub fn add_feed_tags(
+    conn: &mut rusqlite::Connection,
+    feed_id: FeedId,
+    tags: &[String],
+) -> Result<()> {
+    in_transaction(conn, |tx| {
+        let mut statement =
+            tx.prepare("INSERT OR IGNORE INTO feed_tags (feed_id, tag) VALUES (?1, ?2)")?;
+        for tag in tags {
+            statement.execute(params![feed_id, tag])?;
+        }
+        Ok(())
+    })
+}
+
+/// the tags of every feed that has any, in alphabetical order
+pub fn get_feed_tags(
+    conn: &rusqlite::Connection,
+) -> Result<std::collections::HashMap<FeedId, Vec<String>>> {
+    let mut statement = conn.prepare("SELECT feed_id, tag FROM feed_tags ORDER BY tag")?;
+    let mut tags: std::collections::HashMap<FeedId, Vec<String>> = std::collections::HashMap::new();
+    for row in statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+        let (feed_id, tag) = row?;
+        tags.entry(feed_id).or_default().push(tag);
+    }
+    Ok(tags)
+}
+
+/// An entry as another feed reader had it, read state and all
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportedEntry {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub pub_date: Option<DateTime<Utc>>,
+    pub description: Option<String>,
+    pub content: Option<String>,
+    pub link: Option<String>,
+    pub guid: Option<String>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub flagged_at: Option<DateTime<Utc>>,
+}
+
+/// What `import_entries` did
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportedEntries {
+    /// already in the feed, their read state carried over
+    pub matched: usize,
+    /// no longer in the feed, added with their read state
+    pub added: usize,
+    /// older than refreshes keep entries for
+    pub expired: usize,
+}
+
+/// Carries the read and flagged state of `entries` over to the feed's entries
+/// with the same link or guid, and adds the ones the feed no longer has.
+/// Only ever marks entries read or flagged, never the other way around
+pub fn import_entries(
+    conn: &mut rusqlite::Connection,
+    feed_id: FeedId,
+    entries: &[ImportedEntry],
+) -> Result<ImportedEntries> {
+    let cutoff = Utc::now() - chrono::Duration::days(ENTRY_RETENTION_DAYS as i64);
+
+    in_transaction(conn, |tx| {
+        let mut imported = ImportedEntries::default();
+
+        // a guid that is a permalink is often the link we have
+        let mut find_statement = tx.prepare(
+            "SELECT id FROM entries
+            WHERE feed_id = ?1 AND (link = ?2 OR guid = ?3 OR link = ?3)
+            LIMIT 1",
+        )?;
+        let mut update_statement = tx.prepare(
+            "UPDATE entries
+            SET read_at = COALESCE(read_at, ?2),
+                flagged_at = COALESCE(flagged_at, ?3),
+                guid = COALESCE(guid, ?4)
+            WHERE id = ?1",
+        )?;
+        let mut insert_statement = tx.prepare(
+            "INSERT INTO entries (feed_id, title, author, pub_date, description, content, link, guid, read_at, flagged_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )?;
+
+        for entry in entries {
+            let existing = find_statement
+                .query_map(params![feed_id, entry.link, entry.guid], |row| {
+                    row.get::<_, EntryId>(0)
+                })?
+                .next()
+                .transpose()?;
+
+            if let Some(entry_id) = existing {
+                update_statement.execute(params![
+                    entry_id,
+                    entry.read_at,
+                    entry.flagged_at,
+                    entry.guid
+                ])?;
+                imported.matched += 1;
+            } else if entry.pub_date.is_some_and(|pub_date| pub_date < cutoff) {
+                // the next refresh would prune it anyway
+                imported.expired += 1;
+            } else {
+                insert_statement.execute(params![
+                    feed_id,
+                    entry.title,
+                    entry.author,
+                    entry.pub_date,
+                    entry.description,
+                    entry.content,
+                    entry.link,
+                    entry.guid,
+                    entry.read_at,
+                    entry.flagged_at,
+                    Utc::now()
+                ])?;
+                imported.added += 1;
+            }
+        }
+
+        Ok(imported)
+    })
+}
+
 pub fn get_feed_url(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<String> {
     let s: String = conn.query_row(
         "SELECT feed_link FROM feeds WHERE id=?1",
@@ -1009,6 +1997,33 @@ pub fn get_feed_url(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<Stri
     Ok(s)
 }
 
+/// the selectors of every scraped feed
+pub fn get_scrape_selectors(
+    conn: &rusqlite::Connection,
+) -> Result<std::collections::HashMap<FeedId, ScrapeSelectors>> {
+    let mut statement =
+        conn.prepare("SELECT id, scrape_selectors FROM feeds WHERE scrape_selectors IS NOT NULL")?;
+    let mut selectors = std::collections::HashMap::new();
+    for row in statement.query_map([], |row| Ok((row.get(0)?, row.get::<_, String>(1)?)))? {
+        let (feed_id, json) = row?;
+        selectors.insert(feed_id, serde_json::from_str(&json)?);
+    }
+    Ok(selectors)
+}
+
+fn get_feed_scrape_selectors(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+) -> Result<Option<ScrapeSelectors>> {
+    let s: Option<String> = conn.query_row(
+        "SELECT scrape_selectors FROM feeds WHERE id=?1",
+        [feed_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(s.map(|s| serde_json::from_str(&s)).transpose()?)
+}
+
 fn get_feed_latest_etag(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<Option<String>> {
     let s: Option<String> = conn.query_row(
         "SELECT latest_etag FROM feeds WHERE id=?1",
@@ -1023,19 +2038,39 @@ fn get_feed_latest_etag(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<
 }
 
 pub fn get_feeds(conn: &rusqlite::Connection) -> Result<Vec<Feed>> {
-    let mut statement = conn.prepare(
+    get_feeds_sorted(conn, FeedSort::Title)
+}
+
+pub fn get_feeds_sorted(conn: &rusqlite::Connection, sort: FeedSort) -> Result<Vec<Feed>> {
+    let order_by = match sort {
+        FeedSort::Title => "lower(title) ASC",
+        FeedSort::UnreadCount => {
+            "(SELECT COUNT(*) FROM entries WHERE entries.feed_id = feeds.id AND read_at IS NULL) DESC,
+            lower(title) ASC"
+        }
+        FeedSort::LastEntry => {
+            "(SELECT MAX(COALESCE(pub_date, inserted_at)) FROM entries WHERE entries.feed_id = feeds.id) DESC NULLS LAST,
+            lower(title) ASC"
+        }
+        FeedSort::LastRefresh => "refreshed_at DESC NULLS LAST, lower(title) ASC",
+        // feeds that were never moved go after the ones that were
+        FeedSort::Manual => "position ASC NULLS LAST, lower(title) ASC",
+    };
+
+    let mut statement = conn.prepare(&format!(
         "SELECT 
           id, 
           title, 
           feed_link, 
           link, 
           feed_kind, 
-          refreshed_at
+          refreshed_at,
+          parse_warnings
           -- inserted_at,
           -- updated_at,
           -- latest_etag
-        FROM feeds ORDER BY lower(title) ASC",
-    )?;
+        FROM feeds WHERE deleted_at IS NULL ORDER BY {order_by}"
+    ))?;
     let mut feeds = vec![];
     for feed in statement.query_map([], |row| {
         Ok(Feed {
@@ -1045,6 +2080,7 @@ pub fn get_feeds(conn: &rusqlite::Connection) -> Result<Vec<Feed>> {
             link: row.get(3)?,
             feed_kind: row.get(4)?,
             refreshed_at: row.get(5)?,
+            parse_warnings: row.get(6)?,
             // inserted_at: row.get(6)?,
             // updated_at: row.get(7)?,
             // latest_etag: row.get(8)?,
@@ -1056,8 +2092,88 @@ pub fn get_feeds(conn: &rusqlite::Connection) -> Result<Vec<Feed>> {
     Ok(feeds)
 }
 
+/// moves the feed `offset` places up (negative) or down (positive) in the manual order
+pub fn move_feed(conn: &mut rusqlite::Connection, feed_id: FeedId, offset: isize) -> Result<()> {
+    let mut feed_ids = get_feeds_sorted(conn, FeedSort::Manual)?
+        .into_iter()
+        .map(|feed| feed.id)
+        .collect::<Vec<_>>();
+
+    let Some(from) = feed_ids.iter().position(|id| *id == feed_id) else {
+        bail!("feed {feed_id} not found");
+    };
+    let to = from
+        .saturating_add_signed(offset)
+        .min(feed_ids.len().saturating_sub(1));
+
+    let moved = feed_ids.remove(from);
+    feed_ids.insert(to, moved);
+
+    // every feed gets a position, so the order is stable from now on
+    in_transaction(conn, |tx| {
+        let mut statement = tx.prepare("UPDATE feeds SET position = ?2 WHERE id = ?1")?;
+        for (position, feed_id) in feed_ids.iter().enumerate() {
+            statement.execute(params![feed_id, position])?;
+        }
+        Ok(())
+    })
+}
+
+/// how the entries of the feed are ordered
+pub fn get_feed_entry_sort(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<EntrySort> {
+    let entry_sort: Option<String> = conn.query_row(
+        "SELECT entry_sort FROM feeds WHERE id = ?1",
+        [feed_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(entry_sort
+        .map(|entry_sort| EntrySort::from_str(&entry_sort))
+        .transpose()?
+        .unwrap_or_default())
+}
+
+pub fn set_feed_entry_sort(
+    conn: &rusqlite::Connection,
+    feed_id: FeedId,
+    entry_sort: EntrySort,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds SET entry_sort = ?2 WHERE id = ?1",
+        params![feed_id, entry_sort.id()],
+    )?;
+
+    Ok(())
+}
+
+/// the columns entries are ordered by, each with whether it is descending.
+/// The id comes last so every entry has its own place, for pages to start right after one
+fn entry_sort_keys(sort: EntrySort, combined: bool) -> Vec<(&'static str, bool)> {
+    let newest_first = [
+        ("e.pub_date", true),
+        ("e.inserted_at", true),
+        ("e.id", true),
+    ];
+    match sort {
+        EntrySort::NewestFirst => newest_first.to_vec(),
+        EntrySort::OldestFirst => vec![
+            ("e.pub_date", false),
+            ("e.inserted_at", false),
+            ("e.id", false),
+        ],
+        EntrySort::Title => std::iter::once(("lower(e.title)", false))
+            .chain(newest_first)
+            .collect(),
+        EntrySort::Feed if combined => std::iter::once(("lower(f.title)", false))
+            .chain(newest_first)
+            .collect(),
+        EntrySort::Feed => newest_first.to_vec(),
+    }
+}
+
 pub fn get_feed_ids(conn: &rusqlite::Connection) -> Result<Vec<FeedId>> {
-    let mut statement = conn.prepare("SELECT id FROM feeds ORDER BY lower(title) ASC")?;
+    let mut statement =
+        conn.prepare("SELECT id FROM feeds WHERE deleted_at IS NULL ORDER BY lower(title) ASC")?;
     let mut ids = vec![];
     for id in statement.query_map([], |row| row.get(0))? {
         ids.push(id?)
@@ -1067,6 +2183,7 @@ pub fn get_feed_ids(conn: &rusqlite::Connection) -> Result<Vec<FeedId>> {
 }
 
 // count unread entries for a specific feed
+#[cfg(test)]
 pub fn count_unread_entries(conn: &rusqlite::Connection, feed_id: FeedId) -> Result<usize> {
     let count: i64 = conn.query_row(
         "SELECT COUNT(*) FROM entries WHERE feed_id = ?1 AND read_at IS NULL",
@@ -1076,44 +2193,199 @@ pub fn count_unread_entries(conn: &rusqlite::Connection, feed_id: FeedId) -> Res
     Ok(count as usize)
 }
 
-/// Returns entry counts per day for the last N days for sparkline display.
-/// Returns a vector of counts, one per day, from oldest to newest.
-pub fn get_feed_activity(
+/// What the feeds list shows next to a feed, see `get_feed_summaries`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FeedSummary {
+    pub unread: usize,
+    pub total: usize,
+    pub latest_entry_at: Option<DateTime<Utc>>,
+    /// entries per day for the last `days` days, oldest first, for the sparkline
+    pub activity: Vec<u64>,
+}
+
+/// Unread and total counts, the latest entry and `days` days of activity for every feed,
+/// in one query instead of a few per feed.
+pub fn get_feed_summaries(
     conn: &rusqlite::Connection,
-    feed_id: FeedId,
     days: u32,
-) -> Result<Vec<u64>> {
-    let start_date = Utc::now() - chrono::Duration::days(days as i64);
+) -> Result<std::collections::HashMap<FeedId, FeedSummary>> {
+    let now = Utc::now();
+    let start_date = now - chrono::Duration::days(days as i64);
 
-    // Query entries grouped by date
+    // one row per feed and day of activity,
+    // plus one with a NULL day for entries older than that
     let mut statement = conn.prepare(
-        "SELECT DATE(COALESCE(pub_date, inserted_at)) as day, COUNT(*) as count
-         FROM entries
-         WHERE feed_id = ?1
-         AND COALESCE(pub_date, inserted_at) >= ?2
-         GROUP BY day
-         ORDER BY day ASC",
+        "SELECT
+            feeds.id,
+            CASE WHEN COALESCE(entries.pub_date, entries.inserted_at) >= ?1
+                THEN DATE(COALESCE(entries.pub_date, entries.inserted_at))
+            END AS day,
+            COUNT(entries.id),
+            COALESCE(SUM(entries.id IS NOT NULL AND entries.read_at IS NULL), 0),
+            MAX(COALESCE(entries.pub_date, entries.inserted_at))
+        FROM feeds
+        LEFT JOIN entries ON entries.feed_id = feeds.id
+        WHERE feeds.deleted_at IS NULL
+        GROUP BY feeds.id, day",
     )?;
 
-    let mut day_counts: std::collections::HashMap<String, u64> = std::collections::HashMap::new();
-
-    for row in statement.query_map(params![feed_id, start_date], |row| {
-        Ok((row.get::<_, String>(0)?, row.get::<_, u64>(1)?))
+    // oldest to newest, days without entries stay 0
+    let day_indexes = (0..days)
+        .rev()
+        .enumerate()
+        .map(|(idx, days_ago)| {
+            let day = (now - chrono::Duration::days(days_ago as i64))
+                .format("%Y-%m-%d")
+                .to_string();
+            (day, idx)
+        })
+        .collect::<std::collections::HashMap<_, _>>();
+
+    let mut summaries: std::collections::HashMap<FeedId, FeedSummary> =
+        std::collections::HashMap::new();
+
+    for row in statement.query_map([start_date], |row| {
+        Ok((
+            row.get::<_, FeedId>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, usize>(2)?,
+            row.get::<_, usize>(3)?,
+            row.get::<_, Option<DateTime<Utc>>>(4)?,
+        ))
     })? {
-        let (day, count) = row?;
-        day_counts.insert(day, count);
+        let (feed_id, day, total, unread, latest_entry_at) = row?;
+
+        let summary = summaries.entry(feed_id).or_insert_with(|| FeedSummary {
+            activity: vec![0; days as usize],
+            ..Default::default()
+        });
+        summary.total += total;
+        summary.unread += unread;
+        summary.latest_entry_at = summary.latest_entry_at.max(latest_entry_at);
+        if let Some(idx) = day.and_then(|day| day_indexes.get(&day)) {
+            summary.activity[*idx] += total as u64;
+        }
+    }
+
+    Ok(summaries)
+}
+
+/// a feed that hasn't posted in this many days is stale
+const STALE_AFTER_DAYS: i64 = 90;
+/// a feed that hasn't posted in this many days is probably dead
+const DEAD_AFTER_DAYS: i64 = 365;
+/// a feed whose last this many refreshes failed is erroring
+const ERRORING_AFTER_FAILURES: usize = 3;
+/// posting frequency is averaged over this many days
+const FREQUENCY_DAYS: u32 = 90;
+
+/// How well a feed is doing, worst first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FeedHealthStatus {
+    Erroring,
+    Dead,
+    Stale,
+    Healthy,
+}
+
+impl Display for FeedHealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let out = match self {
+            FeedHealthStatus::Erroring => "erroring",
+            FeedHealthStatus::Dead => "dead",
+            FeedHealthStatus::Stale => "stale",
+            FeedHealthStatus::Healthy => "ok",
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+/// A feed with what's needed to tell whether it silently died
+#[derive(Clone, Debug)]
+pub struct FeedHealth {
+    pub feed: Feed,
+    /// failed refreshes since the last successful one
+    pub consecutive_errors: usize,
+    pub last_entry_at: Option<chrono::DateTime<Utc>>,
+    /// entries per 30 days, averaged over the last `FREQUENCY_DAYS`
+    pub entries_per_month: f64,
+}
+
+impl FeedHealth {
+    pub fn days_since_last_entry(&self) -> Option<i64> {
+        self.last_entry_at
+            .map(|last_entry_at| (Utc::now() - last_entry_at).num_days().max(0))
     }
 
-    // Fill in missing days with 0, from oldest to newest
-    let mut activity = Vec::with_capacity(days as usize);
-    for i in (0..days).rev() {
-        let date = (Utc::now() - chrono::Duration::days(i as i64))
-            .format("%Y-%m-%d")
-            .to_string();
-        activity.push(*day_counts.get(&date).unwrap_or(&0));
+    pub fn status(&self) -> FeedHealthStatus {
+        if self.consecutive_errors >= ERRORING_AFTER_FAILURES {
+            return FeedHealthStatus::Erroring;
+        }
+
+        match self.days_since_last_entry() {
+            None => FeedHealthStatus::Dead,
+            Some(days) if days >= DEAD_AFTER_DAYS => FeedHealthStatus::Dead,
+            Some(days) if days >= STALE_AFTER_DAYS => FeedHealthStatus::Stale,
+            Some(_) => FeedHealthStatus::Healthy,
+        }
     }
+}
+
+pub fn get_feeds_health(conn: &rusqlite::Connection) -> Result<Vec<FeedHealth>> {
+    let mut statement = conn.prepare(
+        "SELECT
+        feeds.id,
+        (SELECT COUNT(*) FROM refresh_log failed
+            WHERE failed.feed_id = feeds.id
+            AND failed.error IS NOT NULL
+            AND failed.id > COALESCE(
+                (SELECT MAX(ok.id) FROM refresh_log ok WHERE ok.feed_id = feeds.id AND ok.error IS NULL),
+                0
+            )),
+        (SELECT MAX(COALESCE(pub_date, inserted_at)) FROM entries WHERE entries.feed_id = feeds.id)
+        FROM feeds
+        WHERE deleted_at IS NULL",
+    )?;
 
-    Ok(activity)
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, FeedId>(0)?,
+                row.get::<_, usize>(1)?,
+                row.get::<_, Option<chrono::DateTime<Utc>>>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut feeds = get_feeds(conn)?
+        .into_iter()
+        .map(|feed| (feed.id, feed))
+        .collect::<std::collections::HashMap<_, _>>();
+
+    let summaries = get_feed_summaries(conn, FREQUENCY_DAYS)?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(feed_id, consecutive_errors, last_entry_at)| {
+            feeds
+                .remove(&feed_id)
+                .map(|feed| (feed, consecutive_errors, last_entry_at))
+        })
+        .map(|(feed, consecutive_errors, last_entry_at)| {
+            let entries = summaries
+                .get(&feed.id)
+                .map(|summary| summary.activity.iter().sum::<u64>())
+                .unwrap_or(0);
+
+            FeedHealth {
+                feed,
+                consecutive_errors,
+                last_entry_at,
+                entries_per_month: entries as f64 * 30.0 / FREQUENCY_DAYS as f64,
+            }
+        })
+        .collect())
 }
 
 pub fn get_entry_meta(conn: &rusqlite::Connection, entry_id: EntryId) -> Result<EntryMetadata> {
@@ -1148,6 +2420,30 @@ pub fn get_entry_meta(conn: &rusqlite::Connection, entry_id: EntryId) -> Result<
     Ok(result)
 }
 
+/// how far down the entry was scrolled when it was last left
+pub fn get_entry_scroll_position(conn: &rusqlite::Connection, entry_id: EntryId) -> Result<u16> {
+    let scroll_position: Option<u16> = conn.query_row(
+        "SELECT scroll_position FROM entries WHERE id = ?1",
+        [entry_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(scroll_position.unwrap_or(0))
+}
+
+pub fn set_entry_scroll_position(
+    conn: &rusqlite::Connection,
+    entry_id: EntryId,
+    scroll_position: u16,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE entries SET scroll_position = ?2 WHERE id = ?1",
+        params![entry_id, scroll_position],
+    )?;
+
+    Ok(())
+}
+
 pub fn get_entry_content(conn: &rusqlite::Connection, entry_id: EntryId) -> Result<EntryContent> {
     let result = conn.query_row(
         "SELECT content, description FROM entries WHERE id=?1",
@@ -1163,87 +2459,230 @@ pub fn get_entry_content(conn: &rusqlite::Connection, entry_id: EntryId) -> Resu
     Ok(result)
 }
 
-pub fn get_entries_metas(
-    conn: &rusqlite::Connection,
-    read_mode: &ReadMode,
-    feed_id: FeedId,
-) -> Result<Vec<EntryMetadata>> {
-    let read_at_predicate = match read_mode {
-        ReadMode::ShowUnread => "\nAND read_at IS NULL",
-        ReadMode::ShowRead => "\nAND read_at IS NOT NULL",
-        ReadMode::All => "\n",
-    };
+/// The entries of an entry list: one feed's, or the unread entries of every feed.
+/// Feeds can have tens of thousands of entries, so lists are loaded a page at a time
+/// with keyset pagination, starting right after or before an entry that is already loaded
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryQuery {
+    /// `None` for every feed outside the trash
+    pub feed_id: Option<FeedId>,
+    pub read_mode: ReadMode,
+    pub sort: EntrySort,
+}
 
-    // we get weird pubDate formats from feeds,
-    // so sort by inserted at as this as a stable order at least
-    let mut query = "SELECT 
-        id,
-        feed_id,
-        title,
-        -- author,
-        pub_date,
-        link,
-        read_at,
-        inserted_at
-        -- updated_at
-        FROM entries 
-        WHERE feed_id=?1"
-        .to_string();
+impl EntryQuery {
+    pub fn feed(feed_id: FeedId, read_mode: ReadMode, sort: EntrySort) -> Self {
+        EntryQuery {
+            feed_id: Some(feed_id),
+            read_mode,
+            sort,
+        }
+    }
 
-    query.push_str(read_at_predicate);
-    query.push_str("\nORDER BY pub_date DESC, inserted_at DESC");
+    pub fn combined_unread(sort: EntrySort) -> Self {
+        EntryQuery {
+            feed_id: None,
+            read_mode: ReadMode::ShowUnread,
+            sort,
+        }
+    }
 
-    let mut statement = conn.prepare(&query)?;
-    let mut entries = vec![];
-    for entry in statement.query_map([feed_id], |row| {
-        Ok(EntryMetadata {
-            id: row.get(0)?,
-            feed_id: row.get(1)?,
-            title: row.get(2)?,
-            // unused:
-            // author: row.get(3)?,
-            pub_date: row.get(3)?,
-            link: row.get(4)?,
-            read_at: row.get(5)?,
-            inserted_at: row.get(6)?,
-            // unused:
-            // updated_at: row.get(8)?,
-        })
-    })? {
-        entries.push(entry?)
+    fn sort_keys(&self) -> Vec<(&'static str, bool)> {
+        entry_sort_keys(self.sort, self.feed_id.is_none())
     }
 
-    Ok(entries)
-}
+    /// the where clause, and the values for its placeholders
+    fn filter(&self) -> (String, Vec<rusqlite::types::Value>) {
+        let mut filter = "f.deleted_at IS NULL".to_string();
+        let mut values = vec![];
+        if let Some(feed_id) = self.feed_id {
+            filter.push_str(" AND e.feed_id = ?");
+            values.push(feed_id.0.into());
+        }
+        filter.push_str(match self.read_mode {
+            ReadMode::ShowUnread => " AND e.read_at IS NULL",
+            ReadMode::ShowRead => " AND e.read_at IS NOT NULL",
+            ReadMode::All => "",
+        });
+        (filter, values)
+    }
 
-/// all unread entries across feeds, each paired with its feed title for combined view
-pub fn get_all_unread_entries_with_feed_name(
-    conn: &rusqlite::Connection,
-) -> Result<Vec<(String, EntryMetadata)>> {
-    let mut statement = conn.prepare(
-        "SELECT e.id, e.feed_id, e.title, e.pub_date, e.link, e.read_at, e.inserted_at, f.title AS feed_title
-         FROM entries e
-         JOIN feeds f ON e.feed_id = f.id
-         WHERE e.read_at IS NULL
-         ORDER BY e.pub_date DESC, e.inserted_at DESC",
-    )?;
-    let mut out = vec![];
-    for row in statement.query_map([], |row| {
-        let entry = EntryMetadata {
-            id: row.get(0)?,
-            feed_id: row.get(1)?,
-            title: row.get(2)?,
-            pub_date: row.get(3)?,
-            link: row.get(4)?,
-            read_at: row.get(5)?,
-            inserted_at: row.get(6)?,
+    /// a one row table `anchor` of the sort keys of an entry, for comparing other entries to
+    fn anchor(&self) -> String {
+        let keys = self
+            .sort_keys()
+            .iter()
+            .enumerate()
+            .map(|(i, (expr, _))| format!("{expr} AS k{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "(SELECT {keys} FROM entries e JOIN feeds f ON e.feed_id = f.id WHERE e.id = ?) AS anchor
+             CROSS JOIN "
+        )
+    }
+
+    /// entries that come after the anchor in the list, or before it.
+    /// sqlite sorts nulls first, which `<` and `>` alone don't know about
+    fn beyond_anchor(&self, after: bool) -> String {
+        let keys = self.sort_keys();
+        let alternatives = keys
+            .iter()
+            .enumerate()
+            .map(|(i, (expr, descending))| {
+                let mut terms = keys[..i]
+                    .iter()
+                    .enumerate()
+                    .map(|(j, (expr, _))| format!("{expr} IS anchor.k{j}"))
+                    .collect::<Vec<_>>();
+                terms.push(if after != *descending {
+                    format!(
+                        "({expr} IS NOT NULL AND (anchor.k{i} IS NULL OR {expr} > anchor.k{i}))"
+                    )
+                } else {
+                    format!(
+                        "(anchor.k{i} IS NOT NULL AND ({expr} IS NULL OR {expr} < anchor.k{i}))"
+                    )
+                });
+                format!("({})", terms.join(" AND "))
+            })
+            .collect::<Vec<_>>();
+        format!("({})", alternatives.join(" OR "))
+    }
+
+    fn order_by(&self, reversed: bool) -> String {
+        self.sort_keys()
+            .iter()
+            .map(|(expr, descending)| {
+                let direction = if *descending != reversed {
+                    "DESC"
+                } else {
+                    "ASC"
+                };
+                format!("{expr} {direction}")
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// how many entries the list has
+    pub fn count(&self, conn: &rusqlite::Connection) -> Result<usize> {
+        let (filter, values) = self.filter();
+        let count: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM entries e JOIN feeds f ON e.feed_id = f.id WHERE {filter}"
+            ),
+            rusqlite::params_from_iter(values),
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// where the entry is in the list, if it is in it
+    pub fn position(
+        &self,
+        conn: &rusqlite::Connection,
+        entry_id: EntryId,
+    ) -> Result<Option<usize>> {
+        let (filter, values) = self.filter();
+        let is_listed: bool = conn.query_row(
+            &format!(
+                "SELECT EXISTS(SELECT 1 FROM entries e JOIN feeds f ON e.feed_id = f.id
+                 WHERE {filter} AND e.id = ?)"
+            ),
+            rusqlite::params_from_iter(
+                values
+                    .iter()
+                    .cloned()
+                    .chain(std::iter::once(entry_id.0.into())),
+            ),
+            |row| row.get(0),
+        )?;
+        if !is_listed {
+            return Ok(None);
+        }
+
+        let before: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM {}entries e JOIN feeds f ON e.feed_id = f.id
+                 WHERE {filter} AND {}",
+                self.anchor(),
+                self.beyond_anchor(false)
+            ),
+            rusqlite::params_from_iter(std::iter::once(entry_id.0.into()).chain(values)),
+            |row| row.get(0),
+        )?;
+        Ok(Some(before as usize))
+    }
+
+    /// the entries of one page of the list, in list order, each paired with its feed's title
+    pub fn page(
+        &self,
+        conn: &rusqlite::Connection,
+        request: PageRequest<EntryId>,
+    ) -> Result<Vec<(String, EntryMetadata)>> {
+        let (filter, filter_values) = self.filter();
+        let mut values: Vec<rusqlite::types::Value> = vec![];
+
+        let (anchor, beyond_anchor, reversed, limit, offset) = match request {
+            PageRequest::At { offset, limit } => {
+                (String::new(), String::new(), false, limit, offset)
+            }
+            PageRequest::After(entry_id, limit) => {
+                values.push(entry_id.0.into());
+                (
+                    self.anchor(),
+                    format!(" AND {}", self.beyond_anchor(true)),
+                    false,
+                    limit,
+                    0,
+                )
+            }
+            PageRequest::Before(entry_id, limit) => {
+                values.push(entry_id.0.into());
+                (
+                    self.anchor(),
+                    format!(" AND {}", self.beyond_anchor(false)),
+                    true,
+                    limit,
+                    0,
+                )
+            }
         };
-        let feed_title: Option<String> = row.get(7)?;
-        Ok((feed_title.unwrap_or_else(|| "?".to_string()), entry))
-    })? {
-        out.push(row?);
+        values.extend(filter_values);
+        values.push((limit as i64).into());
+        values.push((offset as i64).into());
+
+        let mut statement = conn.prepare(&format!(
+            "SELECT e.id, e.feed_id, e.title, e.pub_date, e.link, e.read_at, e.inserted_at, f.title
+             FROM {anchor}entries e JOIN feeds f ON e.feed_id = f.id
+             WHERE {filter}{beyond_anchor}
+             ORDER BY {}
+             LIMIT ? OFFSET ?",
+            self.order_by(reversed)
+        ))?;
+        let mut entries = statement
+            .query_map(rusqlite::params_from_iter(values), |row| {
+                let entry = EntryMetadata {
+                    id: row.get(0)?,
+                    feed_id: row.get(1)?,
+                    title: row.get(2)?,
+                    pub_date: row.get(3)?,
+                    link: row.get(4)?,
+                    read_at: row.get(5)?,
+                    inserted_at: row.get(6)?,
+                };
+                let feed_title: Option<String> = row.get(7)?;
+                Ok((feed_title.unwrap_or_else(|| "?".to_string()), entry))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if reversed {
+            entries.reverse();
+        }
+
+        Ok(entries)
     }
-    Ok(out)
 }
 
 pub fn get_entries_links(
@@ -1294,6 +2733,23 @@ mod tests {
     use super::*;
     const ZCT: &str = "https://zeroclarkthirty.com/feed";
 
+    /// the whole list of entries, loaded the way the reader loads a page of them
+    fn load_entries(
+        conn: &rusqlite::Connection,
+        query: &EntryQuery,
+    ) -> Vec<(String, EntryMetadata)> {
+        let mut entries: crate::util::PagedList<(String, EntryMetadata)> =
+            crate::util::PagedList::new(query.count(conn).unwrap());
+        entries
+            .select(Some(0), |request| {
+                query.page(conn, request.map(|(_, entry)| entry.id))
+            })
+            .unwrap();
+        (0..entries.len())
+            .map(|idx| entries.get(idx).unwrap().clone())
+            .collect()
+    }
+
     #[test]
     fn atom_feed_with_default_namespace_parses() {
         // atom with default namespace (typical real-world atom)
@@ -1306,7 +2762,7 @@ mod tests {
     <link href="http://example.com/1"/>
   </entry>
 </feed>"#;
-        let result = parse_feed_streaming(atom.as_bytes(), "http://example.com/feed");
+        let result = parse_feed_streaming(atom.as_bytes(), "http://example.com/feed", None);
         let fa = result.expect("parse should succeed");
         assert!(matches!(fa.feed.feed_kind, FeedKind::Atom));
         assert_eq!(fa.entries.len(), 1, "expected one entry");
@@ -1334,20 +2790,217 @@ mod tests {
     <link href="http://example.com/1"/>
   </entry>
 </feed>"#;
-        let result = parse_feed_streaming(atom.as_bytes(), "http://example.com/feed");
+        let result = parse_feed_streaming(atom.as_bytes(), "http://example.com/feed", None);
         let fa = result.expect("parse should succeed");
         assert!(matches!(fa.feed.feed_kind, FeedKind::Atom));
         assert_eq!(fa.entries.len(), 1);
         assert_eq!(fa.entries[0].link.as_deref(), Some("http://example.com/1"));
     }
 
+    #[test]
+    fn atom_xhtml_content_keeps_markup() {
+        let atom = r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Test Feed</title>
+  <entry>
+    <title>Entry 1</title>
+    <link href="http://example.com/1"/>
+    <summary type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p>short</p></div></summary>
+    <content type="xhtml">
+      <div xmlns="http://www.w3.org/1999/xhtml">
+        <h1>Heading</h1>
+        <p>First &amp; <a href="http://example.com/x">link</a></p>
+        <ul><li>one</li><li>two</li></ul>
+      </div>
+    </content>
+  </entry>
+</feed>"#;
+        let fa = parse_feed_streaming(atom.as_bytes(), "http://example.com/feed", None).unwrap();
+        assert_eq!(fa.entries.len(), 1);
+        let content = fa.entries[0].content.as_deref().unwrap();
+        assert!(content.starts_with("<div"), "got {content}");
+        assert!(content.contains("<h1>Heading</h1>"));
+        assert!(content.contains("<li>two</li>"));
+        assert_eq!(fa.entries[0].link.as_deref(), Some("http://example.com/1"));
+    }
+
+    #[test]
+    fn atom_text_and_html_content_are_unchanged() {
+        let atom = r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <title>Text</title>
+    <content type="text">plain words</content>
+  </entry>
+  <entry>
+    <title>Html</title>
+    <content type="html">&lt;p&gt;escaped&lt;/p&gt;</content>
+  </entry>
+</feed>"#;
+        let fa = parse_feed_streaming(atom.as_bytes(), "http://example.com/feed", None).unwrap();
+        assert_eq!(fa.entries[0].content.as_deref(), Some("plain words"));
+        assert_eq!(fa.entries[1].content.as_deref(), Some("<p>escaped</p>"));
+    }
+
+    #[test]
+    fn atom_guid_is_the_entry_id_not_its_sources() {
+        let atom = r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <source><id>tag:origin.example.com,2024:feed</id></source>
+    <id>tag:example.com,2024:1</id>
+    <content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p>x</p></div></content>
+  </entry>
+  <entry>
+    <content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p>x</p></div></content>
+    <id>tag:example.com,2024:2</id>
+    <source><id>tag:origin.example.com,2024:feed</id></source>
+  </entry>
+</feed>"#;
+        let fa = parse_feed_streaming(atom.as_bytes(), "http://example.com/feed", None).unwrap();
+        assert_eq!(
+            fa.entries[0].guid.as_deref(),
+            Some("tag:example.com,2024:1")
+        );
+        assert_eq!(
+            fa.entries[1].guid.as_deref(),
+            Some("tag:example.com,2024:2")
+        );
+    }
+
+    #[test]
+    fn json_feed_parses() {
+        let json = r#"{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "Json Test",
+  "home_page_url": "https://example.com/",
+  "items": [
+    {
+      "id": "1",
+      "url": "https://example.com/1",
+      "title": "First",
+      "content_html": "<p>Hello</p>",
+      "date_published": "2024-01-02T03:04:05Z",
+      "authors": [{ "name": "Alice" }],
+      "attachments": [{ "url": "https://example.com/1.mp3", "mime_type": "audio/mpeg" }]
+    },
+    {
+      "id": 2,
+      "content_text": "line one\nline two\n\nsecond paragraph <b>",
+      "author": { "name": "Bob" }
+    }
+  ]
+}"#;
+        let fa =
+            parse_feed_streaming(json.as_bytes(), "https://example.com/feed.json", None).unwrap();
+        assert!(matches!(fa.feed.feed_kind, FeedKind::Json));
+        assert_eq!(fa.feed.title.as_deref(), Some("Json Test"));
+        assert_eq!(fa.feed.link.as_deref(), Some("https://example.com/"));
+        assert_eq!(fa.entries.len(), 2);
+
+        let first = &fa.entries[0];
+        assert_eq!(first.link.as_deref(), Some("https://example.com/1"));
+        assert_eq!(first.author.as_deref(), Some("Alice"));
+        assert!(first.pub_date.is_some());
+        let content = first.content.as_deref().unwrap();
+        assert!(content.starts_with("<p>Hello</p>"));
+        assert!(content.contains(r#"<a href="https://example.com/1.mp3">audio/mpeg</a>"#));
+
+        let second = &fa.entries[1];
+        assert_eq!(
+            second.link.as_deref(),
+            Some("2"),
+            "falls back to the item id"
+        );
+        assert_eq!(second.author.as_deref(), Some("Bob"));
+        assert_eq!(
+            second.content.as_deref(),
+            Some("<p>line one<br>line two</p><p>second paragraph &lt;b&gt;</p>")
+        );
+    }
+
+    #[test]
+    fn json_feed_is_detected_by_content_type() {
+        assert!(is_json_feed(
+            Some("application/feed+json; charset=utf-8"),
+            ""
+        ));
+        assert!(is_json_feed(None, "\n  {\"items\": []}"));
+        assert!(!is_json_feed(Some("application/rss+xml"), "<rss></rss>"));
+    }
+
+    #[test]
+    fn malformed_feed_is_repaired_with_warnings() {
+        let rss = "<?xml version=\"1.0\"?>
+<rss version=\"2.0\"><channel>
+  <title>Fish & Chips&nbsp;Weekly</title>
+  <item>
+    <title>Salt &amp; vinegar\u{0001}</title>
+    <link>http://example.com/1?a=1&b=2</link>
+    <description><![CDATA[<p>keep &nbsp; & this</p>]]></description>
+  </item>
+</channel></rss>";
+        let fa = parse_feed_streaming(rss.as_bytes(), "http://example.com/feed", None).unwrap();
+        assert_eq!(fa.feed.title.as_deref(), Some("Fish & Chips\u{a0}Weekly"));
+        assert_eq!(fa.entries.len(), 1);
+        assert_eq!(fa.entries[0].title.as_deref(), Some("Salt & vinegar"));
+        assert_eq!(
+            fa.entries[0].link.as_deref(),
+            Some("http://example.com/1?a=1&b=2")
+        );
+        let warnings = fa.feed.parse_warnings.join("\n");
+        assert!(warnings.contains("xml parsing error"), "{warnings}");
+        assert!(warnings.contains("escaped 2 bare '&'"), "{warnings}");
+        assert!(
+            warnings.contains("replaced 1 html-only entities"),
+            "{warnings}"
+        );
+        assert!(
+            warnings.contains("removed 1 control characters"),
+            "{warnings}"
+        );
+    }
+
+    #[test]
+    fn truncated_feed_keeps_complete_entries() {
+        let rss = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel>
+  <title>Truncated</title>
+  <item><title>One</title><link>http://example.com/1</link></item>
+  <item><title>Two</title><link>http://example.com/2</link></item>
+  <item><title>Thr"#;
+        let fa = parse_feed_streaming(rss.as_bytes(), "http://example.com/feed", None).unwrap();
+        let links: Vec<_> = fa.entries.iter().flat_map(|e| e.link.as_deref()).collect();
+        assert_eq!(links, ["http://example.com/1", "http://example.com/2"]);
+    }
+
+    #[test]
+    fn well_formed_feed_has_no_warnings() {
+        let rss = r#"<rss version="2.0"><channel><title>Fine</title>
+  <item><title>One</title><link>http://example.com/1</link></item>
+</channel></rss>"#;
+        let fa = parse_feed_streaming(rss.as_bytes(), "http://example.com/feed", None).unwrap();
+        assert!(fa.feed.parse_warnings.is_empty());
+    }
+
+    #[test]
+    fn non_feed_is_still_an_error() {
+        let err = parse_feed_streaming("<html><body>nope</body></html>".as_bytes(), "x", None)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("could not determine feed type"));
+    }
+
     #[test]
     fn it_fetches() {
-        let http_client = ureq::AgentBuilder::new()
-            .timeout_read(std::time::Duration::from_secs(5))
-            .build();
-        let feed_and_entries = fetch_feed(&http_client, ZCT, None).unwrap();
-        if let FeedResponse::CacheMiss(feed_and_entries) = feed_and_entries {
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
+        let feed_and_entries = fetch_feed(&http_client, ZCT, None, None, None).unwrap();
+        if let FeedResponse::CacheMiss {
+            feed_and_entries, ..
+        } = feed_and_entries
+        {
             assert!(!feed_and_entries.entries.is_empty())
         } else {
             panic!("somehow got a cached response when passing no etag")
@@ -1356,12 +3009,12 @@ mod tests {
 
     #[test]
     fn it_subscribes_to_a_feed() {
-        let http_client = ureq::AgentBuilder::new()
-            .timeout_read(std::time::Duration::from_secs(5))
-            .build();
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
         let mut conn = rusqlite::Connection::open_in_memory().unwrap();
         initialize_db(&mut conn).unwrap();
-        subscribe_to_feed(&http_client, &mut conn, ZCT).unwrap();
+        subscribe_to_feed(&http_client, &mut conn, &CredentialStore::default(), ZCT).unwrap();
         let count: i64 = conn
             .query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))
             .unwrap();
@@ -1391,28 +3044,710 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_and_normalize_feed_url_accepts_local_sources() {
+        assert_eq!(
+            validate_and_normalize_feed_url("file:///tmp/feed.xml").unwrap(),
+            "file:///tmp/feed.xml"
+        );
+        assert_eq!(
+            validate_and_normalize_feed_url(" exec:make-feed --weekly ").unwrap(),
+            "exec:make-feed --weekly"
+        );
+        assert!(validate_and_normalize_feed_url("ftp://example.com/feed").is_err());
+    }
+
+    /// Serves `responses` on `ip` one connection at a time, by request path,
+    /// and returns the address and the requests it got
+    fn serve(
+        ip: &str,
+        responses: Vec<(&'static str, String)>,
+    ) -> (String, std::sync::mpsc::Receiver<String>) {
+        use std::io::{BufRead, Write};
+
+        let listener = std::net::TcpListener::bind((ip, 0)).unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = std::io::BufReader::new(stream.unwrap());
+                let mut request = String::new();
+                while stream.read_line(&mut request).unwrap() > 2 {}
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let response = responses
+                    .iter()
+                    .find(|(response_path, _)| *response_path == path)
+                    .map_or("HTTP/1.1 404 Not Found\r\n", |(_, response)| response);
+                write!(
+                    stream.get_mut(),
+                    "{response}Content-Length: 0\r\nConnection: close\r\n\r\n"
+                )
+                .unwrap();
+                if tx.send(request).is_err() {
+                    break;
+                }
+            }
+        });
+        (address, rx)
+    }
+
+    #[test]
+    fn credentials_only_follow_redirects_on_the_feed_host() {
+        // 127.0.0.2 is another host, but still this machine
+        let (other, other_requests) = serve("127.0.0.2", vec![]);
+        let (feed_host, feed_requests) = serve(
+            "127.0.0.1",
+            vec![
+                (
+                    "/away",
+                    format!("HTTP/1.1 302 Found\r\nLocation: http://{other}/feed\r\n"),
+                ),
+                (
+                    "/moved",
+                    "HTTP/1.1 301 Moved Permanently\r\nLocation: /feed\r\n".to_string(),
+                ),
+                ("/feed", "HTTP/1.1 200 OK\r\n".to_string()),
+            ],
+        );
+
+        let credentials = FeedCredentials {
+            basic: Some(crate::credentials::BasicAuth {
+                username: "me".to_string(),
+                password: "secret".to_string(),
+            }),
+            headers: [("X-Api-Key".to_string(), "key".to_string())].into(),
+            ..Default::default()
+        };
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
+        let sent_credentials = |request: &str| {
+            let request = request.to_lowercase();
+            (
+                request.contains("authorization: basic"),
+                request.contains("x-api-key: key"),
+            )
+        };
+
+        fetch_http_body(
+            &http_client,
+            &format!("http://{feed_host}/moved"),
+            None,
+            Some(&credentials),
+        )
+        .unwrap();
+        for _ in 0..2 {
+            assert_eq!(
+                sent_credentials(&feed_requests.recv().unwrap()),
+                (true, true)
+            );
+        }
+
+        // the other host gets a 404 and nothing else
+        fetch_http_body(
+            &http_client,
+            &format!("http://{feed_host}/away"),
+            None,
+            Some(&credentials),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            sent_credentials(&feed_requests.recv().unwrap()),
+            (true, true)
+        );
+        assert_eq!(
+            sent_credentials(&other_requests.recv().unwrap()),
+            (false, false)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn subscribes_to_file_exec_and_filter_sources() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-sources-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let feed_path = dir.join("feed.xml");
+        std::fs::write(
+            &feed_path,
+            r#"<rss version="2.0"><channel><title>CI</title><link>https://ci.example.com</link>
+<item><title>build 1</title><link>https://ci.example.com/1</link></item>
+</channel></rss>"#,
+        )
+        .unwrap();
+
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let file_url = url::Url::from_file_path(&feed_path).unwrap().to_string();
+        let exec_url = format!("exec:cat {}", feed_path.display());
+        let filter_url = format!("filter:sed s/CI/Nightly/:{file_url}");
+
+        for url in [&file_url, &exec_url, &filter_url] {
+            subscribe_to_feed(&http_client, &mut conn, &CredentialStore::default(), url).unwrap();
+        }
+
+        let feeds = get_feeds(&conn).unwrap();
+        assert_eq!(feeds.len(), 3);
+        assert_eq!(feeds[0].title.as_deref(), Some("CI"));
+        assert_eq!(feeds[2].title.as_deref(), Some("Nightly"));
+
+        let err = subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            &CredentialStore::default(),
+            "exec:echo no feed here >&2; exit 1",
+        )
+        .unwrap_err();
+        assert!(format!("{err:?}").contains("no feed here"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn scraped_feeds_refresh_with_their_selectors() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-scrape-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let page_path = dir.join("news.html");
+        let page = |posts: &str| {
+            format!(
+                "<html><head><title>News</title></head><body><main>{posts}</main></body></html>"
+            )
+        };
+        std::fs::write(
+            &page_path,
+            page(r#"<article><a href="https://example.com/1">One</a></article>"#),
+        )
+        .unwrap();
+
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let url = url::Url::from_file_path(&page_path).unwrap().to_string();
+        let selectors = ScrapeSelectors {
+            item: "main article".to_string(),
+            ..Default::default()
+        };
+        let feed_id = subscribe_to_scraped_feed(
+            &http_client,
+            &mut conn,
+            &CredentialStore::default(),
+            &url,
+            &selectors,
+        )
+        .unwrap();
+
+        let feed = get_feed(&conn, feed_id).unwrap();
+        assert!(matches!(feed.feed_kind, FeedKind::Scraped));
+        assert_eq!(feed.title.as_deref(), Some("News"));
+        assert_eq!(
+            get_feed_scrape_selectors(&conn, feed_id).unwrap(),
+            Some(selectors)
+        );
+
+        std::fs::write(
+            &page_path,
+            page(
+                r#"<article><a href="https://example.com/2">Two</a></article>
+<article><a href="https://example.com/1">One</a></article>"#,
+            ),
+        )
+        .unwrap();
+        refresh_feed(
+            &http_client,
+            &mut conn,
+            &CredentialStore::default(),
+            feed_id,
+        )
+        .unwrap();
+
+        let entries = EntryQuery::feed(feed_id, ReadMode::All, EntrySort::NewestFirst);
+        assert_eq!(entries.count(&conn).unwrap(), 2);
+
+        // a kind this version doesn't know is an error, not a crash
+        conn.execute(
+            "UPDATE feeds SET feed_kind = 'Podcast' WHERE id = ?1",
+            [feed_id],
+        )
+        .unwrap();
+        let err = get_feed(&conn, feed_id).err().unwrap();
+        assert!(format!("{err:#}").contains("Podcast is not a valid FeedKind"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn refresh_feed_does_not_add_any_items_if_there_are_no_new_items() {
-        let http_client = ureq::AgentBuilder::new()
-            .timeout_read(std::time::Duration::from_secs(5))
-            .build();
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
         let mut conn = rusqlite::Connection::open_in_memory().unwrap();
         initialize_db(&mut conn).unwrap();
-        subscribe_to_feed(&http_client, &mut conn, ZCT).unwrap();
+        subscribe_to_feed(&http_client, &mut conn, &CredentialStore::default(), ZCT).unwrap();
         let feed_id = 1.into();
-        let old_unread = get_entries_metas(&conn, &ReadMode::ShowUnread, feed_id).unwrap();
-        refresh_feed(&http_client, &mut conn, feed_id).unwrap();
-        let after_refresh_unread =
-            get_entries_metas(&conn, &ReadMode::ShowUnread, feed_id).unwrap();
+        let unread = EntryQuery::feed(feed_id, ReadMode::ShowUnread, EntrySort::NewestFirst);
+        let old_unread = unread.count(&conn).unwrap();
+        refresh_feed(
+            &http_client,
+            &mut conn,
+            &CredentialStore::default(),
+            feed_id,
+        )
+        .unwrap();
+        let after_refresh_unread = unread.count(&conn).unwrap();
         // refresh never adds when remote unchanged; count may drop due to retention prune
         assert!(
-            after_refresh_unread.len() <= old_unread.len(),
+            after_refresh_unread <= old_unread,
             "refresh must not add items"
         );
         let e = get_entry_meta(&conn, 1.into()).unwrap();
         e.mark_as_read(&conn).unwrap();
-        let new_unread = get_entries_metas(&conn, &ReadMode::ShowUnread, feed_id).unwrap();
-        assert_eq!(new_unread.len(), after_refresh_unread.len() - 1);
+        assert_eq!(unread.count(&conn).unwrap(), after_refresh_unread - 1);
+    }
+
+    #[test]
+    fn refreshes_are_logged_with_their_error_chain() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-refresh-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let feed_path = dir.join("feed.xml");
+        std::fs::write(
+            &feed_path,
+            r#"<rss version="2.0"><channel><title>Log</title>
+<item><title>one</title><link>https://example.com/1</link></item>
+</channel></rss>"#,
+        )
+        .unwrap();
+
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let url = url::Url::from_file_path(&feed_path).unwrap().to_string();
+        let feed_id =
+            subscribe_to_feed(&http_client, &mut conn, &CredentialStore::default(), &url).unwrap();
+
+        refresh_feed(
+            &http_client,
+            &mut conn,
+            &CredentialStore::default(),
+            feed_id,
+        )
+        .unwrap();
+        assert!(get_failing_feeds(&conn).unwrap().is_empty());
+
+        std::fs::remove_file(&feed_path).unwrap();
+        refresh_feed(
+            &http_client,
+            &mut conn,
+            &CredentialStore::default(),
+            feed_id,
+        )
+        .unwrap_err();
+
+        let log = get_refresh_log(&conn, feed_id, 10).unwrap();
+        assert_eq!(log.len(), 2);
+        let error = log[0].error.as_deref().unwrap();
+        assert!(error.starts_with("Failed to fetch feed"));
+        assert!(error.lines().count() > 1);
+        assert!(log[1].error.is_none());
+        assert!(log[1].bytes.unwrap() > 0);
+        assert_eq!(log[1].new_entries, 0);
+
+        let failing = get_failing_feeds(&conn).unwrap();
+        assert_eq!(failing.len(), 1);
+        assert_eq!(failing[0].0, feed_id);
+
+        delete_feed(&mut conn, feed_id).unwrap();
+        assert!(get_refresh_log(&conn, feed_id, 10).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn feed_health_reflects_errors_and_last_entry() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-health-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let fresh_path = dir.join("fresh.xml");
+        let old_path = dir.join("old.xml");
+        std::fs::write(
+            &fresh_path,
+            r#"<rss version="2.0"><channel><title>Fresh</title>
+<item><title>one</title><link>https://example.com/fresh/1</link></item>
+</channel></rss>"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &old_path,
+            r#"<rss version="2.0"><channel><title>Old</title>
+<item><title>one</title><link>https://example.com/old/1</link>
+<pubDate>Mon, 01 Jan 2018 00:00:00 GMT</pubDate></item>
+</channel></rss>"#,
+        )
+        .unwrap();
+
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let fresh_url = url::Url::from_file_path(&fresh_path).unwrap().to_string();
+        let old_url = url::Url::from_file_path(&old_path).unwrap().to_string();
+        let fresh_id = subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            &CredentialStore::default(),
+            &fresh_url,
+        )
+        .unwrap();
+        let old_id = subscribe_to_feed(
+            &http_client,
+            &mut conn,
+            &CredentialStore::default(),
+            &old_url,
+        )
+        .unwrap();
+
+        let health_of = |conn: &rusqlite::Connection, feed_id| {
+            get_feeds_health(conn)
+                .unwrap()
+                .into_iter()
+                .find(|health| health.feed.id == feed_id)
+                .unwrap()
+        };
+
+        let fresh = health_of(&conn, fresh_id);
+        assert_eq!(fresh.status(), FeedHealthStatus::Healthy);
+        assert_eq!(fresh.consecutive_errors, 0);
+        assert!(fresh.entries_per_month > 0.0);
+
+        let old = health_of(&conn, old_id);
+        assert_eq!(old.status(), FeedHealthStatus::Dead);
+        assert!(old.days_since_last_entry().unwrap() > DEAD_AFTER_DAYS);
+
+        std::fs::remove_file(&fresh_path).unwrap();
+        for _ in 0..ERRORING_AFTER_FAILURES {
+            refresh_feed(
+                &http_client,
+                &mut conn,
+                &CredentialStore::default(),
+                fresh_id,
+            )
+            .unwrap_err();
+        }
+
+        let fresh = health_of(&conn, fresh_id);
+        assert_eq!(fresh.consecutive_errors, ERRORING_AFTER_FAILURES);
+        assert_eq!(fresh.status(), FeedHealthStatus::Erroring);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn trashed_feeds_keep_their_read_state_until_purged() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-trash-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let feed_path = dir.join("feed.xml");
+        std::fs::write(
+            &feed_path,
+            r#"<rss version="2.0"><channel><title>Trash</title>
+<item><title>one</title><link>https://example.com/1</link></item>
+<item><title>two</title><link>https://example.com/2</link></item>
+</channel></rss>"#,
+        )
+        .unwrap();
+
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let url = url::Url::from_file_path(&feed_path).unwrap().to_string();
+        let feed_id =
+            subscribe_to_feed(&http_client, &mut conn, &CredentialStore::default(), &url).unwrap();
+
+        let read_state = mark_feed_read(&mut conn, feed_id).unwrap();
+        assert_eq!(read_state.len(), 2);
+        assert_eq!(count_unread_entries(&conn, feed_id).unwrap(), 0);
+        restore_read_state(&mut conn, &read_state[..1]).unwrap();
+        assert_eq!(count_unread_entries(&conn, feed_id).unwrap(), 1);
+        let summary = &get_feed_summaries(&conn, 14).unwrap()[&feed_id];
+        assert_eq!((summary.unread, summary.total), (1, 2));
+        assert_eq!(summary.activity.len(), 14);
+        assert!(summary.latest_entry_at.is_some());
+
+        trash_feed(&mut conn, feed_id).unwrap();
+        assert!(get_feeds(&conn).unwrap().is_empty());
+        assert!(get_feed_ids(&conn).unwrap().is_empty());
+        assert_eq!(
+            EntryQuery::combined_unread(EntrySort::NewestFirst)
+                .count(&conn)
+                .unwrap(),
+            0
+        );
+
+        let trashed = get_trashed_feeds(&conn).unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].entries, 2);
+        assert_eq!(trashed[0].read_entries, 1);
+        assert_eq!(trashed[0].days_until_purge(30), 29);
+        assert_eq!(trashed[0].days_until_purge(7), 6);
+
+        // subscribing again brings the trashed feed back instead of failing on the unique url,
+        // fetched again and without the selectors it had before
+        conn.execute(
+            "UPDATE feeds SET scrape_selectors = '{\"item\": \"li\"}' WHERE id = ?1",
+            [feed_id],
+        )
+        .unwrap();
+        std::fs::write(
+            &feed_path,
+            r#"<rss version="2.0"><channel><title>Trash</title>
+<item><title>one</title><link>https://example.com/1</link></item>
+<item><title>two</title><link>https://example.com/2</link></item>
+<item><title>three</title><link>https://example.com/3</link></item>
+</channel></rss>"#,
+        )
+        .unwrap();
+        let resubscribed_id =
+            subscribe_to_feed(&http_client, &mut conn, &CredentialStore::default(), &url).unwrap();
+        assert_eq!(resubscribed_id, feed_id);
+        assert_eq!(count_unread_entries(&conn, feed_id).unwrap(), 2);
+        assert_eq!(get_feed_scrape_selectors(&conn, feed_id).unwrap(), None);
+        assert!(get_trashed_feeds(&conn).unwrap().is_empty());
+
+        trash_feed(&mut conn, feed_id).unwrap();
+        assert!(purge_expired_trash(&mut conn, 1).unwrap().is_empty());
+        assert_eq!(purge_expired_trash(&mut conn, 0).unwrap(), vec![url]);
+        assert!(get_trashed_feeds(&conn).unwrap().is_empty());
+        assert_eq!(count_unread_entries(&conn, feed_id).unwrap(), 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn feeds_and_entries_are_sorted_as_configured() {
+        let dir = std::env::temp_dir().join(format!("rss-tui-sort-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let http_client =
+            crate::http::HttpClient::new(std::time::Duration::from_secs(5), &Default::default())
+                .unwrap();
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let mut subscribe_file = |name: &str, items: &str| {
+            let path = dir.join(format!("{name}.xml"));
+            std::fs::write(
+                &path,
+                format!(
+                    r#"<rss version="2.0"><channel><title>{name}</title>{items}</channel></rss>"#
+                ),
+            )
+            .unwrap();
+            let url = url::Url::from_file_path(&path).unwrap().to_string();
+            subscribe_to_feed(&http_client, &mut conn, &CredentialStore::default(), &url).unwrap()
+        };
+
+        let serial = subscribe_file(
+            "a serial",
+            r#"<item><title>chapter 2</title><link>https://example.com/2</link><pubDate>Tue, 02 Jan 2024 00:00:00 GMT</pubDate></item>
+<item><title>chapter 1</title><link>https://example.com/1</link><pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate></item>
+<item><title>chapter 3</title><link>https://example.com/3</link><pubDate>Wed, 03 Jan 2024 00:00:00 GMT</pubDate></item>"#,
+        );
+        let busy = subscribe_file(
+            "b busy",
+            r#"<item><title>x</title><link>https://example.com/x</link></item>
+<item><title>y</title><link>https://example.com/y</link></item>
+<item><title>z</title><link>https://example.com/z</link></item>
+<item><title>w</title><link>https://example.com/w</link></item>"#,
+        );
+        let quiet = subscribe_file("c quiet", "");
+
+        let ids = |conn: &rusqlite::Connection, sort| {
+            get_feeds_sorted(conn, sort)
+                .unwrap()
+                .into_iter()
+                .map(|feed| feed.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(&conn, FeedSort::Title), vec![serial, busy, quiet]);
+        assert_eq!(ids(&conn, FeedSort::UnreadCount), vec![busy, serial, quiet]);
+        assert_eq!(ids(&conn, FeedSort::LastEntry), vec![busy, serial, quiet]);
+
+        move_feed(&mut conn, quiet, -2).unwrap();
+        assert_eq!(ids(&conn, FeedSort::Manual), vec![quiet, serial, busy]);
+        move_feed(&mut conn, quiet, 10).unwrap();
+        assert_eq!(ids(&conn, FeedSort::Manual), vec![serial, busy, quiet]);
+
+        let titles = |conn: &rusqlite::Connection| {
+            let sort = get_feed_entry_sort(conn, serial).unwrap();
+            load_entries(conn, &EntryQuery::feed(serial, ReadMode::All, sort))
+                .into_iter()
+                .map(|(_, entry)| entry.title.unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles(&conn), vec!["chapter 3", "chapter 2", "chapter 1"]);
+        set_feed_entry_sort(&conn, serial, EntrySort::OldestFirst).unwrap();
+        assert_eq!(
+            get_feed_entry_sort(&conn, serial).unwrap(),
+            EntrySort::OldestFirst
+        );
+        let stored: String = conn
+            .query_row(
+                "SELECT entry_sort FROM feeds WHERE id = ?1",
+                [serial],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored, "oldest_first");
+        // as stored before there were ids
+        conn.execute(
+            "UPDATE feeds SET entry_sort = 'oldest first' WHERE id = ?1",
+            [serial],
+        )
+        .unwrap();
+        assert_eq!(
+            get_feed_entry_sort(&conn, serial).unwrap(),
+            EntrySort::OldestFirst
+        );
+        assert_eq!(titles(&conn), vec!["chapter 1", "chapter 2", "chapter 3"]);
+        // the order is per feed
+        assert_eq!(
+            get_feed_entry_sort(&conn, busy).unwrap(),
+            EntrySort::NewestFirst
+        );
+
+        let combined = load_entries(&conn, &EntryQuery::combined_unread(EntrySort::Feed));
+        assert_eq!(combined.len(), 7);
+        assert_eq!(combined[0].0, "a serial");
+        assert_eq!(combined[6].0, "b busy");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn entry_pages_line_up_with_the_whole_list() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let now = Utc::now();
+        let mut feed_ids = vec![];
+        for feed in ["b feed", "a feed"] {
+            conn.execute(
+                "INSERT INTO feeds (title, feed_link) VALUES (?1, ?2)",
+                params![feed, format!("https://example.com/{feed}.xml")],
+            )
+            .unwrap();
+            let feed_id = FeedId(conn.last_insert_rowid());
+            feed_ids.push(feed_id);
+            for entry in 0..30 {
+                // ties in every sort column, and nulls where feeds leave things out
+                let pub_date = (entry % 7 != 0).then(|| now - chrono::Duration::days(entry / 3));
+                let title = (entry % 5 != 0).then(|| format!("Entry {}", entry % 4));
+                let read_at = (entry % 4 == 0).then_some(now);
+                conn.execute(
+                    "INSERT INTO entries (feed_id, title, pub_date, read_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![feed_id, title, pub_date, read_at],
+                )
+                .unwrap();
+            }
+        }
+
+        let sorts = [
+            EntrySort::NewestFirst,
+            EntrySort::OldestFirst,
+            EntrySort::Title,
+            EntrySort::Feed,
+        ];
+        for sort in sorts {
+            for query in [
+                EntryQuery::combined_unread(sort),
+                EntryQuery::feed(feed_ids[0], ReadMode::All, sort),
+                EntryQuery::feed(feed_ids[1], ReadMode::ShowRead, sort),
+            ] {
+                let ids = |entries: Vec<(String, EntryMetadata)>| {
+                    entries
+                        .into_iter()
+                        .map(|(_, entry)| entry.id)
+                        .collect::<Vec<_>>()
+                };
+                let len = query.count(&conn).unwrap();
+                let all = ids(query
+                    .page(
+                        &conn,
+                        PageRequest::At {
+                            offset: 0,
+                            limit: len,
+                        },
+                    )
+                    .unwrap());
+                assert_eq!(all.len(), len);
+
+                let mut forwards = ids(query
+                    .page(
+                        &conn,
+                        PageRequest::At {
+                            offset: 0,
+                            limit: 4,
+                        },
+                    )
+                    .unwrap());
+                while forwards.len() < len {
+                    let last = *forwards.last().unwrap();
+                    forwards.extend(ids(query.page(&conn, PageRequest::After(last, 4)).unwrap()));
+                }
+                assert_eq!(forwards, all, "{query:?}");
+
+                let mut backwards = vec![*all.last().unwrap()];
+                while backwards.len() < len {
+                    let mut page = ids(query
+                        .page(&conn, PageRequest::Before(backwards[0], 4))
+                        .unwrap());
+                    page.append(&mut backwards);
+                    backwards = page;
+                }
+                assert_eq!(backwards, all, "{query:?}");
+
+                for (idx, entry_id) in all.iter().enumerate() {
+                    assert_eq!(query.position(&conn, *entry_id).unwrap(), Some(idx));
+                }
+            }
+        }
+
+        // entries without a date go last, newest first
+        let newest_first = EntryQuery::feed(feed_ids[0], ReadMode::All, EntrySort::NewestFirst)
+            .page(
+                &conn,
+                PageRequest::At {
+                    offset: 0,
+                    limit: 30,
+                },
+            )
+            .unwrap();
+        assert!(newest_first[0].1.pub_date.is_some());
+        assert!(newest_first[29].1.pub_date.is_none());
+        // and read entries are not in the combined unread view
+        let read = newest_first
+            .iter()
+            .find(|(_, entry)| entry.read_at.is_some());
+        assert_eq!(
+            EntryQuery::combined_unread(EntrySort::NewestFirst)
+                .position(&conn, read.unwrap().1.id)
+                .unwrap(),
+            None
+        );
     }
 
     #[test]
@@ -1462,4 +3797,74 @@ mod tests {
         // assert that no further entries have been inserted
         assert_eq!(count, 1);
     }
+
+    /// Times the feed summaries on a synthetic 1,000 feed database,
+    /// next to the per-feed unread counts every frame used to run. Run it with
+    /// `cargo test --release bench_feed_summaries -- --ignored --nocapture`
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_feed_summaries() {
+        const FEEDS: usize = 1_000;
+        const ENTRIES_PER_FEED: usize = 50;
+
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        initialize_db(&mut conn).unwrap();
+
+        let now = Utc::now();
+        let tx = conn.transaction().unwrap();
+        for feed in 0..FEEDS {
+            tx.execute(
+                "INSERT INTO feeds (title, feed_link) VALUES (?1, ?2)",
+                params![
+                    format!("feed {feed}"),
+                    format!("https://example.com/{feed}.xml")
+                ],
+            )
+            .unwrap();
+            let feed_id = tx.last_insert_rowid();
+            for entry in 0..ENTRIES_PER_FEED {
+                // spread over the last 100 days, a third of them read
+                let hours_ago = ((feed * 7 + entry * 41) % 2400) as i64;
+                let read_at = (entry % 3 == 0).then_some(now);
+                tx.execute(
+                    "INSERT INTO entries (feed_id, title, pub_date, link, read_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        feed_id,
+                        format!("entry {entry}"),
+                        now - chrono::Duration::hours(hours_ago),
+                        format!("https://example.com/{feed}/{entry}"),
+                        read_at
+                    ],
+                )
+                .unwrap();
+            }
+        }
+        tx.commit().unwrap();
+
+        let feed_ids = get_feed_ids(&conn).unwrap();
+
+        let started_at = std::time::Instant::now();
+        for feed_id in &feed_ids {
+            count_unread_entries(&conn, *feed_id).unwrap();
+        }
+        let per_feed = started_at.elapsed();
+
+        let started_at = std::time::Instant::now();
+        let summaries = get_feed_summaries(&conn, 14).unwrap();
+        let aggregated = started_at.elapsed();
+
+        assert_eq!(summaries.len(), FEEDS);
+        assert!(
+            summaries
+                .values()
+                .all(|summary| summary.total == ENTRIES_PER_FEED)
+        );
+
+        println!(
+            "{FEEDS} feeds with {ENTRIES_PER_FEED} entries each: \
+            {per_feed:?} counting unread entries feed by feed, \
+            {aggregated:?} for every summary at once"
+        );
+    }
 }
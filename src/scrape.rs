@@ -0,0 +1,300 @@
+// synthesizing feeds from html pages that have none, using css selectors
+
+use crate::credentials::CredentialStore;
+use crate::{ScrapeOptions, ScrapePreviewOptions};
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use scraper::{ElementRef, Html, Selector};
+
+/// Where to find entries on a page.
+/// Stored as JSON in the feeds table next to the page url, so refreshes scrape the same way.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize, clap::Args)]
+pub struct ScrapeSelectors {
+    /// matches one element per entry, e.g. `article` or `ul.posts > li`
+    #[arg(long)]
+    pub item: String,
+    /// the entry title within an item. Defaults to the item's own text
+    #[arg(long)]
+    pub title: Option<String>,
+    /// the entry link within an item, read from `href`.
+    /// Defaults to the first link in the item
+    #[arg(long)]
+    pub link: Option<String>,
+    /// the entry date within an item, read from `datetime` if present, otherwise the text
+    #[arg(long)]
+    pub date: Option<String>,
+    /// the entry summary within an item, kept as html
+    #[arg(long)]
+    pub summary: Option<String>,
+}
+
+/// One entry found on a page
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScrapedItem {
+    pub title: Option<String>,
+    pub link: Option<String>,
+    pub pub_date: Option<DateTime<Utc>>,
+    pub summary: Option<String>,
+}
+
+/// Everything found on a page
+#[derive(Debug)]
+pub struct ScrapedPage {
+    pub title: Option<String>,
+    pub items: Vec<ScrapedItem>,
+    /// what didn't match, shown like a parse warning
+    pub warnings: Vec<String>,
+}
+
+fn parse_selector(selector: &str) -> Result<Selector> {
+    Selector::parse(selector).map_err(|e| anyhow::anyhow!("invalid css selector {selector:?}: {e}"))
+}
+
+// all text below an element, with whitespace runs collapsed
+fn collapsed_text(element: ElementRef) -> Option<String> {
+    let text = element.text().collect::<Vec<_>>().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() { None } else { Some(text) }
+}
+
+pub fn scrape(html: &str, page_url: &str, selectors: &ScrapeSelectors) -> Result<ScrapedPage> {
+    let item_selector = parse_selector(&selectors.item)?;
+    let title_selector = selectors.title.as_deref().map(parse_selector).transpose()?;
+    let link_selector = parse_selector(selectors.link.as_deref().unwrap_or("a[href]"))?;
+    let date_selector = selectors.date.as_deref().map(parse_selector).transpose()?;
+    let summary_selector = selectors
+        .summary
+        .as_deref()
+        .map(parse_selector)
+        .transpose()?;
+
+    let document = Html::parse_document(html);
+    let base_url = url::Url::parse(page_url).ok();
+
+    let page_title = document
+        .select(&parse_selector("title")?)
+        .next()
+        .and_then(collapsed_text);
+
+    let mut items = vec![];
+    let mut items_without_link = 0;
+    let mut unparseable_dates = 0;
+
+    for item in document.select(&item_selector) {
+        let title = match &title_selector {
+            Some(selector) => item.select(selector).next().and_then(collapsed_text),
+            None => collapsed_text(item),
+        };
+
+        // the item itself can be the link, e.g. `a.post`
+        let href = if link_selector.matches(&item) {
+            item.attr("href")
+        } else {
+            item.select(&link_selector)
+                .find_map(|element| element.attr("href"))
+        };
+        let link = href.map(|href| match &base_url {
+            Some(base_url) => base_url
+                .join(href.trim())
+                .map(|url| url.to_string())
+                .unwrap_or_else(|_| href.trim().to_string()),
+            None => href.trim().to_string(),
+        });
+
+        // refreshes dedupe on the link, so an entry without one could never be updated
+        let Some(link) = link else {
+            items_without_link += 1;
+            continue;
+        };
+
+        let pub_date = date_selector
+            .as_ref()
+            .and_then(|selector| item.select(selector).next())
+            .and_then(|element| {
+                element
+                    .attr("datetime")
+                    .map(|datetime| datetime.to_string())
+                    .or_else(|| collapsed_text(element))
+            })
+            .and_then(|date| {
+                let parsed = diligent_date_parser::parse_date(&date);
+                if parsed.is_none() {
+                    unparseable_dates += 1;
+                }
+                parsed.map(|date| date.with_timezone(&Utc))
+            });
+
+        let summary = summary_selector
+            .as_ref()
+            .and_then(|selector| item.select(selector).next())
+            .map(|element| element.inner_html().trim().to_string())
+            .filter(|summary| !summary.is_empty());
+
+        items.push(ScrapedItem {
+            title,
+            link: Some(link),
+            pub_date,
+            summary,
+        });
+    }
+
+    if items.is_empty() {
+        if items_without_link > 0 {
+            bail!(
+                "{items_without_link} items matched {:?}, but none of them had a link. check the link selector",
+                selectors.item
+            );
+        }
+        bail!("no items matched {:?} on {page_url}", selectors.item);
+    }
+
+    let mut warnings = vec![];
+    if items_without_link > 0 {
+        warnings.push(format!("skipped {items_without_link} items without a link"));
+    }
+    if unparseable_dates > 0 {
+        warnings.push(format!("unable to parse {unparseable_dates} dates"));
+    }
+
+    Ok(ScrapedPage {
+        title: page_title,
+        items,
+        warnings,
+    })
+}
+
+/// Prints what `selectors` find on the page, without subscribing
+pub(crate) fn preview(options: ScrapePreviewOptions) -> Result<()> {
+    let http_client =
+        crate::http::HttpClient::new(options.network_timeout, &options.config.network)?;
+    http_client.print_warnings();
+    let credentials = CredentialStore::for_database(&options.database_path)?;
+
+    let page =
+        crate::rss::scrape_page(&http_client, &credentials, &options.url, &options.selectors)?;
+
+    println!(
+        "{}: {} items",
+        page.title.as_deref().unwrap_or(&options.url),
+        page.items.len()
+    );
+
+    for item in &page.items {
+        println!();
+        println!("title:   {}", item.title.as_deref().unwrap_or("-"));
+        println!("link:    {}", item.link.as_deref().unwrap_or("-"));
+        println!(
+            "date:    {}",
+            item.pub_date
+                .map(|date| date.to_rfc3339())
+                .unwrap_or_else(|| "-".to_string())
+        );
+        if let Some(summary) = &item.summary {
+            let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+            let summary: String = summary.chars().take(120).collect();
+            println!("summary: {summary}");
+        }
+    }
+
+    for warning in &page.warnings {
+        eprintln!("WARNING: {warning}");
+    }
+
+    Ok(())
+}
+
+/// Subscribes to the page as a scraped feed
+pub(crate) fn subscribe(options: ScrapeOptions) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(&options.database_path)?;
+
+    crate::rss::initialize_db(&mut conn)?;
+
+    let http_client =
+        crate::http::HttpClient::new(options.network_timeout, &options.config.network)?;
+    http_client.print_warnings();
+    let credentials = CredentialStore::for_database(&options.database_path)?;
+
+    let url = crate::rss::validate_and_normalize_feed_url(&options.url)?;
+
+    crate::rss::subscribe_to_scraped_feed(
+        &http_client,
+        &mut conn,
+        &credentials,
+        &url,
+        &options.selectors,
+    )?;
+
+    eprintln!("{url}: subscribed");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><title> Example  News </title></head><body>
+<ul class="posts">
+  <li>
+    <h2><a href="/posts/2">Second post</a></h2>
+    <time datetime="2024-05-02T10:00:00Z">May 2</time>
+    <div class="lede"><p>The <b>second</b> one.</p></div>
+  </li>
+  <li>
+    <h2><a href="https://other.example.com/1">First post</a></h2>
+    <span class="date">Wed, 01 May 2024 09:00:00 GMT</span>
+  </li>
+  <li><h2>No link here</h2></li>
+</ul>
+</body></html>"#;
+
+    #[test]
+    fn scrapes_items_with_selectors() {
+        let selectors = ScrapeSelectors {
+            item: "ul.posts > li".to_string(),
+            title: Some("h2".to_string()),
+            link: None,
+            date: Some("time, .date".to_string()),
+            summary: Some(".lede".to_string()),
+        };
+
+        let page = scrape(PAGE, "https://example.com/news/", &selectors).unwrap();
+
+        assert_eq!(page.title.as_deref(), Some("Example News"));
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.warnings, vec!["skipped 1 items without a link"]);
+
+        let second = &page.items[0];
+        assert_eq!(second.title.as_deref(), Some("Second post"));
+        assert_eq!(second.link.as_deref(), Some("https://example.com/posts/2"));
+        assert_eq!(
+            second.pub_date.unwrap().to_rfc3339(),
+            "2024-05-02T10:00:00+00:00"
+        );
+        assert_eq!(
+            second.summary.as_deref(),
+            Some("<p>The <b>second</b> one.</p>")
+        );
+
+        let first = &page.items[1];
+        assert_eq!(first.link.as_deref(), Some("https://other.example.com/1"));
+        assert!(first.pub_date.is_some());
+        assert!(first.summary.is_none());
+    }
+
+    #[test]
+    fn no_matches_is_an_error() {
+        let selectors = ScrapeSelectors {
+            item: "article".to_string(),
+            ..Default::default()
+        };
+        assert!(scrape(PAGE, "https://example.com/", &selectors).is_err());
+
+        let selectors = ScrapeSelectors {
+            item: "article[".to_string(),
+            ..Default::default()
+        };
+        let err = scrape(PAGE, "https://example.com/", &selectors).unwrap_err();
+        assert!(err.to_string().contains("invalid css selector"));
+    }
+}
@@ -0,0 +1,144 @@
+// remembering where you were between runs
+
+use crate::modes::{EntrySort, FeedSort, PaneLayout, PaneRatios, ReadMode};
+use crate::rss::{EntryId, FeedId};
+use anyhow::Result;
+use rusqlite::OptionalExtension;
+
+/// What was on screen when rss-tui last quit, restored on the next start unless `--fresh` is passed.
+/// Stored as JSON in the `session_state` table, so it lives with the database it describes.
+#[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct SessionState {
+    pub feed_id: Option<FeedId>,
+    pub entry_id: Option<EntryId>,
+    pub view: SessionView,
+    /// whether the open entry was opened from the combined unread view
+    pub came_from_combined_unread: bool,
+    pub read_mode: ReadMode,
+    pub feed_sort: FeedSort,
+    /// the order of the combined unread view, feeds remember their own
+    pub combined_sort: EntrySort,
+    pub theme: crate::ui::Theme,
+    pub show_help: bool,
+    pub layout: PaneLayout,
+    pub pane_ratios: PaneRatios,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            feed_id: None,
+            entry_id: None,
+            view: SessionView::Feeds,
+            came_from_combined_unread: false,
+            read_mode: ReadMode::ShowUnread,
+            feed_sort: FeedSort::Title,
+            combined_sort: EntrySort::NewestFirst,
+            theme: crate::ui::Theme::Hacker,
+            show_help: true,
+            layout: PaneLayout::TwoPane,
+            pane_ratios: PaneRatios::default(),
+        }
+    }
+}
+
+/// The views worth coming back to.
+/// Dashboards like feed health and the trash are restored as the feeds list.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum SessionView {
+    Feeds,
+    Entries,
+    Entry,
+    CombinedUnread,
+}
+
+const SESSION_KEY: &str = "session";
+
+pub fn load(conn: &rusqlite::Connection) -> Result<Option<SessionState>> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM session_state WHERE key = ?1",
+            [SESSION_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    Ok(value
+        .map(|value| serde_json::from_str(&value))
+        .transpose()?)
+}
+
+pub fn save(conn: &rusqlite::Connection, state: &SessionState) -> Result<()> {
+    conn.execute(
+        "INSERT INTO session_state (key, value) VALUES (?1, ?2)
+        ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+        [SESSION_KEY, &serde_json::to_string(state)?],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_and_loads_the_session() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::rss::initialize_db(&mut conn).unwrap();
+
+        assert!(load(&conn).unwrap().is_none());
+
+        let mut state = SessionState {
+            feed_id: Some(3.into()),
+            entry_id: Some(42.into()),
+            view: SessionView::Entry,
+            came_from_combined_unread: true,
+            read_mode: ReadMode::All,
+            feed_sort: FeedSort::Manual,
+            combined_sort: EntrySort::Feed,
+            theme: crate::ui::Theme::Boring,
+            show_help: false,
+            layout: PaneLayout::ThreePane,
+            pane_ratios: PaneRatios {
+                feeds: 25,
+                entries: 50,
+            },
+        };
+        save(&conn, &state).unwrap();
+        assert_eq!(load(&conn).unwrap().unwrap(), state);
+
+        state.view = SessionView::Feeds;
+        save(&conn, &state).unwrap();
+        assert_eq!(load(&conn).unwrap().unwrap().view, SessionView::Feeds);
+
+        // fields added later fall back to their defaults
+        conn.execute(
+            "UPDATE session_state SET value = ?1",
+            [r#"{"show_help": false}"#],
+        )
+        .unwrap();
+        let loaded = load(&conn).unwrap().unwrap();
+        assert!(!loaded.show_help);
+        assert_eq!(loaded.read_mode, ReadMode::ShowUnread);
+        assert_eq!(loaded.layout, PaneLayout::TwoPane);
+        assert_eq!(loaded.pane_ratios, PaneRatios::default());
+
+        // the sort is stored by a stable id, sessions saved with the variant name still load
+        save(&conn, &state).unwrap();
+        let value: String = conn
+            .query_row("SELECT value FROM session_state", [], |row| row.get(0))
+            .unwrap();
+        assert!(value.contains(r#""combined_sort":"feed""#), "{value}");
+        conn.execute(
+            "UPDATE session_state SET value = ?1",
+            [r#"{"combined_sort": "OldestFirst"}"#],
+        )
+        .unwrap();
+        assert_eq!(
+            load(&conn).unwrap().unwrap().combined_sort,
+            EntrySort::OldestFirst
+        );
+    }
+}
@@ -0,0 +1,245 @@
+// where a feed's body comes from: http, a local file, or a command
+
+use anyhow::{Context, Result, bail};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// A feed url as stored in the feeds table, parsed into where to read it from.
+///
+/// - `https://example.com/feed.xml` and `http://...`
+/// - `file:///home/me/feeds/ci.xml`
+/// - `exec:~/bin/make-feed.sh --weekly`, the command's stdout is the feed
+/// - `filter:~/bin/fix-dates.sh:https://example.com/feed.xml`, the body of the inner
+///   source is piped through the command before parsing, like newsboat's `filter:`
+#[derive(Clone, Debug, PartialEq)]
+pub enum FeedSource {
+    Http(String),
+    File(PathBuf),
+    Exec(String),
+    Filter {
+        command: String,
+        source: Box<FeedSource>,
+    },
+}
+
+impl FeedSource {
+    pub fn parse(url: &str) -> Result<Self> {
+        if let Some(command) = url.strip_prefix("exec:") {
+            let command = command.trim();
+            if command.is_empty() {
+                bail!("exec: feed source needs a command, like exec:~/bin/make-feed.sh");
+            }
+            return Ok(FeedSource::Exec(command.to_string()));
+        }
+
+        if let Some(rest) = url.strip_prefix("filter:") {
+            let Some((command, inner)) = rest.split_once(':') else {
+                bail!("filter: feed source must look like filter:<command>:<url>");
+            };
+            if command.trim().is_empty() {
+                bail!("filter: feed source needs a command, like filter:<command>:<url>");
+            }
+            return Ok(FeedSource::Filter {
+                command: command.trim().to_string(),
+                source: Box::new(FeedSource::parse(inner)?),
+            });
+        }
+
+        let parsed = url::Url::parse(url).with_context(|| format!("invalid feed url: {url}"))?;
+
+        match parsed.scheme() {
+            "http" | "https" => Ok(FeedSource::Http(url.to_string())),
+            "file" => parsed
+                .to_file_path()
+                .map(FeedSource::File)
+                .map_err(|_| anyhow::anyhow!("file url must be an absolute path: {url}")),
+            other => bail!(
+                "unsupported url scheme '{other}', only http, https, file, exec: and filter: are allowed"
+            ),
+        }
+    }
+
+    /// whether fetching this source runs a local command
+    pub fn runs_command(&self) -> bool {
+        matches!(self, FeedSource::Exec(_) | FeedSource::Filter { .. })
+    }
+}
+
+/// Runs `command` through the shell, feeding it `stdin`, and returns its stdout.
+/// The command, and anything it started, is killed if it runs longer than `timeout`.
+/// When it fails, its stderr is the error message.
+pub fn run_command(command: &str, stdin: Option<&[u8]>, timeout: Duration) -> Result<Vec<u8>> {
+    let mut child = shell_command(command)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("unable to run `{command}`"))?;
+
+    // stdin, stdout and stderr each get their own thread,
+    // so a command that writes a lot before reading everything can't deadlock us
+    let stdin_writer = match (child.stdin.take(), stdin) {
+        (Some(mut child_stdin), Some(stdin)) => {
+            let stdin = stdin.to_vec();
+            // a command that exits without reading its input is not an error
+            Some(std::thread::spawn(move || {
+                let _ = child_stdin.write_all(&stdin);
+            }))
+        }
+        _ => None,
+    };
+    let stdout_reader = read_in_background(child.stdout.take());
+    let stderr_reader = read_in_background(child.stderr.take());
+
+    // a background process the command started can hold stdout open after the shell exits,
+    // so the command is only done once both pipes are closed too
+    let started_at = Instant::now();
+    let mut status = None;
+    let status = loop {
+        if status.is_none() {
+            status = child.try_wait()?;
+        }
+        if let Some(status) = status
+            && stdout_reader.is_finished()
+            && stderr_reader.is_finished()
+        {
+            break status;
+        }
+
+        if started_at.elapsed() > timeout {
+            // the reader threads are left behind, they end once the killed processes close the pipes
+            kill_process_group(&mut child);
+            let _ = child.wait();
+            bail!("`{command}` timed out after {timeout:?}");
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    };
+
+    if let Some(stdin_writer) = stdin_writer {
+        let _ = stdin_writer.join();
+    }
+    let stdout = stdout_reader.join().unwrap_or_default();
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        let stderr = stderr.trim();
+        if stderr.is_empty() {
+            bail!("`{command}` failed ({status})");
+        } else {
+            bail!("`{command}` failed ({status}): {stderr}");
+        }
+    }
+
+    Ok(stdout)
+}
+
+fn read_in_background<R: Read + Send + 'static>(
+    pipe: Option<R>,
+) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    use std::os::unix::process::CommandExt;
+
+    let mut shell = Command::new("sh");
+    // its own process group, so a timeout can kill whatever the command started too
+    shell.arg("-c").arg(command).process_group(0);
+    shell
+}
+
+#[cfg(unix)]
+fn kill_process_group(child: &mut std::process::Child) {
+    // the group id is the shell's pid, see `shell_command`
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    let _ = child.kill();
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn kill_process_group(child: &mut std::process::Child) {
+    let _ = child.kill();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sources() {
+        assert_eq!(
+            FeedSource::parse("https://example.com/feed").unwrap(),
+            FeedSource::Http("https://example.com/feed".to_string())
+        );
+        assert_eq!(
+            FeedSource::parse("file:///tmp/feed.xml").unwrap(),
+            FeedSource::File(PathBuf::from("/tmp/feed.xml"))
+        );
+        assert_eq!(
+            FeedSource::parse("exec:make-feed --weekly").unwrap(),
+            FeedSource::Exec("make-feed --weekly".to_string())
+        );
+        assert_eq!(
+            FeedSource::parse("filter:fix.sh:https://example.com/feed").unwrap(),
+            FeedSource::Filter {
+                command: "fix.sh".to_string(),
+                source: Box::new(FeedSource::Http("https://example.com/feed".to_string())),
+            }
+        );
+        assert!(FeedSource::parse("ftp://example.com/feed").is_err());
+        assert!(FeedSource::parse("exec:  ").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_commands_with_stdin_timeouts_and_stderr() {
+        let out = run_command("tr a-z A-Z", Some(b"hello"), Duration::from_secs(5)).unwrap();
+        assert_eq!(out, b"HELLO");
+
+        let err = run_command("echo broken >&2; exit 3", None, Duration::from_secs(5)).unwrap_err();
+        assert!(err.to_string().contains("broken"));
+
+        let err = run_command("sleep 5", None, Duration::from_millis(100)).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn kills_what_a_timed_out_command_started() {
+        let started_at = Instant::now();
+        let err = run_command("sleep 60 & sleep 60", None, Duration::from_millis(200)).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(started_at.elapsed() < Duration::from_secs(10));
+
+        // the background sleep holds stdout open after the shell exits
+        let started_at = Instant::now();
+        let err = run_command("echo hi; sleep 60 &", None, Duration::from_millis(200)).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(started_at.elapsed() < Duration::from_secs(10));
+    }
+}
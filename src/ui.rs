@@ -1,17 +1,16 @@
 // UI rendering with Ratatui
 
 use ratatui::Frame;
-use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{
-    Block, Borders, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
-    Tabs, Wrap,
+    Block, Borders, Gauge, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+    ScrollbarState, Tabs, Wrap,
 };
-use std::rc::Rc;
 
 use crate::app::AppImpl;
-use crate::modes::{Mode, ReadMode, Selected};
+use crate::modes::{Mode, PaneLayout, PaneRatios, ReadMode, Selected};
 use crate::rss::EntryMetadata;
 use crate::util::sanitize_for_display;
 use chrono::Utc;
@@ -20,7 +19,7 @@ use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 const PINK: Color = Color::Rgb(255, 150, 167);
 
 // theme system
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum Theme {
     Boring,
     Hacker,
@@ -158,6 +157,8 @@ pub struct Symbols {
     pub error: &'static str,
     pub feed_type_rss: &'static str,
     pub feed_type_atom: &'static str,
+    pub feed_type_json: &'static str,
+    pub feed_type_scraped: &'static str,
 }
 
 impl Default for Symbols {
@@ -170,6 +171,8 @@ impl Default for Symbols {
             error: "⚠ ",
             feed_type_rss: " [RSS]",
             feed_type_atom: " [ATOM]",
+            feed_type_json: " [JSON]",
+            feed_type_scraped: " [HTML]",
         }
     }
 }
@@ -253,8 +256,125 @@ fn command_bar_height(f: &Frame, app: &AppImpl) -> u16 {
     }
 }
 
-pub fn predraw(f: &Frame, app: &AppImpl) -> Rc<[Rect]> {
+/// Where everything goes on screen, decided by the layout before drawing
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Panes {
+    pub version_bar: Rect,
+    /// the feeds, info and help column, hidden while reading in the zen layout
+    pub feeds: Option<Rect>,
+    /// the entries list, the article or a dashboard
+    pub main: Rect,
+    /// the article next to the entries list, in the three pane layout
+    pub preview: Option<Rect>,
+    pub command_bar: Rect,
+}
+
+impl Panes {
+    /// the area an opened entry is drawn in, which the entry text is wrapped to
+    pub fn article(&self) -> Rect {
+        self.preview.unwrap_or(self.main)
+    }
+}
+
+/// A list as drawn, so a click can be mapped back to the item under it
+#[derive(Clone, Debug, Default)]
+pub struct ListTarget {
+    /// the list inside its borders
+    area: Rect,
+    item_heights: Vec<usize>,
+    /// the index of the item the heights start at, for paged lists that only draw what fits
+    first: usize,
+}
+
+impl ListTarget {
+    fn new(area: Rect, item_heights: Vec<usize>) -> Self {
+        Self {
+            area: area.inner(Margin::new(1, 1)),
+            item_heights,
+            first: 0,
+        }
+    }
+
+    fn starting_at(self, first: usize) -> Self {
+        Self { first, ..self }
+    }
+
+    /// the index of the item at `position`, for a list scrolled down to `offset`
+    pub fn item_at(&self, position: Position, offset: usize) -> Option<usize> {
+        if !self.area.contains(position) {
+            return None;
+        }
+
+        let mut bottom = self.area.y as usize;
+        for (idx, height) in self
+            .item_heights
+            .iter()
+            .enumerate()
+            .map(|(idx, height)| (self.first + idx, height))
+            .skip(offset.saturating_sub(self.first))
+        {
+            bottom += height;
+            if (position.y as usize) < bottom {
+                return Some(idx);
+            }
+        }
+
+        None
+    }
+}
+
+fn item_heights(items: &[ListItem]) -> Vec<usize> {
+    items.iter().map(|item| item.height()).collect()
+}
+
+/// Everything clickable from the last draw
+#[derive(Clone, Debug, Default)]
+pub struct ClickTargets {
+    pub feeds: Option<ListTarget>,
+    pub entries: Option<ListTarget>,
+    pub combined_entries: Option<ListTarget>,
+    pub feed_health: Option<ListTarget>,
+    pub trash: Option<ListTarget>,
+    pub profiles: Option<ListTarget>,
+    pub tabs: Vec<(Rect, ReadMode)>,
+    /// the text of the open entry, inside its borders
+    pub article: Option<Rect>,
+    /// what the article looks like on screen, one char per cell, to find links in
+    pub article_rows: Vec<String>,
+}
+
+/// the text on screen in `area`, one string per row with one char per cell
+fn screen_rows(f: &mut Frame, area: Rect) -> Vec<String> {
+    let buffer = f.buffer_mut();
+    area.rows()
+        .map(|row| {
+            row.columns()
+                .map(|cell| buffer[cell].symbol().chars().next().unwrap_or(' '))
+                .collect()
+        })
+        .collect()
+}
+
+pub fn predraw(f: &Frame, app: &AppImpl) -> Panes {
     let bar_height = command_bar_height(f, app);
+    split_panes(
+        f.area(),
+        bar_height,
+        app.layout,
+        app.pane_ratios,
+        &app.selected,
+        app.show_diagnostics,
+    )
+}
+
+fn split_panes(
+    area: Rect,
+    bar_height: u16,
+    layout: PaneLayout,
+    ratios: PaneRatios,
+    selected: &Selected,
+    show_diagnostics: bool,
+) -> Panes {
     let vertical = Layout::default()
         .constraints(
             [
@@ -265,49 +385,120 @@ pub fn predraw(f: &Frame, app: &AppImpl) -> Rc<[Rect]> {
             .as_ref(),
         )
         .direction(Direction::Vertical)
-        .split(f.area());
+        .split(area);
     let version_bar = vertical[0];
     let main_area = vertical[1];
-    let bottom_bar = vertical[2];
-    let horizontal = Layout::default()
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
-        .direction(Direction::Horizontal)
-        .split(main_area);
-    // [version_bar, left, right, command_bar]
-    let mut out = vec![version_bar];
-    out.extend(horizontal.iter().cloned());
-    out.push(bottom_bar);
-    out.into()
-}
+    let command_bar = vertical[2];
+
+    let feeds_and_rest = |direction| {
+        Layout::default()
+            .constraints(
+                [
+                    Constraint::Percentage(ratios.feeds),
+                    Constraint::Percentage(100 - ratios.feeds),
+                ]
+                .as_ref(),
+            )
+            .direction(direction)
+            .split(main_area)
+    };
 
-pub fn draw(f: &mut Frame, chunks: Rc<[Rect]>, app: &mut AppImpl) {
-    if chunks.len() >= 4 {
-        draw_version_line(f, chunks[0], app);
-    }
-    let (left_idx, right_idx, bar_idx) = if chunks.len() >= 4 {
-        (1, 2, 3)
-    } else {
-        (0, 1, 2)
+    let (feeds, main, preview) = match layout {
+        PaneLayout::Zen if matches!(selected, Selected::Entry(_)) => (None, main_area, None),
+        PaneLayout::TwoPane | PaneLayout::Zen => {
+            let horizontal = feeds_and_rest(Direction::Horizontal);
+            (Some(horizontal[0]), horizontal[1], None)
+        }
+        PaneLayout::Stacked => {
+            let stacked = feeds_and_rest(Direction::Vertical);
+            (Some(stacked[0]), stacked[1], None)
+        }
+        PaneLayout::ThreePane => {
+            let horizontal = feeds_and_rest(Direction::Horizontal);
+            // dashboards get the whole width, there is no entry to preview next to them
+            let has_preview = match selected {
+                Selected::Feeds => !show_diagnostics,
+                Selected::Entries | Selected::CombinedUnread | Selected::Entry(_) => true,
+                Selected::Health | Selected::Trash | Selected::Profiles | Selected::None => false,
+            };
+            if has_preview {
+                let right = Layout::default()
+                    .constraints(
+                        [
+                            Constraint::Percentage(ratios.entries),
+                            Constraint::Percentage(100 - ratios.entries),
+                        ]
+                        .as_ref(),
+                    )
+                    .direction(Direction::Horizontal)
+                    .split(horizontal[1]);
+                (Some(horizontal[0]), right[0], Some(right[1]))
+            } else {
+                (Some(horizontal[0]), horizontal[1], None)
+            }
+        }
     };
 
-    draw_info_column(f, chunks[left_idx], app);
+    Panes {
+        version_bar,
+        feeds,
+        main,
+        preview,
+        command_bar,
+    }
+}
+
+pub fn draw(f: &mut Frame, panes: Panes, app: &mut AppImpl) {
+    app.click_targets = ClickTargets::default();
+
+    draw_version_line(f, panes.version_bar, app);
+
+    if let Some(feeds) = panes.feeds {
+        draw_info_column(f, feeds, app);
+    }
 
-    match &app.selected {
-        Selected::Feeds | Selected::Entries => {
-            draw_entries(f, chunks[right_idx], app);
+    match (&app.selected, panes.preview) {
+        (Selected::CombinedUnread, Some(preview)) => {
+            draw_combined_entries(f, panes.main, app);
+            draw_preview(f, preview, app);
         }
-        Selected::CombinedUnread => {
-            draw_combined_entries(f, chunks[right_idx], app);
+        (Selected::Entry(_), Some(preview)) => {
+            if app.came_from_combined_unread {
+                draw_combined_entries(f, panes.main, app);
+            } else {
+                draw_entries(f, panes.main, app);
+            }
+            draw_entry(f, preview, app);
+        }
+        (_, Some(preview)) => {
+            draw_entries(f, panes.main, app);
+            draw_preview(f, preview, app);
         }
-        Selected::Entry(_entry_meta) => {
-            draw_entry(f, chunks[right_idx], app);
+        (Selected::Feeds, None) if app.show_diagnostics => {
+            draw_diagnostics(f, panes.main, app);
         }
-        Selected::None => draw_entries(f, chunks[right_idx], app),
+        (Selected::Feeds | Selected::Entries, None) => {
+            draw_entries(f, panes.main, app);
+        }
+        (Selected::CombinedUnread, None) => {
+            draw_combined_entries(f, panes.main, app);
+        }
+        (Selected::Health, None) => {
+            draw_feed_health(f, panes.main, app);
+        }
+        (Selected::Trash, None) => {
+            draw_trash(f, panes.main, app);
+        }
+        (Selected::Profiles, None) => {
+            draw_profiles(f, panes.main, app);
+        }
+        (Selected::Entry(_entry_meta), None) => {
+            draw_entry(f, panes.main, app);
+        }
+        (Selected::None, None) => draw_entries(f, panes.main, app),
     }
 
-    if bar_idx < chunks.len() {
-        draw_command_bar(f, chunks[bar_idx], app);
-    }
+    draw_command_bar(f, panes.command_bar, app);
 }
 
 fn draw_info_column(f: &mut Frame, area: Rect, app: &mut AppImpl) {
@@ -505,7 +696,8 @@ fn draw_feeds(f: &mut Frame, area: Rect, app: &mut AppImpl) {
             let feed_title = sanitize_for_display(feed.title.as_deref().unwrap_or("No title"));
 
             // get unread count for this feed
-            let unread_count = crate::rss::count_unread_entries(&app.conn, feed.id).unwrap_or(0);
+            let summary = app.feed_summaries.get(&feed.id);
+            let unread_count = summary.map_or(0, |summary| summary.unread);
 
             // build the display with styled components
             let mut display_spans = Vec::new();
@@ -527,6 +719,8 @@ fn draw_feeds(f: &mut Frame, area: Rect, app: &mut AppImpl) {
             let feed_type_badge = match feed.feed_kind {
                 crate::rss::FeedKind::Rss => symbols.feed_type_rss,
                 crate::rss::FeedKind::Atom => symbols.feed_type_atom,
+                crate::rss::FeedKind::Json => symbols.feed_type_json,
+                crate::rss::FeedKind::Scraped => symbols.feed_type_scraped,
             };
             display_spans.push(Span::styled(
                 feed_type_badge,
@@ -543,11 +737,11 @@ fn draw_feeds(f: &mut Frame, area: Rect, app: &mut AppImpl) {
             }
 
             // add sparkline if available
-            if let Some(data) = app.feed_activity_cache.get(&feed.id)
-                && !data.is_empty()
+            if let Some(summary) = summary
+                && !summary.activity.is_empty()
             {
                 display_spans.push(Span::raw(" "));
-                display_spans.push(render_mini_sparkline(data, theme));
+                display_spans.push(render_mini_sparkline(&summary.activity, theme));
             }
 
             // add unread count if > 0
@@ -562,8 +756,12 @@ fn draw_feeds(f: &mut Frame, area: Rect, app: &mut AppImpl) {
             ListItem::new(Line::from(display_spans))
         })
         .collect();
+    let feed_heights = item_heights(&feeds);
 
-    let default_title = String::from("Feeds");
+    let default_title = match app.feed_sort {
+        crate::modes::FeedSort::Title => String::from("Feeds"),
+        feed_sort => format!("Feeds - by {}", feed_sort.name()),
+    };
 
     // if there's a flash message, split the area to show it separately
     if app.flash.is_some() {
@@ -611,6 +809,7 @@ fn draw_feeds(f: &mut Frame, area: Rect, app: &mut AppImpl) {
             _ => feeds,
         };
 
+        app.click_targets.feeds = Some(ListTarget::new(chunks[1], feed_heights));
         f.render_stateful_widget(feeds, chunks[1], &mut app.feeds.state);
     } else {
         // no flash message, show feeds list normally
@@ -640,6 +839,7 @@ fn draw_feeds(f: &mut Frame, area: Rect, app: &mut AppImpl) {
             _ => feeds,
         };
 
+        app.click_targets.feeds = Some(ListTarget::new(area, feed_heights));
         f.render_stateful_widget(feeds, area, &mut app.feeds.state);
     }
 }
@@ -676,7 +876,7 @@ fn draw_feed_info(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         text.push('\n');
     }
 
-    if let Some(item) = app.entries.items.first()
+    if let Some(item) = app.entries.get(0)
         && let Some(pub_date) = &item.pub_date
     {
         text.push_str("Most recent entry at: ");
@@ -701,7 +901,7 @@ fn draw_feed_info(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         ReadMode::ShowRead => text.push_str("Read entries: "),
         ReadMode::All => text.push_str("All entries: "),
     }
-    text.push_str(app.entries.items.len().to_string().as_str());
+    text.push_str(app.entries.len().to_string().as_str());
     text.push('\n');
 
     if let Some(feed_kind) = app.current_feed.as_ref().map(|feed| feed.feed_kind) {
@@ -710,6 +910,19 @@ fn draw_feed_info(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         text.push('\n');
     }
 
+    if let Some(parse_warnings) = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| feed.parse_warnings.as_ref())
+    {
+        text.push_str("Warnings:\n");
+        for warning in parse_warnings.lines() {
+            text.push_str("- ");
+            text.push_str(&sanitize_for_display(warning));
+            text.push('\n');
+        }
+    }
+
     let theme = get_theme(app);
     let block = Block::default()
         .borders(Borders::ALL)
@@ -735,6 +948,88 @@ fn draw_feed_info(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     f.render_widget(paragraph, area);
 }
 
+/// the refresh history of the current feed, with the full error chain of the last failure
+fn draw_diagnostics(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let mut text = String::new();
+
+    if let Some(error) = app
+        .current_feed
+        .as_ref()
+        .and_then(|feed| app.feed_errors.get(&feed.id))
+    {
+        text.push_str("Last error:\n");
+        // errors from this session have their causes in the chain,
+        // errors from the refresh log have them one per line
+        let causes = error
+            .chain()
+            .flat_map(|cause| {
+                cause
+                    .to_string()
+                    .lines()
+                    .map(|line| line.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for (i, cause) in causes.iter().enumerate() {
+            text.push_str(&format!("  {i}: {}\n", sanitize_for_display(cause)));
+        }
+        text.push('\n');
+    }
+
+    if app.refresh_log.is_empty() {
+        text.push_str("No refreshes recorded yet. Press 'r' to refresh this feed.\n");
+    } else {
+        text.push_str("Recent refreshes:\n");
+    }
+
+    for attempt in &app.refresh_log {
+        let status = attempt
+            .http_status
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let bytes = attempt
+            .bytes
+            .map(|bytes| format!("{bytes}B"))
+            .unwrap_or_else(|| "-".to_string());
+        let outcome = match &attempt.error {
+            Some(error) => format!("ERROR {}", error.lines().next().unwrap_or_default()),
+            None => format!("{} new", attempt.new_entries),
+        };
+        text.push_str(&format!(
+            "{}  {:>3}  {:>9}  {:>6}ms  {}\n",
+            attempt.attempted_at.format("%Y-%m-%d %H:%M:%S"),
+            status,
+            bytes,
+            attempt.duration.as_millis(),
+            sanitize_for_display(&outcome)
+        ));
+    }
+
+    let theme = get_theme(app);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_color()))
+        .style(Style::default().bg(theme.background_color()))
+        .title(Span::styled(
+            "Diagnostics",
+            Style::default()
+                .fg(theme.title_color())
+                .bg(theme.background_color())
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let paragraph = Paragraph::new(Text::from(text.as_str()))
+        .block(block)
+        .style(
+            Style::default()
+                .fg(theme.text_color())
+                .bg(theme.background_color()),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
 /// format one keybinding as vim-style "[ key ] action"
 fn cmd(key: &str, action: &str) -> String {
     format!("[ {} ] {}", key, action)
@@ -752,12 +1047,37 @@ fn command_bar_line(app: &AppImpl) -> String {
             parts.push(cmd("o", "open"));
             if app.mode == Mode::Normal {
                 parts.push(cmd("d", "del"));
+                parts.push(cmd("D", "diag"));
+                parts.push(cmd("H", "health"));
+                parts.push(cmd("T", "trash"));
+                parts.push(cmd("M", "mark read"));
+                parts.push(cmd("s", "sort"));
+                if app.feed_sort == crate::modes::FeedSort::Manual {
+                    parts.push(cmd("J/K", "move"));
+                }
                 parts.push(cmd("E", "opml"));
                 parts.push(cmd("e/i", "edit"));
             }
         }
+        Selected::Health => {
+            parts.push(cmd("space", "mark"));
+            parts.push(cmd("s", "sort"));
+            parts.push(cmd("l", "go to feed"));
+            parts.push(cmd("d", "unsub"));
+            parts.push(cmd("E", "export unhealthy"));
+        }
+        Selected::Trash => {
+            parts.push(cmd("r", "restore"));
+            parts.push(cmd("d", "purge"));
+            parts.push(cmd("h", "back"));
+        }
+        Selected::Profiles => {
+            parts.push(cmd("enter", "switch"));
+            parts.push(cmd("h", "back"));
+        }
         Selected::Entry(_) | Selected::Entries | Selected::CombinedUnread => {
             parts.push(cmd("r", "read"));
+            parts.push(cmd("s", "sort"));
             parts.push(cmd("a", "tabs"));
             parts.push(cmd("c", "copy"));
             parts.push(cmd("o", "open"));
@@ -779,9 +1099,11 @@ fn command_bar_line(app: &AppImpl) -> String {
     match app.mode {
         Mode::Normal => {
             parts.push(cmd("1/2/3", "tabs"));
+            parts.push(cmd("u", "undo"));
+            parts.push(cmd("L", "layout"));
             parts.push(cmd("i", "edit"));
             parts.push(cmd("q", "quit"));
-            if app.pending_deletion.is_some() {
+            if app.pending_deletion.is_some() || app.pending_bulk_deletion {
                 parts.push(cmd("d", "confirm"));
                 parts.push(cmd("n", "cancel"));
             }
@@ -806,7 +1128,11 @@ fn command_bar_line(app: &AppImpl) -> String {
 fn draw_version_line(f: &mut Frame, area: Rect, app: &AppImpl) {
     let theme = get_theme(app);
     let version = env!("CARGO_PKG_VERSION");
-    let version_text = format!("rss-tui v.{}", version);
+    let version_text = if app.profile() == crate::config::DEFAULT_PROFILE {
+        format!("rss-tui v.{}", version)
+    } else {
+        format!("[{}] rss-tui v.{}", app.profile(), version)
+    };
     let dim = Style::default()
         .fg(theme.version_line_color())
         .bg(theme.background_color());
@@ -816,6 +1142,11 @@ fn draw_version_line(f: &mut Frame, area: Rect, app: &AppImpl) {
 }
 
 fn draw_command_bar(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    if let Some(progress) = &app.refresh_progress {
+        draw_refresh_progress(f, area, app, progress);
+        return;
+    }
+
     let theme = get_theme(app);
     let line = command_bar_line(app);
     let bar = Paragraph::new(Text::from(line.as_str()))
@@ -829,11 +1160,62 @@ fn draw_command_bar(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     f.render_widget(bar, area);
 }
 
+/// takes the place of the command bar while feeds are refreshing
+fn draw_refresh_progress(
+    f: &mut Frame,
+    area: Rect,
+    app: &AppImpl,
+    progress: &crate::io::RefreshProgress,
+) {
+    let theme = get_theme(app);
+
+    let label = if progress.cancelling {
+        format!(
+            "Cancelling, {}/{} - waiting for the feeds being fetched",
+            progress.done, progress.total
+        )
+    } else {
+        let current = progress
+            .current
+            .and_then(|feed_id| app.feeds.items.iter().find(|feed| feed.id == feed_id))
+            .and_then(|feed| feed.title.as_deref())
+            .map(|title| format!(": {}", sanitize_for_display(title)))
+            .unwrap_or_default();
+        format!(
+            "Refreshing {}/{}{current} - [ X ] cancel",
+            progress.done, progress.total
+        )
+    };
+
+    let gauge = Gauge::default()
+        .gauge_style(
+            Style::default()
+                .fg(theme.highlight_color())
+                .bg(theme.border_color()),
+        )
+        .label(Span::styled(
+            label,
+            Style::default()
+                .fg(theme.command_bar_text_color())
+                .add_modifier(Modifier::BOLD),
+        ))
+        .ratio(progress.done as f64 / progress.total.max(1) as f64)
+        .use_unicode(true);
+    f.render_widget(gauge, area);
+}
+
 fn draw_help(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     let mut text = String::new();
     match app.selected {
         Selected::Feeds => {
-            text.push_str("r - refresh selected feed; x - refresh all feeds\n");
+            text.push_str("r - refresh selected feed; x - refresh all feeds; X - cancel\n");
+            text.push_str("D - show/hide refresh history and errors\n");
+            text.push_str("H - feed health (erroring, dead and stale feeds)\n");
+            text.push_str("T - trash (restore or purge deleted feeds)\n");
+            text.push_str("P - profiles (switch to another database)\n");
+            text.push_str("M - mark every entry of the feed read\n");
+            text.push_str("s - sort feeds (title/unread/last entry/last refresh/manual)\n");
+            text.push_str("J/K - move feed down/up (manual sort)\n");
             text.push_str("A - combined unread (all feeds in one list)\n");
             text.push_str("c - copy link; o - open link in browser\n");
             if app.mode == Mode::Normal {
@@ -842,9 +1224,26 @@ fn draw_help(f: &mut Frame, area: Rect, app: &mut AppImpl) {
                 text.push_str("e/i - edit mode\n");
             }
         }
+        Selected::Health => {
+            text.push_str("feed health: every feed, worst first\n");
+            text.push_str("space - mark feed; s - cycle sort\n");
+            text.push_str("l/enter - go to feed; h - back to feeds\n");
+            text.push_str("d - unsubscribe marked (or selected) feeds\n");
+            text.push_str("E - export unhealthy feeds to OPML\n");
+        }
+        Selected::Trash => {
+            text.push_str("trash: deleted feeds, with their entries and read state\n");
+            text.push_str("r/enter - restore feed; h - back to feeds\n");
+            text.push_str("d - delete permanently (with confirmation)\n");
+        }
+        Selected::Profiles => {
+            text.push_str("profiles: the [profiles.<name>] sections of config.toml\n");
+            text.push_str("l/enter - switch to profile; h - back to feeds\n");
+        }
         Selected::CombinedUnread => {
             text.push_str("combined view: all unread entries from every feed\n");
             text.push_str("r - mark entry read; a - cycle tabs\n");
+            text.push_str("s - sort (newest/oldest/title/feed)\n");
             text.push_str("c - copy link; o - open link in browser\n");
             if app.mode == Mode::Normal {
                 text.push_str("e - email article; E - export OPML\n");
@@ -852,6 +1251,7 @@ fn draw_help(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         }
         Selected::Entry(_) => {
             text.push_str("r - mark entry read/un; a - cycle tabs\n");
+            text.push_str("s - sort this feed's entries\n");
             text.push_str("c - copy link; o - open link in browser\n");
             if app.mode == Mode::Normal {
                 text.push_str("e - email article (title as subject, URL as body)\n");
@@ -860,6 +1260,7 @@ fn draw_help(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         }
         _ => {
             text.push_str("r - mark entry read/un; a - cycle tabs\n");
+            text.push_str("s - sort this feed's entries\n");
             text.push_str("c - copy link; o - open link in browser\n");
             if app.mode == Mode::Normal {
                 text.push_str("E - export feeds to OPML\n");
@@ -869,8 +1270,11 @@ fn draw_help(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     match app.mode {
         Mode::Normal => {
             text.push_str("1/2/3 - Unread/All/Read tabs\n");
+            text.push_str("u - undo delete, mark read or rename\n");
+            text.push_str("L - layout (two pane/three pane/stacked/zen)\n");
+            text.push_str("</> - resize feeds; [/] - resize entries (three pane)\n");
             text.push_str("i - edit mode; q - exit\n");
-            if app.pending_deletion.is_some() {
+            if app.pending_deletion.is_some() || app.pending_bulk_deletion {
                 text.push_str("d - confirm deletion; n - cancel\n");
             }
         }
@@ -944,9 +1348,23 @@ fn draw_new_feed_input(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     f.render_widget(input, area);
 }
 
-fn draw_tabs(f: &mut Frame, area: Rect, app: &AppImpl) {
+fn draw_tabs(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     let theme = get_theme(app);
     let titles = vec![" Unread ", " All ", " Read "];
+
+    // Tabs pads every title with a space on each side and puts a divider between them
+    let mut x = area.x;
+    for (title, read_mode) in
+        titles
+            .iter()
+            .zip([ReadMode::ShowUnread, ReadMode::All, ReadMode::ShowRead])
+    {
+        let width = (title.width() as u16 + 2).min(area.right().saturating_sub(x));
+        app.click_targets
+            .tabs
+            .push((Rect::new(x, area.y, width, 1), read_mode));
+        x = x.saturating_add(width + 1);
+    }
     let selected_idx = match app.read_mode {
         ReadMode::ShowUnread => 0,
         ReadMode::All => 1,
@@ -995,100 +1413,108 @@ fn draw_entries(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         1
     };
 
-    let entries = app
-        .entries
-        .items
-        .iter()
-        .map(|entry| {
-            let mut spans = Vec::new();
+    let (list_area, error_area) = split_error_area(entries_area, app);
 
-            // read/unread indicator
-            if entry.read_at.is_none() {
-                spans.push(Span::styled(
-                    symbols.unread_entry,
-                    Style::default().fg(theme.unread_entry_color()),
-                ));
-            } else {
-                spans.push(Span::styled(
-                    symbols.read_entry,
-                    Style::default().fg(theme.read_entry_color()),
-                ));
-            }
+    let entry_item = |entry: &EntryMetadata| {
+        let mut spans = Vec::new();
 
-            let title_text =
-                sanitize_for_display(entry.title.as_ref().map_or("No title", |t| t.as_str()));
+        // read/unread indicator
+        if entry.read_at.is_none() {
+            spans.push(Span::styled(
+                symbols.unread_entry,
+                Style::default().fg(theme.unread_entry_color()),
+            ));
+        } else {
+            spans.push(Span::styled(
+                symbols.read_entry,
+                Style::default().fg(theme.read_entry_color()),
+            ));
+        }
 
-            // recency indicator (new entries <24h old)
-            let is_new = if let Some(pub_date) = &entry.pub_date {
-                let now = Utc::now();
-                let age = now - *pub_date;
-                age.num_hours() < 24
-            } else {
-                false
-            };
+        let title_text =
+            sanitize_for_display(entry.title.as_ref().map_or("No title", |t| t.as_str()));
 
-            if is_new {
-                // wrap the title text to fit the available width
-                let wrapped_lines = wrap_text(title_text.as_str(), available_width);
-
-                // create a list item with multiple lines if needed
-                if wrapped_lines.len() == 1 {
-                    spans.push(Span::raw(wrapped_lines[0].clone()));
-                    spans.push(Span::styled(
-                        format!(" {}", symbols.new_entry),
-                        Style::default().fg(theme.new_entry_color()),
-                    ));
-                    ListItem::new(Line::from(spans))
-                } else {
-                    // create multiple lines for multi-line items
-                    let mut lines: Vec<Line> = Vec::new();
-                    for (i, line) in wrapped_lines.iter().enumerate() {
-                        if i == 0 {
-                            let mut first_line_spans = spans.clone();
-                            first_line_spans.push(Span::raw(line.clone()));
-                            first_line_spans.push(Span::styled(
-                                format!(" {}", symbols.new_entry),
-                                Style::default().fg(theme.new_entry_color()),
-                            ));
-                            lines.push(Line::from(first_line_spans));
-                        } else {
-                            lines.push(Line::from(Span::raw(line.clone())));
-                        }
+        // recency indicator (new entries <24h old)
+        let is_new = if let Some(pub_date) = &entry.pub_date {
+            let now = Utc::now();
+            let age = now - *pub_date;
+            age.num_hours() < 24
+        } else {
+            false
+        };
+
+        if is_new {
+            // wrap the title text to fit the available width
+            let wrapped_lines = wrap_text(title_text.as_str(), available_width);
+
+            // create a list item with multiple lines if needed
+            if wrapped_lines.len() == 1 {
+                spans.push(Span::raw(wrapped_lines[0].clone()));
+                spans.push(Span::styled(
+                    format!(" {}", symbols.new_entry),
+                    Style::default().fg(theme.new_entry_color()),
+                ));
+                ListItem::new(Line::from(spans))
+            } else {
+                // create multiple lines for multi-line items
+                let mut lines: Vec<Line> = Vec::new();
+                for (i, line) in wrapped_lines.iter().enumerate() {
+                    if i == 0 {
+                        let mut first_line_spans = spans.clone();
+                        first_line_spans.push(Span::raw(line.clone()));
+                        first_line_spans.push(Span::styled(
+                            format!(" {}", symbols.new_entry),
+                            Style::default().fg(theme.new_entry_color()),
+                        ));
+                        lines.push(Line::from(first_line_spans));
+                    } else {
+                        lines.push(Line::from(Span::raw(line.clone())));
                     }
-                    ListItem::new(Text::from(lines))
                 }
-            } else {
-                // wrap the title text to fit the available width
-                let wrapped_lines = wrap_text(title_text.as_str(), available_width);
+                ListItem::new(Text::from(lines))
+            }
+        } else {
+            // wrap the title text to fit the available width
+            let wrapped_lines = wrap_text(title_text.as_str(), available_width);
 
-                // create a list item with multiple lines if needed
-                if wrapped_lines.len() == 1 {
-                    spans.push(Span::raw(wrapped_lines[0].clone()));
-                    ListItem::new(Line::from(spans))
-                } else {
-                    // create multiple lines for multi-line items
-                    let mut lines: Vec<Line> = Vec::new();
-                    for (i, line) in wrapped_lines.iter().enumerate() {
-                        if i == 0 {
-                            let mut first_line_spans = spans.clone();
-                            first_line_spans.push(Span::raw(line.clone()));
-                            lines.push(Line::from(first_line_spans));
-                        } else {
-                            lines.push(Line::from(Span::raw(line.clone())));
-                        }
+            // create a list item with multiple lines if needed
+            if wrapped_lines.len() == 1 {
+                spans.push(Span::raw(wrapped_lines[0].clone()));
+                ListItem::new(Line::from(spans))
+            } else {
+                // create multiple lines for multi-line items
+                let mut lines: Vec<Line> = Vec::new();
+                for (i, line) in wrapped_lines.iter().enumerate() {
+                    if i == 0 {
+                        let mut first_line_spans = spans.clone();
+                        first_line_spans.push(Span::raw(line.clone()));
+                        lines.push(Line::from(first_line_spans));
+                    } else {
+                        lines.push(Line::from(Span::raw(line.clone())));
                     }
-                    ListItem::new(Text::from(lines))
                 }
+                ListItem::new(Text::from(lines))
             }
-        })
-        .collect::<Vec<ListItem>>();
+        }
+    };
 
-    let title = app
+    // only what fits is wrapped and drawn, as a feed can have tens of thousands of entries
+    let (first, visible) = app
+        .entries
+        .visible(list_height(list_area), |entry| entry_item(entry).height());
+    let entries = visible.iter().map(entry_item).collect::<Vec<ListItem>>();
+    let entry_heights = item_heights(&entries);
+    let mut list_state = visible_list_state(&app.entries, first);
+
+    let mut title = app
         .current_feed
         .as_ref()
         .and_then(|feed| feed.title.as_ref())
         .map(|t| sanitize_for_display(t.as_str()))
         .unwrap_or_else(|| "Entries".to_string());
+    if app.entry_sort != crate::modes::EntrySort::NewestFirst {
+        title.push_str(&format!(" - {}", app.entry_sort.name()));
+    }
 
     let entries_titles = List::new(entries).block(
         Block::default()
@@ -1116,40 +1542,10 @@ fn draw_entries(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         _ => entries_titles,
     };
 
-    if !&app.error_flash.is_empty() {
-        let error_chunks = Layout::default()
-            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
-            .direction(Direction::Vertical)
-            .split(entries_area);
-
-        let error_text = error_text(&app.error_flash);
-
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.border_color()))
-            .style(Style::default().bg(theme.background_color()))
-            .title(Span::styled(
-                "Error - press 'q' to close",
-                Style::default()
-                    .fg(theme.title_color())
-                    .bg(theme.background_color())
-                    .add_modifier(Modifier::BOLD),
-            ));
-
-        let error_widget = Paragraph::new(error_text)
-            .block(block)
-            .style(
-                Style::default()
-                    .fg(theme.error_color())
-                    .bg(theme.background_color()),
-            )
-            .wrap(Wrap { trim: false })
-            .scroll((0, 0));
-
-        f.render_stateful_widget(entries_titles, error_chunks[0], &mut app.entries.state);
-        f.render_widget(error_widget, error_chunks[1]);
-    } else {
-        f.render_stateful_widget(entries_titles, entries_area, &mut app.entries.state);
+    app.click_targets.entries = Some(ListTarget::new(list_area, entry_heights).starting_at(first));
+    f.render_stateful_widget(entries_titles, list_area, &mut list_state);
+    if let Some(error_area) = error_area {
+        draw_list_error(f, error_area, app);
     }
 }
 
@@ -1171,47 +1567,59 @@ fn draw_combined_entries(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         1
     };
 
-    let entries: Vec<ListItem> = app
-        .combined_entries
-        .items
-        .iter()
-        .map(|(feed_name, entry)| {
-            let mut spans = Vec::new();
-            spans.push(Span::styled(
-                symbols.unread_entry,
-                Style::default().fg(theme.unread_entry_color()),
-            ));
-            let line_prefix = format!("[{}]: ", sanitize_for_display(feed_name.as_str()));
-            let title_text =
-                sanitize_for_display(entry.title.as_ref().map_or("No title", |t| t.as_str()));
-            let full_text = format!("{}{}", line_prefix, title_text);
-            let wrapped_lines = wrap_text(&full_text, available_width);
-            if wrapped_lines.len() == 1 {
-                spans.push(Span::raw(wrapped_lines[0].clone()));
-                ListItem::new(Line::from(spans))
-            } else {
-                let mut lines: Vec<Line> = Vec::new();
-                for (i, line) in wrapped_lines.iter().enumerate() {
-                    if i == 0 {
-                        let mut first_line_spans = spans.clone();
-                        first_line_spans.push(Span::raw(line.clone()));
-                        lines.push(Line::from(first_line_spans));
-                    } else {
-                        lines.push(Line::from(Span::raw(line.clone())));
-                    }
+    let (list_area, error_area) = split_error_area(entries_area, app);
+
+    let entry_item = |(feed_name, entry): &(String, EntryMetadata)| {
+        let mut spans = Vec::new();
+        spans.push(Span::styled(
+            symbols.unread_entry,
+            Style::default().fg(theme.unread_entry_color()),
+        ));
+        let line_prefix = format!("[{}]: ", sanitize_for_display(feed_name.as_str()));
+        let title_text =
+            sanitize_for_display(entry.title.as_ref().map_or("No title", |t| t.as_str()));
+        let full_text = format!("{}{}", line_prefix, title_text);
+        let wrapped_lines = wrap_text(&full_text, available_width);
+        if wrapped_lines.len() == 1 {
+            spans.push(Span::raw(wrapped_lines[0].clone()));
+            ListItem::new(Line::from(spans))
+        } else {
+            let mut lines: Vec<Line> = Vec::new();
+            for (i, line) in wrapped_lines.iter().enumerate() {
+                if i == 0 {
+                    let mut first_line_spans = spans.clone();
+                    first_line_spans.push(Span::raw(line.clone()));
+                    lines.push(Line::from(first_line_spans));
+                } else {
+                    lines.push(Line::from(Span::raw(line.clone())));
                 }
-                ListItem::new(Text::from(lines))
             }
-        })
-        .collect();
+            ListItem::new(Text::from(lines))
+        }
+    };
 
+    let (first, visible) = app
+        .combined_entries
+        .visible(list_height(list_area), |entry| entry_item(entry).height());
+    let entries = visible.iter().map(entry_item).collect::<Vec<ListItem>>();
+    let entry_heights = item_heights(&entries);
+    let mut list_state = visible_list_state(&app.combined_entries, first);
     let list = List::new(entries).block(
         Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(theme.border_color()))
             .style(Style::default().bg(theme.background_color()))
             .title(Span::styled(
-                format!("All unread [{}]", app.combined_entries.items.len()),
+                match app.combined_sort {
+                    crate::modes::EntrySort::NewestFirst => {
+                        format!("All unread [{}]", app.combined_entries.len())
+                    }
+                    combined_sort => format!(
+                        "All unread [{}] - {}",
+                        app.combined_entries.len(),
+                        combined_sort.name()
+                    ),
+                },
                 Style::default()
                     .fg(theme.title_color())
                     .bg(theme.background_color())
@@ -1231,39 +1639,288 @@ fn draw_combined_entries(f: &mut Frame, area: Rect, app: &mut AppImpl) {
         _ => list,
     };
 
-    if !app.error_flash.is_empty() {
-        let error_chunks = Layout::default()
-            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
-            .direction(Direction::Vertical)
-            .split(entries_area);
-        let error_text = error_text(&app.error_flash);
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.border_color()))
-            .style(Style::default().bg(theme.background_color()))
-            .title(Span::styled(
-                "Error - press 'q' to close",
-                Style::default()
-                    .fg(theme.title_color())
-                    .bg(theme.background_color())
-                    .add_modifier(Modifier::BOLD),
-            ));
-        let error_widget = Paragraph::new(error_text)
-            .block(block)
-            .style(
-                Style::default()
-                    .fg(theme.error_color())
-                    .bg(theme.background_color()),
-            )
-            .wrap(Wrap { trim: false })
-            .scroll((0, 0));
-        f.render_stateful_widget(list, error_chunks[0], &mut app.combined_entries.state);
-        f.render_widget(error_widget, error_chunks[1]);
-    } else {
-        f.render_stateful_widget(list, entries_area, &mut app.combined_entries.state);
+    app.click_targets.combined_entries =
+        Some(ListTarget::new(list_area, entry_heights).starting_at(first));
+    f.render_stateful_widget(list, list_area, &mut list_state);
+    if let Some(error_area) = error_area {
+        draw_list_error(f, error_area, app);
     }
 }
 
+/// the list gets the top of `area` while there are errors to show below it
+fn split_error_area(area: Rect, app: &AppImpl) -> (Rect, Option<Rect>) {
+    if app.error_flash.is_empty() {
+        return (area, None);
+    }
+
+    let error_chunks = Layout::default()
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+        .direction(Direction::Vertical)
+        .split(area);
+    (error_chunks[0], Some(error_chunks[1]))
+}
+
+fn draw_list_error(f: &mut Frame, area: Rect, app: &AppImpl) {
+    let theme = get_theme(app);
+    let error_text = error_text(&app.error_flash);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_color()))
+        .style(Style::default().bg(theme.background_color()))
+        .title(Span::styled(
+            "Error - press 'q' to close",
+            Style::default()
+                .fg(theme.title_color())
+                .bg(theme.background_color())
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let error_widget = Paragraph::new(error_text)
+        .block(block)
+        .style(
+            Style::default()
+                .fg(theme.error_color())
+                .bg(theme.background_color()),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((0, 0));
+
+    f.render_widget(error_widget, area);
+}
+
+/// the rows inside a list's borders
+fn list_height(area: Rect) -> usize {
+    area.height.saturating_sub(2).into()
+}
+
+/// the selection of a paged list, for drawing only its visible items from `first` on
+fn visible_list_state<T>(list: &crate::util::PagedList<T>, first: usize) -> ListState {
+    ListState::default().with_selected(list.state.selected().and_then(|idx| idx.checked_sub(first)))
+}
+
+fn draw_feed_health(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let theme = get_theme(app);
+
+    let rows: Vec<ListItem> = app
+        .feed_health
+        .items
+        .iter()
+        .map(|health| {
+            let status = health.status();
+            let status_color = match status {
+                crate::rss::FeedHealthStatus::Healthy => theme.text_color(),
+                crate::rss::FeedHealthStatus::Stale => theme.feed_type_badge_color(),
+                crate::rss::FeedHealthStatus::Dead | crate::rss::FeedHealthStatus::Erroring => {
+                    theme.error_color()
+                }
+            };
+            let mark = if app.health_marked.contains(&health.feed.id) {
+                "[x] "
+            } else {
+                "[ ] "
+            };
+            let last_entry = health
+                .days_since_last_entry()
+                .map(|days| format!("{days}d"))
+                .unwrap_or_else(|| "never".to_string());
+            let title = sanitize_for_display(
+                health
+                    .feed
+                    .title
+                    .as_deref()
+                    .or(health.feed.feed_link.as_deref())
+                    .unwrap_or("No title"),
+            );
+
+            ListItem::new(Line::from(vec![
+                Span::raw(mark),
+                Span::styled(format!("{:<8}", status), Style::default().fg(status_color)),
+                Span::raw(format!(
+                    " {:>3} err  {:>6}  {:>5.1}/mo  ",
+                    health.consecutive_errors, last_entry, health.entries_per_month
+                )),
+                Span::raw(title),
+            ]))
+        })
+        .collect();
+
+    let unhealthy = app
+        .feed_health
+        .items
+        .iter()
+        .filter(|health| health.status() != crate::rss::FeedHealthStatus::Healthy)
+        .count();
+    let mut title = format!(
+        "Feed health [{unhealthy}/{} unhealthy] - sorted by {}",
+        app.feed_health.items.len(),
+        app.health_sort.name()
+    );
+    if !app.health_marked.is_empty() {
+        title.push_str(&format!(" - {} marked", app.health_marked.len()));
+    }
+
+    let row_heights = item_heights(&rows);
+    let list = List::new(rows)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_color()))
+                .style(Style::default().bg(theme.background_color()))
+                .title(Span::styled(
+                    title,
+                    Style::default()
+                        .fg(theme.title_color())
+                        .bg(theme.background_color())
+                        .add_modifier(Modifier::BOLD),
+                )),
+        )
+        .style(
+            Style::default()
+                .fg(theme.text_color())
+                .bg(theme.background_color()),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight_color())
+                .bg(theme.background_color())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    app.click_targets.feed_health = Some(ListTarget::new(area, row_heights));
+    f.render_stateful_widget(list, area, &mut app.feed_health.state);
+}
+
+fn draw_trash(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let theme = get_theme(app);
+
+    let rows: Vec<ListItem> = app
+        .trash
+        .items
+        .iter()
+        .map(|trashed| {
+            let title = sanitize_for_display(
+                trashed
+                    .feed
+                    .title
+                    .as_deref()
+                    .or(trashed.feed.feed_link.as_deref())
+                    .unwrap_or("No title"),
+            );
+            let deleted_days_ago = (chrono::Utc::now() - trashed.deleted_at).num_days();
+
+            ListItem::new(Line::from(vec![
+                Span::raw(title),
+                Span::styled(
+                    format!(
+                        "  deleted {}d ago, {} entries ({} read), purged in {}d",
+                        deleted_days_ago,
+                        trashed.entries,
+                        trashed.read_entries,
+                        trashed.days_until_purge(app.trash_retention_days())
+                    ),
+                    Style::default().fg(theme.feed_type_badge_color()),
+                ),
+            ]))
+        })
+        .collect();
+
+    let title = format!(
+        "Trash [{}] - kept for {} days",
+        app.trash.items.len(),
+        app.trash_retention_days()
+    );
+
+    let row_heights = item_heights(&rows);
+    let list = List::new(rows)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_color()))
+                .style(Style::default().bg(theme.background_color()))
+                .title(Span::styled(
+                    title,
+                    Style::default()
+                        .fg(theme.title_color())
+                        .bg(theme.background_color())
+                        .add_modifier(Modifier::BOLD),
+                )),
+        )
+        .style(
+            Style::default()
+                .fg(theme.text_color())
+                .bg(theme.background_color()),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight_color())
+                .bg(theme.background_color())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    app.click_targets.trash = Some(ListTarget::new(area, row_heights));
+    f.render_stateful_widget(list, area, &mut app.trash.state);
+}
+
+fn draw_profiles(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let theme = get_theme(app);
+
+    let rows: Vec<ListItem> = app
+        .profiles
+        .items
+        .iter()
+        .map(|profile| {
+            let mut spans = vec![Span::raw(sanitize_for_display(profile))];
+            if profile == app.profile() {
+                spans.push(Span::styled(
+                    "  current",
+                    Style::default().fg(theme.feed_type_badge_color()),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = format!(
+        "Profiles [{}] - {}",
+        app.profiles.items.len(),
+        app.database_path.display()
+    );
+
+    let row_heights = item_heights(&rows);
+    let list = List::new(rows)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_color()))
+                .style(Style::default().bg(theme.background_color()))
+                .title(Span::styled(
+                    title,
+                    Style::default()
+                        .fg(theme.title_color())
+                        .bg(theme.background_color())
+                        .add_modifier(Modifier::BOLD),
+                )),
+        )
+        .style(
+            Style::default()
+                .fg(theme.text_color())
+                .bg(theme.background_color()),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.highlight_color())
+                .bg(theme.background_color())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    app.click_targets.profiles = Some(ListTarget::new(area, row_heights));
+    f.render_stateful_widget(list, area, &mut app.profiles.state);
+}
+
 fn draw_entry(f: &mut Frame, area: Rect, app: &mut AppImpl) {
     // Split area for tabs and entry content
     let main_chunks = Layout::default()
@@ -1370,16 +2027,54 @@ fn draw_entry(f: &mut Frame, area: Rect, app: &mut AppImpl) {
             .scroll((0, 0));
 
         // Render paragraph and scrollbar in top chunk
+        let article = chunks[0].inner(Margin::new(1, 1));
         f.render_widget(paragraph, chunks[0]);
+        app.click_targets.article = Some(article);
+        app.click_targets.article_rows = screen_rows(f, article);
         f.render_stateful_widget(scrollbar, chunks[0], &mut scrollbar_state);
         f.render_widget(error_widget, chunks[1]);
     } else {
         // Render paragraph with scrollbar overlay
+        let article = content_area.inner(Margin::new(1, 1));
         f.render_widget(paragraph, content_area);
+        app.click_targets.article = Some(article);
+        app.click_targets.article_rows = screen_rows(f, article);
         f.render_stateful_widget(scrollbar, content_area, &mut scrollbar_state);
     }
 }
 
+/// the highlighted entry, read-only, next to the entries list
+fn draw_preview(f: &mut Frame, area: Rect, app: &mut AppImpl) {
+    let theme = get_theme(app);
+    let title = app
+        .preview_entry()
+        .map(|entry| sanitize_for_display(entry.title.as_deref().unwrap_or("No entry title")))
+        .unwrap_or_else(|| "Preview".to_string());
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_color()))
+        .style(Style::default().bg(theme.background_color()))
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(theme.title_color())
+                .bg(theme.background_color())
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    let paragraph = Paragraph::new(app.preview_text.as_str())
+        .block(block)
+        .style(
+            Style::default()
+                .fg(theme.text_color())
+                .bg(theme.background_color()),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
 fn error_text(errors: &[anyhow::Error]) -> String {
     errors
         .iter()
@@ -1394,3 +2089,95 @@ fn error_text(errors: &[anyhow::Error]) -> String {
         .collect::<Vec<String>>()
         .join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 100,
+        height: 40,
+    };
+
+    #[test]
+    fn panes_follow_the_layout() {
+        let ratios = PaneRatios::default();
+
+        let two_pane = split_panes(
+            AREA,
+            1,
+            PaneLayout::TwoPane,
+            ratios,
+            &Selected::Entries,
+            false,
+        );
+        assert_eq!(two_pane.feeds.unwrap().width, 30);
+        assert_eq!(two_pane.article().width, 70);
+        assert!(two_pane.preview.is_none());
+
+        let three_pane = split_panes(
+            AREA,
+            1,
+            PaneLayout::ThreePane,
+            ratios,
+            &Selected::Entries,
+            false,
+        );
+        assert_eq!(three_pane.main.width, 28);
+        assert_eq!(three_pane.article(), three_pane.preview.unwrap());
+        assert_eq!(three_pane.article().width, 42);
+
+        // dashboards don't get a preview
+        let health = split_panes(
+            AREA,
+            1,
+            PaneLayout::ThreePane,
+            ratios,
+            &Selected::Health,
+            false,
+        );
+        assert!(health.preview.is_none());
+
+        let stacked = split_panes(
+            AREA,
+            1,
+            PaneLayout::Stacked,
+            ratios,
+            &Selected::Entries,
+            false,
+        );
+        assert_eq!(stacked.feeds.unwrap().width, 100);
+        assert_eq!(stacked.article().width, 100);
+        assert!(stacked.feeds.unwrap().y < stacked.main.y);
+
+        let zen_feeds = split_panes(AREA, 1, PaneLayout::Zen, ratios, &Selected::Feeds, false);
+        assert!(zen_feeds.feeds.is_some());
+    }
+
+    #[test]
+    fn clicks_map_to_list_items() {
+        // a bordered list at the top left, the second item wraps onto two lines
+        let target = ListTarget::new(Rect::new(0, 0, 20, 10), vec![1, 2, 1]);
+
+        assert_eq!(target.item_at(Position::new(5, 0), 0), None);
+        assert_eq!(target.item_at(Position::new(5, 1), 0), Some(0));
+        assert_eq!(target.item_at(Position::new(5, 3), 0), Some(1));
+        assert_eq!(target.item_at(Position::new(5, 4), 0), Some(2));
+        assert_eq!(target.item_at(Position::new(5, 5), 0), None);
+        assert_eq!(target.item_at(Position::new(5, 1), 1), Some(1));
+        assert_eq!(target.item_at(Position::new(19, 1), 0), None);
+    }
+
+    #[test]
+    fn pane_ratios_are_clamped() {
+        let mut ratios = PaneRatios::default();
+        for _ in 0..20 {
+            ratios.resize_feeds(-PaneRatios::STEP);
+            ratios.resize_entries(PaneRatios::STEP);
+        }
+        assert_eq!(ratios.feeds, 10);
+        assert_eq!(ratios.entries, 80);
+    }
+}
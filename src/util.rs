@@ -83,6 +83,232 @@ impl<T> From<Vec<T>> for StatefulList<T> {
     }
 }
 
+/// How many items a `PagedList` loads at a time
+const PAGE_SIZE: usize = 256;
+/// How many items around the selection a `PagedList` keeps loaded, more than fit on any screen
+const PAGE_MARGIN: usize = 100;
+/// How many items a `PagedList` holds before dropping the ones far from the selection
+const MAX_LOADED: usize = 4 * PAGE_SIZE;
+
+/// Which items of a `PagedList` to load, in list order
+#[derive(Debug, PartialEq)]
+pub enum PageRequest<K> {
+    /// `limit` items from `offset` on, for jumps
+    At { offset: usize, limit: usize },
+    /// the `limit` items right after `K`
+    After(K, usize),
+    /// the `limit` items right before `K`
+    Before(K, usize),
+}
+
+impl<K> PageRequest<K> {
+    pub fn map<L>(self, f: impl FnOnce(K) -> L) -> PageRequest<L> {
+        match self {
+            PageRequest::At { offset, limit } => PageRequest::At { offset, limit },
+            PageRequest::After(key, limit) => PageRequest::After(f(key), limit),
+            PageRequest::Before(key, limit) => PageRequest::Before(f(key), limit),
+        }
+    }
+}
+
+/// A list too long to keep in memory, like `StatefulList` but with only the items
+/// around the selection loaded. `state` and every index are positions in the whole list,
+/// and the items are loaded with a `PageRequest` as the selection moves
+#[derive(Debug)]
+pub struct PagedList<T> {
+    pub state: ListState,
+    len: usize,
+    /// the index of the first loaded item
+    start: usize,
+    items: Vec<T>,
+}
+
+impl<T> Default for PagedList<T> {
+    fn default() -> Self {
+        PagedList::new(0)
+    }
+}
+
+impl<T> PagedList<T> {
+    /// a list of `len` items, none of them loaded yet
+    pub fn new(len: usize) -> PagedList<T> {
+        PagedList {
+            state: ListState::default(),
+            len,
+            start: 0,
+            items: vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// the item at `idx`, if it is loaded
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        idx.checked_sub(self.start)
+            .and_then(|idx| self.items.get(idx))
+    }
+
+    pub fn selected_item(&self) -> Option<&T> {
+        self.state.selected().and_then(|idx| self.get(idx))
+    }
+
+    /// what `StatefulList::next` would select
+    pub fn next_index(&self) -> Option<usize> {
+        match self.state.selected() {
+            _ if self.is_empty() => None,
+            Some(i) if i + 1 < self.len => Some(i + 1),
+            _ => Some(0),
+        }
+    }
+
+    /// what `StatefulList::previous` would select
+    pub fn previous_index(&self) -> Option<usize> {
+        match self.state.selected() {
+            _ if self.is_empty() => None,
+            Some(0) => Some(self.len - 1),
+            Some(i) => Some(i - 1),
+            None => Some(0),
+        }
+    }
+
+    pub fn unselect(&mut self) {
+        self.state.select(None);
+    }
+
+    /// selects `idx`, loading the items around it that are not loaded yet
+    pub fn select(
+        &mut self,
+        idx: Option<usize>,
+        load: impl FnMut(PageRequest<&T>) -> anyhow::Result<Vec<T>>,
+    ) -> anyhow::Result<()> {
+        self.state.select(idx);
+        match idx {
+            Some(idx) if idx < self.len => self.load_around(idx, load),
+            _ => Ok(()),
+        }
+    }
+
+    fn load_around(
+        &mut self,
+        idx: usize,
+        mut load: impl FnMut(PageRequest<&T>) -> anyhow::Result<Vec<T>>,
+    ) -> anyhow::Result<()> {
+        let wanted_start = idx.saturating_sub(PAGE_MARGIN);
+        let wanted_end = (idx + PAGE_MARGIN + 1).min(self.len);
+        let loaded_end = self.start + self.items.len();
+
+        if self.items.is_empty() || wanted_end <= self.start || loaded_end <= wanted_start {
+            return self.jump_to(idx, load);
+        }
+
+        if wanted_start < self.start {
+            let limit = PAGE_SIZE.min(self.start);
+            let mut before = load(PageRequest::Before(&self.items[0], limit))?;
+            // the list changed since it was loaded, so the indexes no longer line up
+            if before.len() != limit {
+                return self.jump_to(idx, load);
+            }
+            before.append(&mut self.items);
+            self.items = before;
+            self.start -= limit;
+        }
+
+        let loaded_end = self.start + self.items.len();
+        if loaded_end < wanted_end {
+            let limit = PAGE_SIZE.min(self.len - loaded_end);
+            let after = load(PageRequest::After(&self.items[self.items.len() - 1], limit))?;
+            if after.len() != limit {
+                return self.jump_to(idx, load);
+            }
+            self.items.extend(after);
+        }
+
+        if self.items.len() > MAX_LOADED {
+            let keep_from = idx
+                .saturating_sub(MAX_LOADED / 2)
+                .clamp(self.start, self.start + self.items.len() - MAX_LOADED);
+            self.items.drain(..keep_from - self.start);
+            self.items.truncate(MAX_LOADED);
+            self.start = keep_from;
+        }
+
+        Ok(())
+    }
+
+    /// throws away what is loaded and loads the page around `idx`
+    fn jump_to(
+        &mut self,
+        idx: usize,
+        mut load: impl FnMut(PageRequest<&T>) -> anyhow::Result<Vec<T>>,
+    ) -> anyhow::Result<()> {
+        let offset = idx
+            .saturating_sub(PAGE_SIZE / 2)
+            .min(self.len.saturating_sub(PAGE_SIZE));
+        let limit = PAGE_SIZE.min(self.len - offset);
+        self.items = load(PageRequest::At { offset, limit })?;
+        self.start = offset;
+
+        // items were deleted since the list was counted
+        if self.items.len() < limit {
+            self.len = self.start + self.items.len();
+            if self
+                .state
+                .selected()
+                .is_some_and(|selected| selected >= self.len)
+            {
+                self.state.select(self.len.checked_sub(1));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// scrolls so the selection is shown in `height` rows, the way a `List` does,
+    /// and returns the loaded items that fit from the top, with the index of the first one
+    pub fn visible(&mut self, height: usize, item_height: impl Fn(&T) -> usize) -> (usize, &[T]) {
+        let height_at = |idx: usize| self.get(idx).map_or(1, &item_height);
+        let loaded_end = self.start + self.items.len();
+
+        let mut top = self
+            .state
+            .offset()
+            .clamp(self.start, loaded_end.max(self.start));
+        if let Some(selected) = self
+            .state
+            .selected()
+            .filter(|idx| (self.start..loaded_end).contains(idx))
+        {
+            if selected < top {
+                top = selected;
+            } else {
+                let mut first = selected;
+                let mut used = height_at(selected);
+                while first > top && used + height_at(first - 1) <= height {
+                    first -= 1;
+                    used += height_at(first);
+                }
+                top = first;
+            }
+        }
+
+        let mut end = top;
+        let mut used = 0;
+        while end < loaded_end && used + height_at(end) <= height {
+            used += height_at(end);
+            end += 1;
+        }
+
+        *self.state.offset_mut() = top;
+        (top, &self.items[top - self.start..end - self.start])
+    }
+}
+
 // work around for clipboard access in WSL
 #[cfg(target_os = "linux")]
 pub(crate) fn set_wsl_clipboard_contents(s: &str) -> anyhow::Result<()> {
@@ -104,3 +330,157 @@ pub(crate) fn set_wsl_clipboard_contents(s: &str) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// The http(s) url under `column` of `rows[row]`, where `rows` is text as shown on screen.
+/// Urls too long for one row are wrapped mid-word, so they are followed onto the rows around them.
+pub fn link_at(rows: &[String], row: usize, column: usize) -> Option<String> {
+    let chars: Vec<char> = rows.get(row)?.chars().collect();
+    if chars.get(column)?.is_whitespace() {
+        return None;
+    }
+
+    let start = chars[..column]
+        .iter()
+        .rposition(|c| c.is_whitespace())
+        .map_or(0, |i| i + 1);
+    let end = chars[column..]
+        .iter()
+        .position(|c| c.is_whitespace())
+        .map_or(chars.len(), |i| column + i);
+    let mut word: String = chars[start..end].iter().collect();
+
+    let mut previous = row;
+    let mut at_row_start = start == 0;
+    while at_row_start && previous > 0 {
+        previous -= 1;
+        let chars: Vec<char> = rows[previous].chars().collect();
+        if chars.last().is_none_or(|c| c.is_whitespace()) {
+            break;
+        }
+        let start = chars
+            .iter()
+            .rposition(|c| c.is_whitespace())
+            .map_or(0, |i| i + 1);
+        word.insert_str(0, &chars[start..].iter().collect::<String>());
+        at_row_start = start == 0;
+    }
+
+    let mut next = row;
+    let mut at_row_end = end == chars.len();
+    while at_row_end && next + 1 < rows.len() {
+        next += 1;
+        let chars: Vec<char> = rows[next].chars().collect();
+        let end = chars
+            .iter()
+            .position(|c| c.is_whitespace())
+            .unwrap_or(chars.len());
+        word.extend(&chars[..end]);
+        at_row_end = end == chars.len();
+    }
+
+    let url_start = word.find("https://").or_else(|| word.find("http://"))?;
+    let url = word[url_start..].trim_end_matches(|c: char| ")]>.,;:'\"".contains(c));
+
+    Some(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// loads pages of `source` the way the entry queries do, counting the loads
+    fn load_from<'a>(
+        source: &'a [usize],
+        loads: &'a mut usize,
+    ) -> impl FnMut(PageRequest<&usize>) -> anyhow::Result<Vec<usize>> + 'a {
+        move |request| {
+            *loads += 1;
+            let position = |key: &usize| source.iter().position(|item| item == key).unwrap();
+            let (from, limit) = match request {
+                PageRequest::At { offset, limit } => (offset, limit),
+                PageRequest::After(key, limit) => (position(key) + 1, limit),
+                PageRequest::Before(key, limit) => {
+                    let end = position(key);
+                    (end.saturating_sub(limit), limit.min(end))
+                }
+            };
+            Ok(source.iter().skip(from).take(limit).copied().collect())
+        }
+    }
+
+    #[test]
+    fn paged_lists_load_around_the_selection() {
+        let source: Vec<usize> = (0..5_000).map(|i| i * 10).collect();
+        let mut loads = 0;
+        let mut list = PagedList::new(source.len());
+
+        // wraps around like a StatefulList
+        assert_eq!(list.next_index(), Some(0));
+        assert_eq!(list.previous_index(), Some(0));
+        list.select(Some(0), load_from(&source, &mut loads))
+            .unwrap();
+        list.select(list.previous_index(), load_from(&source, &mut loads))
+            .unwrap();
+        assert_eq!(list.state.selected(), Some(4_999));
+        assert_eq!(list.selected_item(), Some(&49_990));
+        list.select(list.next_index(), load_from(&source, &mut loads))
+            .unwrap();
+        assert_eq!(list.selected_item(), Some(&0));
+
+        // moving one at a time only loads a page now and then
+        let loads_before = loads;
+        for idx in 1..2_000 {
+            list.select(list.next_index(), load_from(&source, &mut loads))
+                .unwrap();
+            assert_eq!(list.selected_item(), Some(&source[idx]));
+            assert!(list.get(idx + PAGE_MARGIN).is_some());
+            assert!(list.items.len() <= MAX_LOADED);
+        }
+        assert!(loads - loads_before < 2_000 / PAGE_SIZE + 2);
+        for idx in (1_000..1_999).rev() {
+            list.select(list.previous_index(), load_from(&source, &mut loads))
+                .unwrap();
+            assert_eq!(list.selected_item(), Some(&source[idx]));
+            assert!(list.get(idx - PAGE_MARGIN).is_some());
+        }
+
+        // only what fits is handed out for drawing, scrolled to the selection
+        let (first, visible) = list.visible(10, |_| 1);
+        assert_eq!(first, 991);
+        assert_eq!(visible, &source[991..1_001]);
+        list.select(Some(1_020), load_from(&source, &mut loads))
+            .unwrap();
+        let (first, visible) = list.visible(10, |item| if *item == 10_200 { 2 } else { 1 });
+        assert_eq!(first, 1_012);
+        assert_eq!(visible.len(), 9);
+
+        // entries deleted since the list was counted
+        let mut list = PagedList::new(source.len() + 20);
+        list.select(Some(source.len() + 10), load_from(&source, &mut loads))
+            .unwrap();
+        assert_eq!(list.len(), source.len());
+        assert_eq!(list.selected_item(), source.last());
+    }
+
+    #[test]
+    fn finds_links_under_the_cursor() {
+        let rows = vec![
+            "see [1]                   ".to_string(),
+            "[1]: https://example.com/a".to_string(),
+            "/long/path). more text    ".to_string(),
+        ];
+
+        assert_eq!(link_at(&rows, 0, 1), None);
+        assert_eq!(link_at(&rows, 0, 10), None);
+        assert_eq!(
+            link_at(&rows, 1, 10).as_deref(),
+            Some("https://example.com/a/long/path")
+        );
+        // clicking the wrapped part finds the start of the url
+        assert_eq!(
+            link_at(&rows, 2, 2).as_deref(),
+            Some("https://example.com/a/long/path")
+        );
+        assert_eq!(link_at(&rows, 2, 14), None);
+    }
+}